@@ -53,6 +53,146 @@ impl Config {
         env::var("MAIL_FROM").unwrap_or_else(|_| "Mento Services <noreply@mentoservices.com>".to_string())
     }
 
+    pub fn payment_gateway() -> String {
+        env::var("PAYMENT_GATEWAY").unwrap_or_else(|_| "razorpay".to_string())
+    }
+
+    pub fn scheduler_interval_secs() -> u64 {
+        env::var("SCHEDULER_INTERVAL_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .unwrap_or(300)
+    }
+
+    pub fn renewal_lead_days() -> i64 {
+        env::var("RENEWAL_LEAD_DAYS")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()
+            .unwrap_or(3)
+    }
+
+    pub fn renewal_retry_hours() -> i64 {
+        env::var("RENEWAL_RETRY_HOURS")
+            .unwrap_or_else(|_| "24".to_string())
+            .parse()
+            .unwrap_or(24)
+    }
+
+    pub fn renewal_max_attempts() -> i32 {
+        env::var("RENEWAL_MAX_ATTEMPTS")
+            .unwrap_or_else(|_| "4".to_string())
+            .parse()
+            .unwrap_or(4)
+    }
+
+    pub fn renewal_grace_days() -> i64 {
+        env::var("RENEWAL_GRACE_DAYS")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()
+            .unwrap_or(3)
+    }
+
+    pub fn payment_link_expiry_hours() -> i64 {
+        env::var("PAYMENT_LINK_EXPIRY_HOURS")
+            .unwrap_or_else(|_| "72".to_string())
+            .parse()
+            .unwrap_or(72)
+    }
+
+    /// Head start each higher worker plan gets on new service requests
+    pub fn service_request_early_access_minutes() -> i64 {
+        env::var("SERVICE_REQUEST_EARLY_ACCESS_MINUTES")
+            .unwrap_or_else(|_| "15".to_string())
+            .parse()
+            .unwrap_or(15)
+    }
+
+    /// Days after a booking is completed during which the customer can review it
+    pub fn review_window_days() -> i64 {
+        env::var("REVIEW_WINDOW_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .unwrap_or(30)
+    }
+
+    /// Default for categories without a cancellation policy: customer cancellations
+    /// within this many hours of the start are late
+    pub fn cancellation_window_hours() -> i64 {
+        env::var("CANCELLATION_WINDOW_HOURS")
+            .unwrap_or_else(|_| "4".to_string())
+            .parse()
+            .unwrap_or(4)
+    }
+
+    /// Default window for workers cancelling an accepted booking
+    pub fn worker_cancellation_window_hours() -> i64 {
+        env::var("WORKER_CANCELLATION_WINDOW_HOURS")
+            .unwrap_or_else(|_| "12".to_string())
+            .parse()
+            .unwrap_or(12)
+    }
+
+    /// Default wait after a booking's start before a no-show can be reported
+    pub fn no_show_grace_minutes() -> i64 {
+        env::var("NO_SHOW_GRACE_MINUTES")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .unwrap_or(30)
+    }
+
+    /// Hours a customer has to confirm or contest a completed job before it
+    /// confirms itself
+    pub fn completion_confirm_hours() -> i64 {
+        env::var("COMPLETION_CONFIRM_HOURS")
+            .unwrap_or_else(|_| "48".to_string())
+            .parse()
+            .unwrap_or(48)
+    }
+
+    /// Hours support has to first respond to a new dispute
+    pub fn dispute_response_hours() -> i64 {
+        env::var("DISPUTE_RESPONSE_HOURS")
+            .unwrap_or_else(|_| "24".to_string())
+            .parse()
+            .unwrap_or(24)
+    }
+
+    /// Hours from filing within which a dispute should be resolved
+    pub fn dispute_resolution_hours() -> i64 {
+        env::var("DISPUTE_RESOLUTION_HOURS")
+            .unwrap_or_else(|_| "72".to_string())
+            .parse()
+            .unwrap_or(72)
+    }
+
+    /// Days an approved job post stays public before it expires
+    pub fn job_post_validity_days() -> i64 {
+        env::var("JOB_POST_VALIDITY_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .unwrap_or(30)
+    }
+
+    /// Days before expiry the owner is reminded to renew a job post
+    pub fn job_expiry_reminder_days() -> i64 {
+        env::var("JOB_EXPIRY_REMINDER_DAYS")
+            .unwrap_or_else(|_| "3".to_string())
+            .parse()
+            .unwrap_or(3)
+    }
+
+    /// How long before a scheduled interview both parties are reminded
+    pub fn interview_reminder_hours() -> i64 {
+        env::var("INTERVIEW_REMINDER_HOURS")
+            .unwrap_or_else(|_| "24".to_string())
+            .parse()
+            .unwrap_or(24)
+    }
+
+    pub fn app_base_url() -> String {
+        env::var("APP_BASE_URL").unwrap_or_else(|_| "http://localhost:3000".to_string())
+    }
+
     pub fn is_development() -> bool {
         env::var("ROCKET_ENV").unwrap_or_default() == "development"
     }
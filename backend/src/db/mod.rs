@@ -1,6 +1,14 @@
-use mongodb::{Client, Database};
+use mongodb::bson::{doc, Document};
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::IndexOptions;
+use mongodb::{Client, Database, IndexModel};
 use rocket::{Rocket, Build};
 use rocket::fairing::AdHoc;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::models::LOCATION_RETENTION_SECS;
+use crate::utils::time::DAY_MILLIS;
 
 pub fn init() -> AdHoc {
     AdHoc::on_ignite("MongoDB", |rocket| async {
@@ -24,10 +32,357 @@ async fn connect() -> Result<Database, mongodb::error::Error> {
     // Test connection
     client
         .database("admin")
-        .run_command(mongodb::bson::doc! {"ping": 1}, None)
+        .run_command(doc! {"ping": 1}, None)
         .await?;
     
-    Ok(client.database("mento-services"))
+    let database = client.database("mento-services");
+    if let Err(e) = ensure_indexes(&database).await {
+        warn!("Failed to create indexes: {}", e);
+    }
+    if let Err(e) = backfill(&database).await {
+        warn!("Failed to backfill fields: {}", e);
+    }
+
+    Ok(database)
 }
 
-pub type DbConn = Database;
\ No newline at end of file
+/// Indexes the application relies on for correctness (uniqueness), created on startup
+async fn ensure_indexes(db: &Database) -> Result<(), mongodb::error::Error> {
+    db.collection::<Document>("entitlement_usage")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "user_id": 1, "feature": 1, "period": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("contact_reveals")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "user_id": 1, "target_user_id": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("payment_links")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "gateway_link_id": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("quotes")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "quote_number": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("service_offers")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "request_id": 1, "worker_id": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("conversations")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "customer_id": 1, "worker_user_id": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("messages")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "conversation_id": 1, "_id": -1 })
+                .build(),
+            None,
+        )
+        .await?;
+
+    // Live positions expire on their own; closed bookings are cleared right away
+    db.collection::<Document>("booking_locations")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "recorded_at": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .expire_after(Duration::from_secs(LOCATION_RETENTION_SECS))
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("booking_locations")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "booking_id": 1, "recorded_at": -1 })
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("cancellation_policies")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "category": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("disputes")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "status": 1, "resolution_due_at": 1 })
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("audit_logs")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "entity_type": 1, "entity_id": 1, "created_at": -1 })
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("applications")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "job_id": 1, "seeker_profile_id": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("applications")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "seeker_user_id": 1, "applied_at": -1 })
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("interviews")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "status": 1, "scheduled_slot.start": 1 })
+                .build(),
+            None,
+        )
+        .await?;
+
+    // Expiry sweep over live job posts (stored camelCase)
+    db.collection::<Document>("jobs")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "status": 1, "expiresAt": 1 })
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("saved_searches")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "unsubscribe_token": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("saved_searches")
+        .create_index(IndexModel::builder().keys(doc! { "user_id": 1 }).build(), None)
+        .await?;
+
+    // New-job scan for saved search alerts
+    db.collection::<Document>("jobs")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "status": 1, "approvedAt": 1 })
+                .build(),
+            None,
+        )
+        .await?;
+
+    // Radius search over job posts that have coordinates
+    db.collection::<Document>("jobs")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "geoLocation": "2dsphere" })
+                .build(),
+            None,
+        )
+        .await?;
+
+    // Text search; each collection can only have one text index
+    db.collection::<Document>("jobs")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {
+                    "title": "text",
+                    "jobRole": "text",
+                    "companyName": "text",
+                    "requirements": "text",
+                    "companyBrief": "text"
+                })
+                .options(
+                    IndexOptions::builder()
+                        .name("jobs_text".to_string())
+                        .weights(doc! { "title": 10, "jobRole": 6, "companyName": 4, "requirements": 2 })
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("worker_profiles")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {
+                    "categories": "text",
+                    "subcategories": "text",
+                    "service_areas": "text",
+                    "description": "text"
+                })
+                .options(
+                    IndexOptions::builder()
+                        .name("worker_profiles_text".to_string())
+                        .weights(doc! { "categories": 6, "subcategories": 6, "service_areas": 3 })
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await?;
+
+    db.collection::<Document>("services")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {
+                    "name": "text",
+                    "serviceCategory": "text",
+                    "service_category": "text",
+                    "description": "text"
+                })
+                .options(
+                    IndexOptions::builder()
+                        .name("services_text".to_string())
+                        .weights(doc! { "name": 10, "serviceCategory": 4, "service_category": 4 })
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await?;
+
+    // One review per booking; older reviews have no booking
+    db.collection::<Document>("reviews")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "booking_id": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .partial_filter_expression(doc! { "booking_id": { "$type": "objectId" } })
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await?;
+
+    // One review per contact reveal
+    db.collection::<Document>("reviews")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "reveal_id": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .unique(true)
+                        .partial_filter_expression(doc! { "reveal_id": { "$type": "objectId" } })
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Give documents written before a field existed its default, so queries on it match
+async fn backfill(db: &Database) -> Result<(), mongodb::error::Error> {
+    // Reviews from before bookings were required stay, flagged as unverified
+    db.collection::<Document>("reviews")
+        .update_many(
+            doc! { "is_verified": { "$exists": false } },
+            doc! { "$set": { "is_verified": false } },
+            None,
+        )
+        .await?;
+
+    // Job posts (stored camelCase) from before the typed lifecycle: "active" meant
+    // public, and public posts had no expiry
+    let jobs = db.collection::<Document>("jobs");
+    jobs.update_many(
+        doc! { "status": "active" },
+        doc! { "$set": { "status": "approved" } },
+        None,
+    )
+    .await?;
+    jobs.update_many(
+        doc! { "status": "approved", "expiresAt": null },
+        vec![doc! {
+            "$set": {
+                "expiresAt": {
+                    "$add": [
+                        { "$ifNull": ["$approvedAt", "$createdAt"] },
+                        Config::job_post_validity_days() * DAY_MILLIS
+                    ]
+                }
+            }
+        }],
+        None,
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub type DbConn = Database;
+
+/// Whether a write failed on a unique index
+pub fn is_duplicate_key(e: &mongodb::error::Error) -> bool {
+    matches!(
+        *e.kind,
+        ErrorKind::Write(WriteFailure::WriteError(ref w)) if w.code == 11000
+    )
+}
\ No newline at end of file
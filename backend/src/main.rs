@@ -99,7 +99,9 @@ fn rocket() -> Rocket<Build> {
 
     rocket::build()
         .attach(db::init())
+        .attach(services::scheduler::init())
         .attach(CORS)
+        .manage(services::EventHub::new())
         .mount("/", routes![options_handler])
         .mount(
             "/api/v1",
@@ -126,6 +128,72 @@ fn rocket() -> Rocket<Build> {
                 routes::worker::create_subscription,
                 routes::worker::verify_subscription_payment,
                 routes::worker::get_subscription_status,
+                // Subscription Plan Changes
+                routes::subscription::change_worker_plan,
+                routes::subscription::verify_worker_plan_change,
+                routes::subscription::cancel_worker_scheduled_change,
+                routes::subscription::get_worker_plan_history,
+                routes::subscription::update_worker_auto_renew,
+                routes::subscription::confirm_worker_auto_renew,
+                routes::subscription::change_job_seeker_plan,
+                routes::subscription::verify_job_seeker_plan_change,
+                routes::subscription::cancel_job_seeker_scheduled_change,
+                routes::subscription::get_job_seeker_plan_history,
+                routes::subscription::update_job_seeker_auto_renew,
+                routes::subscription::confirm_job_seeker_auto_renew,
+                routes::subscription::get_plans,
+                routes::subscription::get_entitlements,
+                routes::subscription::start_worker_trial,
+                routes::subscription::start_job_seeker_trial,
+                // Bookings
+                routes::booking::create_booking,
+                routes::booking::list_bookings,
+                routes::booking::get_booking,
+                routes::booking::accept_booking,
+                routes::booking::decline_booking,
+                routes::booking::start_booking,
+                routes::booking::complete_booking,
+                routes::booking::cancel_booking,
+                routes::booking::get_cancellation_policy,
+                routes::booking::report_no_show,
+                routes::booking::confirm_completion,
+                routes::booking::contest_completion,
+                // Live Tracking
+                routes::tracking::post_booking_location,
+                routes::tracking::get_booking_location,
+                routes::tracking::booking_location_stream,
+                // Quotes
+                routes::quote::create_quote,
+                routes::quote::update_quote,
+                routes::quote::send_quote,
+                routes::quote::list_quotes,
+                routes::quote::get_quote,
+                routes::quote::accept_quote,
+                routes::quote::reject_quote,
+                routes::quote::download_quote_pdf,
+                // Service Requests
+                routes::service_request::create_service_request,
+                routes::service_request::list_my_service_requests,
+                routes::service_request::list_open_service_requests,
+                routes::service_request::get_service_request,
+                routes::service_request::cancel_service_request,
+                routes::service_request::create_service_offer,
+                routes::service_request::withdraw_service_offer,
+                routes::service_request::accept_service_offer,
+                // Messaging
+                routes::message::start_conversation,
+                routes::message::list_conversations,
+                routes::message::get_messages,
+                routes::message::send_message,
+                routes::message::mark_conversation_read,
+                routes::message::reveal_contact,
+                routes::message::message_stream,
+                // Disputes
+                routes::dispute::create_dispute,
+                routes::dispute::list_my_disputes,
+                routes::dispute::get_dispute,
+                routes::dispute::add_dispute_evidence,
+                routes::dispute::post_dispute_message,
                 // Worker
                 routes::worker::create_worker_profile,
                 routes::worker::get_worker_profile,
@@ -134,6 +202,9 @@ fn rocket() -> Rocket<Build> {
                 routes::worker::search_workers,
                 routes::worker::find_nearby_workers,
                 routes::worker::update_worker_location,
+                routes::worker::update_worker_availability,
+                routes::worker::get_worker_availability,
+                routes::worker::get_worker_slots,
                 // Categories
                 routes::category::get_all_categories,
                 routes::category::get_subcategories,
@@ -149,6 +220,7 @@ fn rocket() -> Rocket<Build> {
                 routes::file_upload::upload_document_base64,
                 // Reviews
                 routes::review::create_review,
+                routes::review::get_pending_reviews,
                 routes::review::get_worker_reviews,
                 routes::review::delete_review,
                 // Job Seeker Subscription
@@ -162,12 +234,46 @@ fn rocket() -> Rocket<Build> {
                 routes::job::update_job_seeker_profile,
                 routes::job::search_job_seekers,
                 routes::job::delete_job_seeker_profile,
+                routes::job::parse_resume,
+                routes::job::generate_resume,
                 // Job Posts (User)
                 routes::job::create_job_post,
                 routes::job::get_my_jobs,
                 routes::job::get_public_jobs,
                 routes::job::get_job_by_id,
+                routes::job::reveal_job_contact,
                 routes::job::upload_job_document,
+                routes::job::update_job_post,
+                routes::job::close_job_post,
+                routes::job::reopen_job_post,
+                routes::job::renew_job_post,
+                routes::job::get_recommended_jobs,
+                routes::job::get_job_candidates,
+                // Job Applications
+                routes::application::apply_to_job,
+                routes::application::list_my_applications,
+                routes::application::get_my_application,
+                routes::application::withdraw_application,
+                routes::application::list_applicants,
+                routes::application::get_applicant,
+                routes::application::update_applicant_status,
+                routes::application::add_applicant_note,
+                routes::application::rate_applicant,
+                routes::application::update_pipeline,
+                // Interviews
+                routes::interview::propose_interview,
+                routes::interview::list_interviews,
+                routes::interview::get_interview,
+                routes::interview::select_interview_slot,
+                routes::interview::reschedule_interview,
+                routes::interview::cancel_interview,
+                // Saved Searches
+                routes::saved_search::create_saved_search,
+                routes::saved_search::list_saved_searches,
+                routes::saved_search::update_saved_search,
+                routes::saved_search::delete_saved_search,
+                routes::saved_search::run_saved_search,
+                routes::saved_search::unsubscribe_saved_search,
                 // Admin Routes - Workers
                 routes::admin::get_all_workers,
                 routes::admin::verify_worker,
@@ -201,6 +307,19 @@ fn rocket() -> Rocket<Build> {
                 routes::admin::create_subscription,
                 routes::admin::update_subscription,
                 routes::admin::delete_subscription,
+                // Admin Routes - Analytics
+                routes::admin::get_analytics_summary,
+                routes::admin::get_revenue_analytics,
+                routes::admin::get_revenue_breakdown,
+                routes::admin::get_subscriber_analytics,
+                // Admin Routes - Payment Links
+                routes::payment_link::create_payment_link,
+                routes::payment_link::get_payment_links,
+                routes::payment_link::get_payment_link,
+                routes::payment_link::resend_payment_link,
+                routes::payment_link::cancel_payment_link,
+                // Payment Gateway Webhooks
+                routes::payment_link::razorpay_webhook,
                 // Admin Routes - Services (CRUD)
                 routes::admin::get_all_services_admin,
                 routes::admin::create_service_admin,
@@ -208,6 +327,18 @@ fn rocket() -> Rocket<Build> {
                 routes::admin::delete_service_admin,
                 // Admin Routes - Razorpay
                 routes::admin::get_razorpay_payment,
+                // Admin Routes - Cancellation Policies
+                routes::booking::list_cancellation_policies,
+                routes::booking::upsert_cancellation_policy,
+                routes::booking::delete_cancellation_policy,
+                // Admin Routes - Disputes
+                routes::dispute::admin_list_disputes,
+                routes::dispute::admin_get_dispute,
+                routes::dispute::assign_dispute,
+                routes::dispute::admin_post_dispute_message,
+                routes::dispute::resolve_dispute,
+                routes::dispute::reject_dispute,
+                routes::dispute::get_audit_logs,
                 // Notification Routes
                 routes::notification::get_notifications,
                 routes::notification::mark_notification_read,
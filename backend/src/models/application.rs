@@ -0,0 +1,186 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+use super::JobSeekerProfile;
+
+/// Hiring pipeline: applied → the job's stages (shortlisted → interview → offered
+/// by default) → hired, with rejection possible at any open stage
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ApplicationStatus {
+    Applied,
+    Shortlisted,
+    Interview,
+    Offered,
+    Hired,
+    Rejected,
+    Withdrawn, // By the seeker
+}
+
+impl ApplicationStatus {
+    /// Value stored in the `status` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApplicationStatus::Applied => "applied",
+            ApplicationStatus::Shortlisted => "shortlisted",
+            ApplicationStatus::Interview => "interview",
+            ApplicationStatus::Offered => "offered",
+            ApplicationStatus::Hired => "hired",
+            ApplicationStatus::Rejected => "rejected",
+            ApplicationStatus::Withdrawn => "withdrawn",
+        }
+    }
+
+    /// Whether a poster can make this one of a job's pipeline stages
+    pub fn is_pipeline_stage(&self) -> bool {
+        matches!(
+            self,
+            ApplicationStatus::Shortlisted | ApplicationStatus::Interview | ApplicationStatus::Offered
+        )
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(
+            self,
+            ApplicationStatus::Hired | ApplicationStatus::Rejected | ApplicationStatus::Withdrawn
+        )
+    }
+
+    /// Moves a poster may make along a job's `pipeline` (its stages between applied
+    /// and hired, in order): forward, possibly skipping stages, or rejecting from any
+    /// open stage. Only applicants at the last stage can be hired.
+    pub fn can_move_to(&self, next: ApplicationStatus, pipeline: &[ApplicationStatus]) -> bool {
+        if self.is_closed() {
+            return false;
+        }
+        let position = |status: ApplicationStatus| match status {
+            ApplicationStatus::Applied => Some(0),
+            _ => pipeline.iter().position(|s| *s == status).map(|i| i + 1),
+        };
+        match next {
+            ApplicationStatus::Rejected => true,
+            ApplicationStatus::Hired => *self == pipeline.last().copied().unwrap_or(ApplicationStatus::Applied),
+            _ => matches!((position(*self), position(next)), (Some(from), Some(to)) if to > from),
+        }
+    }
+}
+
+/// Stages a job uses until its poster sets its own
+pub const DEFAULT_PIPELINE: [ApplicationStatus; 3] = [
+    ApplicationStatus::Shortlisted,
+    ApplicationStatus::Interview,
+    ApplicationStatus::Offered,
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ApplicationStatusChange {
+    pub status: ApplicationStatus,
+    pub note: Option<String>, // Shown to the applicant
+    #[schemars(skip)]
+    pub changed_at: DateTime,
+}
+
+/// Poster's private note on an applicant
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct PosterNote {
+    pub body: String,
+    #[schemars(skip)]
+    pub created_at: DateTime,
+}
+
+/// The seeker's profile as it was when they applied, so later edits don't
+/// change what the poster received
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ResumeSnapshot {
+    pub full_name: String,
+    pub headline: Option<String>,
+    pub skills: Vec<String>,
+    pub experience_years: Option<i32>,
+    pub resume_url: Option<String>,
+    pub portfolio_url: Option<String>,
+    pub linkedin_url: Option<String>,
+}
+
+impl From<&JobSeekerProfile> for ResumeSnapshot {
+    fn from(profile: &JobSeekerProfile) -> Self {
+        ResumeSnapshot {
+            full_name: profile.full_name.clone(),
+            headline: profile.headline.clone(),
+            skills: profile.skills.clone(),
+            experience_years: profile.experience_years,
+            resume_url: profile.resume_url.clone(),
+            portfolio_url: profile.portfolio_url.clone(),
+            linkedin_url: profile.linkedin_url.clone(),
+        }
+    }
+}
+
+/// A job seeker's application to a job post (`applications` collection).
+/// Active applications are also listed in `JobPost.applications` and counted in
+/// `JobSeekerProfile.applications_count`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobApplication {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub job_id: ObjectId,
+    pub seeker_profile_id: ObjectId,
+    pub seeker_user_id: ObjectId,
+    pub cover_note: Option<String>,
+    pub resume: ResumeSnapshot,
+    pub status: ApplicationStatus,
+    #[serde(default)]
+    pub status_history: Vec<ApplicationStatusChange>,
+    // Private to the poster
+    #[serde(default)]
+    pub poster_notes: Vec<PosterNote>,
+    #[serde(default)]
+    pub poster_rating: Option<i32>, // 1-5
+    pub applied_at: DateTime,
+    pub withdrawn_at: Option<DateTime>,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ApplyJobDto {
+    pub cover_note: Option<String>,
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct ApplicationListQuery {
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct ApplicantListQuery {
+    pub status: Option<String>,
+    pub skill: Option<String>,
+    pub min_experience: Option<i32>,
+    pub min_rating: Option<i32>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateApplicationStatusDto {
+    pub status: ApplicationStatus,
+    pub note: Option<String>,
+}
+
+/// A job's stages between applied and hired, in order, e.g. `["interview", "offered"]`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdatePipelineDto {
+    pub stages: Vec<ApplicationStatus>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ApplicantNoteDto {
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RateApplicantDto {
+    pub rating: i32, // 1-5
+}
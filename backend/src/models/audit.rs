@@ -0,0 +1,27 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+/// Record of a consequential action (`audit_logs` collection). Entries are only
+/// ever inserted.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AuditLog {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub actor_type: String, // "admin" | "user" | "system"
+    pub actor_id: Option<ObjectId>,
+    pub action: String,      // e.g. "dispute.refund"
+    pub entity_type: String, // e.g. "dispute"
+    pub entity_id: Option<ObjectId>,
+    pub details: serde_json::Value,
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct AuditLogQuery {
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub action: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+pub const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Local time range, "HH:MM" to "HH:MM" (end exclusive)
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct TimeRange {
+    pub start: String,
+    pub end: String,
+}
+
+impl TimeRange {
+    fn new(start: &str, end: &str) -> Self {
+        TimeRange { start: start.to_string(), end: end.to_string() }
+    }
+
+    /// Start and end as minutes since midnight
+    pub fn minutes(&self) -> Option<(i32, i32)> {
+        Some((parse_hhmm(&self.start)?, parse_hhmm(&self.end)?))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct DaySchedule {
+    pub day: String, // "mon" .. "sun"
+    pub hours: Vec<TimeRange>,
+    #[serde(default)]
+    pub breaks: Vec<TimeRange>,
+}
+
+/// Weekly working calendar of a worker (embedded in `WorkerProfile.availability`)
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct WorkerAvailability {
+    pub weekly: Vec<DaySchedule>,
+    #[serde(default)]
+    pub holidays: Vec<String>, // "YYYY-MM-DD"
+    pub daily_capacity: Option<i32>, // Max bookings per day, None = no limit
+    pub slot_minutes: i32, // Spacing of bookable slot start times
+}
+
+impl WorkerAvailability {
+    /// Calendar assumed for workers who have not set one: Mon-Sat, 09:00-18:00
+    pub fn default_schedule() -> Self {
+        WorkerAvailability {
+            weekly: WEEKDAYS[..6]
+                .iter()
+                .map(|day| DaySchedule {
+                    day: day.to_string(),
+                    hours: vec![TimeRange::new("09:00", "18:00")],
+                    breaks: Vec::new(),
+                })
+                .collect(),
+            holidays: Vec::new(),
+            daily_capacity: None,
+            slot_minutes: 60,
+        }
+    }
+
+    pub fn day(&self, day: &str) -> Option<&DaySchedule> {
+        self.weekly.iter().find(|d| d.day == day)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(15..=240).contains(&self.slot_minutes) {
+            return Err("slot_minutes must be between 15 and 240".to_string());
+        }
+        if matches!(self.daily_capacity, Some(c) if c < 1) {
+            return Err("daily_capacity must be at least 1".to_string());
+        }
+
+        for (i, schedule) in self.weekly.iter().enumerate() {
+            if !WEEKDAYS.contains(&schedule.day.as_str()) {
+                return Err(format!("Invalid day '{}'. Use mon, tue, wed, thu, fri, sat or sun", schedule.day));
+            }
+            if self.weekly[..i].iter().any(|d| d.day == schedule.day) {
+                return Err(format!("Day '{}' is listed more than once", schedule.day));
+            }
+
+            for range in schedule.hours.iter().chain(schedule.breaks.iter()) {
+                match range.minutes() {
+                    Some((start, end)) if start < end => {}
+                    _ => {
+                        return Err(format!(
+                            "Invalid time range {}-{} on {}. Use HH:MM with start before end",
+                            range.start, range.end, schedule.day
+                        ));
+                    }
+                }
+            }
+        }
+
+        for holiday in &self.holidays {
+            if chrono::NaiveDate::parse_from_str(holiday, "%Y-%m-%d").is_err() {
+                return Err(format!("Invalid holiday '{}', expected YYYY-MM-DD", holiday));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// "HH:MM" to minutes since midnight; "24:00" is allowed as an end of day
+pub fn parse_hhmm(value: &str) -> Option<i32> {
+    let (h, m) = value.split_once(':')?;
+    if h.len() != 2 || m.len() != 2 {
+        return None;
+    }
+    let (h, m): (i32, i32) = (h.parse().ok()?, m.parse().ok()?);
+
+    match (h, m) {
+        (24, 0) => Some(24 * 60),
+        (0..=23, 0..=59) => Some(h * 60 + m),
+        _ => None,
+    }
+}
+
+/// Minutes since midnight to "HH:MM"
+pub fn format_hhmm(minutes: i32) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct SlotQuery {
+    pub from: String,       // YYYY-MM-DD
+    pub to: Option<String>, // YYYY-MM-DD inclusive, defaults to `from`
+    pub duration_minutes: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FreeSlot {
+    pub start: String, // RFC 3339, local time
+    pub end: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DaySlots {
+    pub date: String, // YYYY-MM-DD
+    pub is_holiday: bool,
+    pub bookings: i32,
+    pub fully_booked: bool,
+    pub slots: Vec<FreeSlot>,
+}
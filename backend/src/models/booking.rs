@@ -0,0 +1,183 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+use super::GeoLocation;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BookingStatus {
+    Requested, // Waiting for the worker to respond
+    Accepted,
+    Declined,
+    InProgress,
+    Completed,
+    Cancelled,
+    NoShow, // A party didn't turn up; see `no_show`
+}
+
+impl BookingStatus {
+    /// Value stored in the `status` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookingStatus::Requested => "requested",
+            BookingStatus::Accepted => "accepted",
+            BookingStatus::Declined => "declined",
+            BookingStatus::InProgress => "in_progress",
+            BookingStatus::Completed => "completed",
+            BookingStatus::Cancelled => "cancelled",
+            BookingStatus::NoShow => "no_show",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BookingStatusChange {
+    pub status: BookingStatus,
+    pub changed_by: ObjectId, // User who made the change
+    pub note: Option<String>,
+    pub at: DateTime,
+}
+
+/// No-show reported by the party who turned up
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NoShowReport {
+    pub absent_party: String, // "customer" | "worker"
+    pub reported_by: ObjectId,
+    pub note: Option<String>,
+    pub evidence: Vec<String>, // Uploaded file URLs
+    pub reported_at: DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionConfirmation {
+    Pending,       // Waiting for the customer
+    Confirmed,     // By the customer, or by support closing a contest in the worker's favour
+    AutoConfirmed, // Customer didn't respond in time
+    Contested,     // Customer opened a dispute
+}
+
+/// What the worker submitted when marking the job done, and the customer's sign-off
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CompletionProof {
+    pub before_photos: Vec<String>,
+    pub after_photos: Vec<String>,
+    pub note: String,
+    pub submitted_at: DateTime,
+    pub confirm_by: DateTime, // Auto-confirmed after this
+    pub confirmation: CompletionConfirmation,
+    pub confirmed_at: Option<DateTime>,
+    pub dispute_id: Option<ObjectId>, // When contested
+}
+
+/// A customer's booking of a worker (`bookings` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Booking {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub customer_id: ObjectId,    // User who booked
+    pub worker_id: ObjectId,      // Worker profile ID
+    pub worker_user_id: ObjectId, // User behind the worker profile
+    pub category: Option<String>,
+    pub subcategory: String,
+    pub address: String,
+    pub location: GeoLocation,
+    pub scheduled_at: DateTime,
+    pub duration_minutes: i32,
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote_id: Option<ObjectId>, // Accepted quote the booking was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_request_id: Option<ObjectId>, // Service request whose offer was accepted
+    pub status: BookingStatus,
+    pub status_history: Vec<BookingStatusChange>,
+    pub cancellation_reason: Option<String>,
+    pub cancelled_by: Option<ObjectId>,
+    #[serde(default)]
+    pub late_cancellation: bool, // Cancelled inside the category's cancellation window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_show: Option<NoShowReport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion: Option<CompletionProof>,
+    pub accepted_at: Option<DateTime>,
+    pub started_at: Option<DateTime>,
+    pub completed_at: Option<DateTime>,
+    pub cancelled_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateBookingDto {
+    pub worker_id: String,
+    pub category: Option<String>,
+    pub subcategory: String,
+    pub address: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub scheduled_at: String, // RFC 3339, e.g. "2025-03-14T10:30:00+05:30"
+    pub duration_minutes: Option<i32>, // Defaults to 60
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BookingReasonDto {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct BookingListQuery {
+    pub role: Option<String>, // "customer" (default) or "worker"
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Cancellation rules for a category (`cancellation_policies` collection).
+/// Categories without one use the configured defaults.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct CancellationPolicy {
+    pub category: String,
+    pub customer_window_hours: i64, // Customer cancellations closer to the start are late
+    pub worker_window_hours: i64,   // Same for workers cancelling accepted bookings
+    pub no_show_grace_minutes: i64, // Wait after the start before a no-show can be reported
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CancellationPolicyDto {
+    pub customer_window_hours: i64,
+    pub worker_window_hours: i64,
+    pub no_show_grace_minutes: i64,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CompleteBookingDto {
+    pub before_photos: Option<Vec<String>>, // URLs from /upload/image
+    pub after_photos: Vec<String>,          // At least one
+    pub note: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ContestCompletionDto {
+    pub reason: String,
+    pub evidence: Option<Vec<String>>, // URLs from the upload endpoints
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct NoShowReportDto {
+    pub note: Option<String>,
+    pub evidence: Option<Vec<String>>, // URLs from the upload endpoints
+}
+
+/// Booking track record behind a reliability score
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
+pub struct ReliabilityStats {
+    pub completed: i64,
+    pub late_cancellations: i64,
+    pub no_shows: i64,
+    pub score: f64, // 0–100
+    #[schemars(skip)]
+    pub updated_at: Option<DateTime>,
+}
@@ -0,0 +1,163 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeCategory {
+    ServiceQuality,
+    NoShow,
+    PropertyDamage,
+    Overcharge,
+    Behaviour,
+    Billing, // Subscription charges
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeStatus {
+    Open,      // Waiting for support
+    InReview,  // Assigned and being handled
+    Resolved,
+    Rejected,  // Closed without action
+}
+
+impl DisputeStatus {
+    /// Value stored in the `status` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisputeStatus::Open => "open",
+            DisputeStatus::InReview => "in_review",
+            DisputeStatus::Resolved => "resolved",
+            DisputeStatus::Rejected => "rejected",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionActionKind {
+    Refund,
+    Warning,          // Formal warning to the other party
+    WorkerSuspension, // Worker hidden from search and bookings for a period
+}
+
+/// One action taken when resolving a dispute, linked to its audit entry
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ResolutionAction {
+    pub kind: ResolutionActionKind,
+    pub amount: Option<f64>,          // Refund
+    pub refund_id: Option<String>,    // Gateway refund ID, if refunded through the gateway
+    pub suspension_days: Option<i64>, // Worker suspension
+    pub audit_log_id: Option<ObjectId>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DisputeResolution {
+    pub summary: String, // Shown to both parties
+    pub actions: Vec<ResolutionAction>,
+    pub resolved_by: ObjectId, // Admin
+    pub resolved_at: DateTime,
+}
+
+/// A complaint about a booking or subscription (`disputes` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Dispute {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub raised_by: ObjectId,
+    pub against_user_id: Option<ObjectId>, // Other booking party; None for subscriptions
+    pub booking_id: Option<ObjectId>,
+    pub subscription_id: Option<ObjectId>,
+    pub category: DisputeCategory,
+    pub subject: String,
+    pub description: String,
+    pub evidence: Vec<String>, // Uploaded file URLs
+    pub status: DisputeStatus,
+    pub assigned_admin_id: Option<ObjectId>,
+    pub first_response_due_at: DateTime,
+    pub resolution_due_at: DateTime,
+    pub first_responded_at: Option<DateTime>,
+    pub sla_breached: bool,
+    pub resolution: Option<DisputeResolution>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl Dispute {
+    pub fn is_closed(&self) -> bool {
+        matches!(self.status, DisputeStatus::Resolved | DisputeStatus::Rejected)
+    }
+
+    pub fn is_party(&self, user_id: ObjectId) -> bool {
+        self.raised_by == user_id || self.against_user_id == Some(user_id)
+    }
+}
+
+/// A post in a dispute's thread (`dispute_messages` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DisputeMessage {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub dispute_id: ObjectId,
+    pub author_type: String, // "user" | "admin"
+    pub author_id: ObjectId,
+    pub body: String,
+    #[serde(default)]
+    pub attachments: Vec<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateDisputeDto {
+    pub booking_id: Option<String>,
+    pub subscription_id: Option<String>, // One of booking_id / subscription_id
+    pub category: DisputeCategory,
+    pub subject: String,
+    pub description: String,
+    pub evidence: Option<Vec<String>>, // URLs from the upload endpoints
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DisputeMessageDto {
+    pub body: String,
+    pub attachments: Option<Vec<String>>, // URLs from the upload endpoints
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddEvidenceDto {
+    pub urls: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AssignDisputeDto {
+    pub admin_id: Option<String>, // Defaults to the calling admin
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResolutionActionDto {
+    pub kind: ResolutionActionKind,
+    pub amount: Option<f64>,          // Required for refunds
+    pub suspension_days: Option<i64>, // Required for suspensions
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResolveDisputeDto {
+    pub summary: String,
+    pub actions: Vec<ResolutionActionDto>, // Empty to close without action
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RejectDisputeDto {
+    pub reason: String,
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct DisputeListQuery {
+    pub status: Option<String>,
+    pub assigned_to_me: Option<bool>,
+    pub sla_breached: Option<bool>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
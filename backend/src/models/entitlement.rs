@@ -0,0 +1,98 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MeterPeriod {
+    Day,
+    Month,
+}
+
+impl MeterPeriod {
+    /// Bucket key for usage counters, e.g. "2025-03" or "2025-03-14"
+    pub fn current_key(&self) -> String {
+        let now = chrono::Utc::now();
+        match self {
+            MeterPeriod::Day => now.format("%Y-%m-%d").to_string(),
+            MeterPeriod::Month => now.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, JsonSchema)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FeatureLimit {
+    Flag,      // Feature is simply on
+    Unlimited, // Metered feature without a cap
+    Metered { limit: i64, period: MeterPeriod },
+}
+
+/// One entitlement granted by a plan
+#[derive(Debug, Serialize, Clone, PartialEq, JsonSchema)]
+pub struct Feature {
+    pub key: String,
+    pub limit: FeatureLimit,
+}
+
+impl Feature {
+    /// Parse a plan feature spec: `priority_listing`, `job_applications:unlimited`,
+    /// `contact_reveals:50/month` or `contact_reveals:5/day`
+    pub fn parse(spec: &str) -> Option<Feature> {
+        let (key, limit) = match spec.split_once(':') {
+            None => (spec, FeatureLimit::Flag),
+            Some((key, "unlimited")) => (key, FeatureLimit::Unlimited),
+            Some((key, quota)) => {
+                let (count, period) = quota.split_once('/')?;
+                let period = match period {
+                    "day" => MeterPeriod::Day,
+                    "month" => MeterPeriod::Month,
+                    _ => return None,
+                };
+                (key, FeatureLimit::Metered { limit: count.parse().ok()?, period })
+            }
+        };
+
+        if key.is_empty() {
+            return None;
+        }
+
+        Some(Feature { key: key.to_string(), limit })
+    }
+
+    /// More generous of two grants for the same feature (a user may hold several plans)
+    pub fn merge(self, other: Feature) -> Feature {
+        let limit = match (&self.limit, &other.limit) {
+            (FeatureLimit::Unlimited, _) | (_, FeatureLimit::Unlimited) => FeatureLimit::Unlimited,
+            (FeatureLimit::Metered { limit: a, .. }, FeatureLimit::Metered { limit: b, .. }) => {
+                if b > a { other.limit.clone() } else { self.limit.clone() }
+            }
+            (FeatureLimit::Metered { .. }, FeatureLimit::Flag) => self.limit.clone(),
+            (FeatureLimit::Flag, _) => other.limit.clone(),
+        };
+
+        Feature { key: self.key, limit }
+    }
+}
+
+/// Usage counter for a metered feature in one period (`entitlement_usage` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EntitlementUsage {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub feature: String,
+    pub period: String,
+    pub count: i64,
+    pub updated_at: DateTime,
+}
+
+/// A user paid (from `contact_reveals`) to see another user's contact details
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContactReveal {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub target_user_id: ObjectId,
+    pub created_at: DateTime,
+}
@@ -0,0 +1,104 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InterviewStatus {
+    Proposed,  // Waiting for the applicant to pick a slot
+    Scheduled,
+    Cancelled,
+}
+
+impl InterviewStatus {
+    /// Value stored in the `status` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InterviewStatus::Proposed => "proposed",
+            InterviewStatus::Scheduled => "scheduled",
+            InterviewStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InterviewMode {
+    InPerson,
+    Phone,
+    Video,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InterviewSlot {
+    pub start: DateTime,
+    pub duration_minutes: i32,
+}
+
+/// An interview between a job poster and an applicant (`interviews` collection).
+/// The poster proposes slots and the applicant picks one.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Interview {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub application_id: ObjectId,
+    pub job_id: ObjectId,
+    pub poster_user_id: ObjectId,
+    pub seeker_user_id: ObjectId,
+    pub mode: InterviewMode,
+    pub location: Option<String>, // Address, phone number or meeting link
+    pub notes: Option<String>,
+    pub slots: Vec<InterviewSlot>,
+    pub scheduled_slot: Option<InterviewSlot>,
+    pub status: InterviewStatus,
+    pub round: i32, // Bumped on each reschedule; part of the calendar invite UID
+    pub reminder_sent: bool,
+    pub cancelled_by: Option<ObjectId>,
+    pub cancel_reason: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl Interview {
+    pub fn is_party(&self, user_id: ObjectId) -> bool {
+        self.poster_user_id == user_id || self.seeker_user_id == user_id
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct InterviewSlotDto {
+    pub start: String, // RFC 3339, e.g. "2025-03-14T10:30:00+05:30"
+    pub duration_minutes: Option<i32>, // Defaults to 30
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ProposeInterviewDto {
+    pub slots: Vec<InterviewSlotDto>,
+    pub mode: InterviewMode,
+    pub location: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SelectInterviewSlotDto {
+    pub slot_index: usize,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RescheduleInterviewDto {
+    pub slots: Vec<InterviewSlotDto>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CancelInterviewDto {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct InterviewListQuery {
+    pub role: Option<String>, // "seeker" (default) or "poster"
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
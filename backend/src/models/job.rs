@@ -2,6 +2,8 @@ use mongodb::bson::{oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 use rocket_okapi::okapi::schemars::JsonSchema;
 
+use super::{ApplicationStatus, DEFAULT_PIPELINE, GeoLocation};
+
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum JobSeekerSubscriptionPlan {
@@ -115,6 +117,51 @@ pub struct UpdateJobSeekerProfileDto {
     pub is_available: Option<bool>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ParseResumeDto {
+    pub resume_url: String, // URL returned by /upload/document
+}
+
+/// Profile fields guessed from a resume. Nothing is saved; the seeker reviews
+/// the draft and submits it as a `CreateJobSeekerProfileDto`.
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct ResumeDraft {
+    pub full_name: Option<String>,
+    pub headline: Option<String>,
+    pub bio: Option<String>,
+    pub skills: Vec<String>,
+    pub experience_years: Option<i32>,
+    pub education: Vec<Education>,
+    pub work_experience: Vec<WorkExperience>,
+    pub resume_url: Option<String>,
+    pub portfolio_url: Option<String>,
+    pub linkedin_url: Option<String>,
+}
+
+/// Layouts for generated resumes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResumeTemplate {
+    Classic, // Centred header, one section after another
+    Modern,  // Left-aligned header with bold ruled sections
+    Compact, // Smaller type to fit more on a page
+}
+
+impl ResumeTemplate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResumeTemplate::Classic => "classic",
+            ResumeTemplate::Modern => "modern",
+            ResumeTemplate::Compact => "compact",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GenerateResumeDto {
+    pub template: Option<ResumeTemplate>, // Defaults to classic
+}
+
 #[derive(Debug, Serialize, JsonSchema)]
 pub struct JobSeekerProfileResponse {
     pub id: String,
@@ -174,6 +221,120 @@ impl From<JobSeekerProfile> for JobSeekerProfileResponse {
 
 // ================== JOB POSTS ==================
 
+/// Lifecycle of a job post. New and edited posts wait for moderation; approved
+/// posts are public until the owner closes them or they expire.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Pending,  // Awaiting moderation
+    Approved, // Public
+    Rejected, // By moderation; the owner can edit and resubmit
+    Inactive, // Taken down by an admin
+    Closed,   // By the owner
+    Expired,
+}
+
+impl JobStatus {
+    /// Value stored in the `status` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Pending => "pending",
+            JobStatus::Approved => "approved",
+            JobStatus::Rejected => "rejected",
+            JobStatus::Inactive => "inactive",
+            JobStatus::Closed => "closed",
+            JobStatus::Expired => "expired",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<JobStatus> {
+        match value {
+            "pending" => Some(JobStatus::Pending),
+            "approved" => Some(JobStatus::Approved),
+            "rejected" => Some(JobStatus::Rejected),
+            "inactive" => Some(JobStatus::Inactive),
+            "closed" => Some(JobStatus::Closed),
+            "expired" => Some(JobStatus::Expired),
+            _ => None,
+        }
+    }
+
+    pub fn can_move_to(&self, next: JobStatus) -> bool {
+        use JobStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Approved | Rejected)
+                | (Approved, Pending | Inactive | Closed | Expired)
+                | (Rejected, Pending)
+                | (Inactive, Approved)
+                | (Closed, Approved)
+                | (Expired, Approved)
+        )
+    }
+}
+
+/// Employment type; same values as `JobSeekerProfile.preferred_job_types`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobType {
+    Fulltime,
+    Parttime,
+    Contract,
+    Freelance,
+}
+
+impl JobType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobType::Fulltime => "fulltime",
+            JobType::Parttime => "parttime",
+            JobType::Contract => "contract",
+            JobType::Freelance => "freelance",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<JobType> {
+        match value {
+            "fulltime" => Some(JobType::Fulltime),
+            "parttime" => Some(JobType::Parttime),
+            "contract" => Some(JobType::Contract),
+            "freelance" => Some(JobType::Freelance),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkMode {
+    Onsite,
+    Remote,
+    Hybrid,
+}
+
+impl WorkMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkMode::Onsite => "onsite",
+            WorkMode::Remote => "remote",
+            WorkMode::Hybrid => "hybrid",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<WorkMode> {
+        match value {
+            "onsite" => Some(WorkMode::Onsite),
+            "remote" => Some(WorkMode::Remote),
+            "hybrid" => Some(WorkMode::Hybrid),
+            _ => None,
+        }
+    }
+}
+
+fn default_openings() -> i32 {
+    1
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct JobPost {
@@ -187,19 +348,52 @@ pub struct JobPost {
     pub job_role: String,
     pub salary_min: Option<f64>,
     pub salary_max: Option<f64>,
-    pub location: Option<String>,
+    pub location: Option<String>, // Display text, e.g. "Andheri, Mumbai"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo_location: Option<GeoLocation>, // For radius search (2dsphere index)
+    #[serde(default)]
+    pub job_type: Option<JobType>,
+    #[serde(default)]
+    pub work_mode: Option<WorkMode>,
+    #[serde(default)]
+    pub experience_min: Option<i32>, // Years
+    #[serde(default)]
+    pub experience_max: Option<i32>,
+    #[serde(default)]
+    pub category_id: Option<ObjectId>, // main_categories
+    #[serde(default)]
+    pub subcategory_id: Option<ObjectId>, // sub_categories, within category_id
+    #[serde(default = "default_openings")]
+    pub openings: i32,
     pub hr_name: Option<String>,
     pub hr_email: Option<String>,
     pub hr_contact: Option<String>,
     pub company_document_url: Option<String>,
-    pub status: String, // pending, approved, inactive
+    pub status: JobStatus,
     pub posted_by: ObjectId,
     #[serde(default)]
     pub applications: Vec<ObjectId>,
+    #[serde(default)]
+    pub rejection_reason: Option<String>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime>, // Set on approval
+    #[serde(default)]
+    pub expiry_reminder_sent: bool,
+    #[serde(default)]
+    pub approved_at: Option<DateTime>, // First approval; later re-approvals keep it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<Vec<ApplicationStatus>>, // None uses DEFAULT_PIPELINE
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
 
+impl JobPost {
+    /// Applicant stages between applied and hired, in order
+    pub fn pipeline(&self) -> &[ApplicationStatus] {
+        self.pipeline.as_deref().unwrap_or(&DEFAULT_PIPELINE)
+    }
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct CreateJobPostDto {
@@ -218,6 +412,32 @@ pub struct CreateJobPostDto {
     pub company_document_url: Option<String>,
 }
 
+/// Owner edits; any change sends the post back to moderation
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateJobPostDto {
+    pub title: Option<String>,
+    pub company_name: Option<String>,
+    pub company_brief: Option<String>,
+    pub eligibility: Option<Vec<String>>,
+    pub requirements: Option<Vec<String>>,
+    pub job_role: Option<String>,
+    pub salary_min: Option<f64>,
+    pub salary_max: Option<f64>,
+    pub location: Option<String>,
+    pub latitude: Option<f64>, // Both or neither
+    pub longitude: Option<f64>,
+    pub job_type: Option<JobType>,
+    pub work_mode: Option<WorkMode>,
+    pub experience_min: Option<i32>,
+    pub experience_max: Option<i32>,
+    pub category_id: Option<String>,
+    pub subcategory_id: Option<String>,
+    pub openings: Option<i32>,
+    pub hr_name: Option<String>,
+    pub hr_email: Option<String>,
+    pub hr_contact: Option<String>,
+}
+
 #[derive(Debug, Serialize, JsonSchema)]
 pub struct JobPostResponse {
     pub id: String,
@@ -230,13 +450,26 @@ pub struct JobPostResponse {
     pub salary_min: Option<f64>,
     pub salary_max: Option<f64>,
     pub location: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub job_type: Option<JobType>,
+    pub work_mode: Option<WorkMode>,
+    pub experience_min: Option<i32>,
+    pub experience_max: Option<i32>,
+    pub category_id: Option<String>,
+    pub subcategory_id: Option<String>,
+    pub openings: i32,
     pub hr_name: Option<String>,
     pub hr_email: Option<String>,
     pub hr_contact: Option<String>,
     pub company_document_url: Option<String>,
-    pub status: String,
+    pub status: JobStatus,
     pub posted_by: String,
     pub applications: Vec<String>,
+    pub rejection_reason: Option<String>,
+    pub pipeline: Vec<ApplicationStatus>,
+    #[schemars(skip)]
+    pub expires_at: Option<DateTime>,
     #[schemars(skip)]
     pub created_at: DateTime,
     #[schemars(skip)]
@@ -245,6 +478,7 @@ pub struct JobPostResponse {
 
 impl From<JobPost> for JobPostResponse {
     fn from(job: JobPost) -> Self {
+        let pipeline = job.pipeline().to_vec();
         JobPostResponse {
             id: job.id.unwrap().to_hex(),
             title: job.title,
@@ -256,6 +490,15 @@ impl From<JobPost> for JobPostResponse {
             salary_min: job.salary_min,
             salary_max: job.salary_max,
             location: job.location,
+            latitude: job.geo_location.as_ref().map(|g| g.coordinates[1]),
+            longitude: job.geo_location.as_ref().map(|g| g.coordinates[0]),
+            job_type: job.job_type,
+            work_mode: job.work_mode,
+            experience_min: job.experience_min,
+            experience_max: job.experience_max,
+            category_id: job.category_id.map(|id| id.to_hex()),
+            subcategory_id: job.subcategory_id.map(|id| id.to_hex()),
+            openings: job.openings,
             hr_name: job.hr_name,
             hr_email: job.hr_email,
             hr_contact: job.hr_contact,
@@ -263,6 +506,9 @@ impl From<JobPost> for JobPostResponse {
             status: job.status,
             posted_by: job.posted_by.to_hex(),
             applications: job.applications.into_iter().map(|id| id.to_hex()).collect(),
+            rejection_reason: job.rejection_reason,
+            pipeline,
+            expires_at: job.expires_at,
             created_at: job.created_at,
             updated_at: job.updated_at,
         }
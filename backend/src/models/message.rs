@@ -0,0 +1,71 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+/// A chat between a customer and a worker (`conversations` collection).
+/// There is at most one per customer and worker.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Conversation {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub customer_id: ObjectId,
+    pub worker_id: ObjectId,      // Worker profile ID
+    pub worker_user_id: ObjectId, // User behind the worker profile
+    pub participants: Vec<ObjectId>, // [customer_id, worker_user_id], for lookups by user
+    pub last_message_preview: Option<String>,
+    pub last_message_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+impl Conversation {
+    /// The participant who isn't `user_id`
+    pub fn other_participant(&self, user_id: ObjectId) -> ObjectId {
+        if user_id == self.customer_id {
+            self.worker_user_id
+        } else {
+            self.customer_id
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct MessageAttachment {
+    pub kind: String, // "image"
+    pub url: String,  // From the upload endpoints
+}
+
+/// A message in a conversation (`messages` collection). The body is stored as sent;
+/// phone numbers are masked when it is shown.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Message {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub conversation_id: ObjectId,
+    pub sender_id: ObjectId,
+    pub recipient_id: ObjectId,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub attachments: Vec<MessageAttachment>,
+    pub read_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct StartConversationDto {
+    pub worker_id: Option<String>,  // As a customer, the worker profile to message
+    pub booking_id: Option<String>, // Or either side of an existing booking
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SendMessageDto {
+    pub body: Option<String>,
+    pub image_urls: Option<Vec<String>>, // URLs returned by /upload/image
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct MessageListQuery {
+    pub after: Option<String>,  // Message ID; newer messages, oldest first (polling)
+    pub before: Option<String>, // Message ID; older messages, newest first (history)
+    pub limit: Option<i64>,
+}
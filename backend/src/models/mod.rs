@@ -2,10 +2,24 @@ pub mod user;
 pub mod otp;
 pub mod kyc;
 pub mod worker;
+pub mod availability;
 pub mod job;
+pub mod application;
+pub mod interview;
+pub mod saved_search;
 pub mod category;
 pub mod subscription;
+pub mod plan;
+pub mod entitlement;
+pub mod payment_link;
+pub mod booking;
+pub mod tracking;
+pub mod quote;
+pub mod service_request;
 pub mod review;
+pub mod message;
+pub mod dispute;
+pub mod audit;
 pub mod service;
 pub mod notification;
 pub mod admin;
@@ -14,10 +28,24 @@ pub use user::*;
 pub use otp::*;
 pub use kyc::*;
 pub use worker::*;
+pub use availability::*;
 pub use job::*;
+pub use application::*;
+pub use interview::*;
+pub use saved_search::*;
 pub use category::*;
 pub use subscription::*;
+pub use plan::*;
+pub use entitlement::*;
+pub use payment_link::*;
+pub use booking::*;
+pub use tracking::*;
+pub use quote::*;
+pub use service_request::*;
 pub use review::*;
+pub use message::*;
+pub use dispute::*;
+pub use audit::*;
 pub use service::*;
 pub use notification::*;
 pub use admin::*;
\ No newline at end of file
@@ -0,0 +1,53 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+use super::SubscriptionType;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentLinkStatus {
+    Created, // Sent, waiting for the customer to pay
+    Paid,
+    Expired,
+    Cancelled,
+}
+
+/// Hosted payment link created by an admin for an assisted sale (`payment_links` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PaymentLink {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub subscription_id: ObjectId, // Pending subscription activated when the link is paid
+    pub subscription_type: SubscriptionType,
+    pub plan_name: String,
+    pub amount: f64,
+    pub invoice_number: i64,
+    pub gateway: String,
+    pub gateway_link_id: String,
+    pub short_url: String,
+    pub status: PaymentLinkStatus,
+    #[serde(default)]
+    pub sent_via: Vec<String>, // "sms" | "email"
+    pub created_by: ObjectId, // Admin who generated the link
+    pub payment_id: Option<String>,
+    pub paid_at: Option<DateTime>,
+    pub expires_at: DateTime,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreatePaymentLinkDto {
+    pub user_id: String,
+    pub subscription_type: String, // "worker" or "jobseeker"
+    pub plan_name: String,
+    pub send_via: Option<Vec<String>>, // Defaults to ["sms"]
+    pub expires_in_hours: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResendPaymentLinkDto {
+    pub send_via: Vec<String>,
+}
@@ -0,0 +1,128 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+use super::SubscriptionType;
+
+/// Static definition of a purchasable plan
+#[derive(Debug, Clone)]
+pub struct PlanDefinition {
+    pub name: &'static str,
+    pub subscription_type: SubscriptionType,
+    pub price: f64,
+    pub duration_days: i64,
+    pub tier: i32, // Higher tier = more expensive plan
+    pub trial_days: i64, // 0 = no free trial (override with TRIAL_DAYS_<PLAN>)
+    pub features: &'static [&'static str], // Entitlement specs, see `Feature::parse`
+}
+
+pub const PLANS: &[PlanDefinition] = &[
+    PlanDefinition {
+        name: "silver",
+        subscription_type: SubscriptionType::Worker,
+        price: 499.0,
+        duration_days: 365,
+        tier: 1,
+        trial_days: 7,
+        features: &["profile_listing", "booking_requests:unlimited", "contact_reveals:30/month"],
+    },
+    PlanDefinition {
+        name: "gold",
+        subscription_type: SubscriptionType::Worker,
+        price: 799.0,
+        duration_days: 365,
+        tier: 2,
+        trial_days: 7,
+        features: &[
+            "profile_listing",
+            "priority_listing",
+            "verified_badge",
+            "booking_requests:unlimited",
+            "contact_reveals:100/month",
+        ],
+    },
+    PlanDefinition {
+        name: "basic",
+        subscription_type: SubscriptionType::JobSeeker,
+        price: 99.0,
+        duration_days: 365,
+        tier: 1,
+        trial_days: 0,
+        features: &["profile_listing", "job_applications:20/month"],
+    },
+    PlanDefinition {
+        name: "premium",
+        subscription_type: SubscriptionType::JobSeeker,
+        price: 1.5,
+        duration_days: 365,
+        tier: 2,
+        trial_days: 7,
+        features: &[
+            "profile_listing",
+            "priority_listing",
+            "job_applications:unlimited",
+            "contact_reveals:50/month",
+        ],
+    },
+];
+
+impl PlanDefinition {
+    /// Free trial length, overridable per plan with `TRIAL_DAYS_<PLAN>` (e.g. `TRIAL_DAYS_GOLD=14`)
+    pub fn trial_days(&self) -> i64 {
+        std::env::var(format!("TRIAL_DAYS_{}", self.name.to_uppercase()))
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(self.trial_days)
+    }
+}
+
+/// Look up a plan by type and (case-insensitive) name
+pub fn find_plan(subscription_type: &SubscriptionType, name: &str) -> Option<&'static PlanDefinition> {
+    let name = name.to_lowercase();
+    PLANS.iter().find(|p| p.subscription_type == *subscription_type && p.name == name)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanChangeType {
+    Upgrade,
+    Downgrade,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanChangeStatus {
+    Pending,   // Upgrade awaiting payment
+    Scheduled, // Downgrade waiting for renewal time
+    Completed,
+    Cancelled,
+}
+
+/// One entry in a user's plan change history (`plan_changes` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlanChange {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub subscription_type: SubscriptionType,
+    pub change_type: PlanChangeType,
+    pub status: PlanChangeStatus,
+    pub from_plan: String,
+    pub to_plan: String,
+    pub from_subscription_id: ObjectId,
+    pub to_subscription_id: Option<ObjectId>,
+    pub prorated_amount: f64, // Amount charged for the remaining period (0 for downgrades)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>, // Gateway order the upgrade must be paid through
+    pub effective_at: DateTime,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct VerifyPlanChangeDto {
+    pub plan_change_id: String,
+    pub razorpay_order_id: String,
+    pub razorpay_payment_id: String,
+    pub razorpay_signature: String,
+}
@@ -0,0 +1,115 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+use super::GeoLocation;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteStatus {
+    Draft, // Only visible to the worker
+    Sent,
+    Accepted,
+    Rejected,
+    Expired,
+}
+
+impl QuoteStatus {
+    /// Value stored in the `status` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuoteStatus::Draft => "draft",
+            QuoteStatus::Sent => "sent",
+            QuoteStatus::Accepted => "accepted",
+            QuoteStatus::Rejected => "rejected",
+            QuoteStatus::Expired => "expired",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LineItemKind {
+    Labour,
+    Materials,
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct QuoteLineItem {
+    pub kind: LineItemKind,
+    pub description: String,
+    pub quantity: f64,
+    pub unit_price: f64,
+    pub amount: f64, // quantity * unit_price, rounded to the paisa
+}
+
+/// A worker's estimate for a customer (`quotes` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Quote {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub quote_number: i64,
+    pub customer_id: ObjectId,
+    pub worker_id: ObjectId,      // Worker profile ID
+    pub worker_user_id: ObjectId, // User behind the worker profile
+    pub booking_id: Option<ObjectId>, // Visit or request the quote follows up on
+    pub category: Option<String>,
+    pub subcategory: String,
+    pub address: String,
+    pub location: GeoLocation,
+    pub line_items: Vec<QuoteLineItem>,
+    pub subtotal: f64,
+    pub tax_percent: f64,
+    pub tax_amount: f64,
+    pub total: f64,
+    pub notes: Option<String>,
+    pub valid_days: i32,
+    pub valid_until: Option<DateTime>, // Set when the quote is sent
+    pub status: QuoteStatus,
+    pub rejection_reason: Option<String>,
+    pub converted_booking_id: Option<ObjectId>, // Booking created on acceptance
+    pub sent_at: Option<DateTime>,
+    pub responded_at: Option<DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct QuoteLineItemDto {
+    pub kind: LineItemKind,
+    pub description: String,
+    pub quantity: Option<f64>, // Defaults to 1
+    pub unit_price: f64,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateQuoteDto {
+    pub booking_id: String,
+    pub line_items: Vec<QuoteLineItemDto>,
+    pub tax_percent: Option<f64>, // Defaults to 0
+    pub valid_days: Option<i32>,  // Defaults to 7
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateQuoteDto {
+    pub line_items: Option<Vec<QuoteLineItemDto>>,
+    pub tax_percent: Option<f64>,
+    pub valid_days: Option<i32>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AcceptQuoteDto {
+    pub scheduled_at: String, // RFC 3339, e.g. "2025-03-14T10:30:00+05:30"
+    pub duration_minutes: Option<i32>, // Defaults to 60
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct QuoteListQuery {
+    pub role: Option<String>, // "customer" (default) or "worker"
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
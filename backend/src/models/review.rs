@@ -8,6 +8,12 @@ pub struct Review {
     pub id: Option<ObjectId>,
     pub worker_id: ObjectId,
     pub user_id: ObjectId,
+    #[serde(default)]
+    pub booking_id: Option<ObjectId>, // Completed booking being reviewed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reveal_id: Option<ObjectId>, // Contact reveal being reviewed, for work arranged outside a booking
+    #[serde(default)]
+    pub is_verified: bool, // false for reviews from before bookings or reveals were required
     pub rating: i32, // 1-5
     pub comment: Option<String>,
     pub helpful_count: i32,
@@ -17,7 +23,8 @@ pub struct Review {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateReviewDto {
-    pub worker_id: String,
+    pub booking_id: Option<String>, // Either a completed booking...
+    pub reveal_id: Option<String>,  // ...or a contact reveal of the worker
     pub rating: i32,
     pub comment: Option<String>,
 }
\ No newline at end of file
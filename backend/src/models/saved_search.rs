@@ -0,0 +1,100 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+/// How often a saved search sends alerts for newly approved jobs
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertFrequency {
+    Off,
+    Instant, // On the next scheduler run after approval
+    Daily,
+    Weekly,
+}
+
+impl AlertFrequency {
+    /// Value stored in the `alert_frequency` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertFrequency::Off => "off",
+            AlertFrequency::Instant => "instant",
+            AlertFrequency::Daily => "daily",
+            AlertFrequency::Weekly => "weekly",
+        }
+    }
+}
+
+/// A job seeker's saved `get_public_jobs` query (`saved_searches` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedSearch {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub user_id: ObjectId,
+    pub name: String,
+    pub q: Option<String>, // Matched against title, company and role
+    pub location: Option<String>,
+    pub job_role: Option<String>,
+    pub salary_min: Option<f64>, // Lowest acceptable salary
+    pub alert_frequency: AlertFrequency,
+    pub email_alerts: bool, // In-app notifications are always sent
+    pub unsubscribe_token: String, // Lets email links turn off email alerts without signing in
+    pub last_checked_at: DateTime, // Jobs approved after this are new
+    pub pending_job_ids: Vec<ObjectId>, // Matches waiting for the next digest
+    pub last_digest_at: DateTime,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateSavedSearchDto {
+    pub name: String,
+    pub q: Option<String>,
+    pub location: Option<String>,
+    pub job_role: Option<String>,
+    pub salary_min: Option<f64>,
+    pub alert_frequency: Option<AlertFrequency>, // Defaults to daily
+    pub email_alerts: Option<bool>, // Defaults to true
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateSavedSearchDto {
+    pub name: Option<String>,
+    pub q: Option<String>,
+    pub location: Option<String>,
+    pub job_role: Option<String>,
+    pub salary_min: Option<f64>,
+    pub alert_frequency: Option<AlertFrequency>,
+    pub email_alerts: Option<bool>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SavedSearchResponse {
+    pub id: String,
+    pub name: String,
+    pub q: Option<String>,
+    pub location: Option<String>,
+    pub job_role: Option<String>,
+    pub salary_min: Option<f64>,
+    pub alert_frequency: AlertFrequency,
+    pub email_alerts: bool,
+    pub pending_matches: usize,
+    #[schemars(skip)]
+    pub created_at: DateTime,
+}
+
+impl From<SavedSearch> for SavedSearchResponse {
+    fn from(search: SavedSearch) -> Self {
+        SavedSearchResponse {
+            id: search.id.map(|id| id.to_hex()).unwrap_or_default(),
+            name: search.name,
+            q: search.q,
+            location: search.location,
+            job_role: search.job_role,
+            salary_min: search.salary_min,
+            alert_frequency: search.alert_frequency,
+            email_alerts: search.email_alerts,
+            pending_matches: search.pending_job_ids.len(),
+            created_at: search.created_at,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+use super::GeoLocation;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceRequestStatus {
+    Open, // Collecting offers
+    Assigned,
+    Cancelled,
+    Expired,
+}
+
+impl ServiceRequestStatus {
+    /// Value stored in the `status` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceRequestStatus::Open => "open",
+            ServiceRequestStatus::Assigned => "assigned",
+            ServiceRequestStatus::Cancelled => "cancelled",
+            ServiceRequestStatus::Expired => "expired",
+        }
+    }
+}
+
+/// A worker matched to a request when it was posted. Higher plans get an earlier
+/// `access_at`; the worker is notified and can respond from then on.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RequestMatch {
+    pub worker_id: ObjectId,      // Worker profile ID
+    pub worker_user_id: ObjectId, // User behind the worker profile
+    pub distance_m: f64,
+    pub access_at: DateTime,
+    pub notified_at: Option<DateTime>,
+}
+
+/// A customer's posted requirement that nearby workers respond to
+/// (`service_requests` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServiceRequest {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub customer_id: ObjectId,
+    pub category: Option<String>,
+    pub subcategory: String,
+    pub description: String,
+    pub address: String, // Only shown to the chosen worker
+    pub pincode: Option<String>,
+    pub location: GeoLocation,
+    pub radius_km: f64,
+    pub budget_min: Option<f64>,
+    pub budget_max: Option<f64>,
+    pub preferred_at: Option<DateTime>,
+    pub matches: Vec<RequestMatch>,
+    pub offers_count: i32,
+    pub status: ServiceRequestStatus,
+    pub accepted_offer_id: Option<ObjectId>,
+    pub booking_id: Option<ObjectId>, // Booking created from the accepted offer
+    pub expires_at: DateTime,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OfferStatus {
+    Pending,
+    Accepted,
+    Declined, // Another offer was chosen or the request closed
+    Withdrawn,
+}
+
+/// A worker's response to a service request (`service_offers` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ServiceOffer {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub request_id: ObjectId,
+    pub worker_id: ObjectId,
+    pub worker_user_id: ObjectId,
+    pub price: f64,
+    pub message: Option<String>,
+    pub scheduled_at: DateTime,
+    pub duration_minutes: i32,
+    pub status: OfferStatus,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateServiceRequestDto {
+    pub category: Option<String>,
+    pub subcategory: String,
+    pub description: String,
+    pub address: String,
+    pub pincode: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_km: Option<f64>, // Defaults to 10
+    pub budget_min: Option<f64>,
+    pub budget_max: Option<f64>,
+    pub preferred_at: Option<String>, // RFC 3339
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateOfferDto {
+    pub price: f64,
+    pub message: Option<String>,
+    pub scheduled_at: String, // RFC 3339, e.g. "2025-03-14T10:30:00+05:30"
+    pub duration_minutes: Option<i32>, // Defaults to 60
+}
+
+#[derive(Debug, FromForm, Deserialize, JsonSchema)]
+pub struct ServiceRequestListQuery {
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
@@ -2,20 +2,50 @@ use mongodb::bson::{oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 use rocket_okapi::okapi::schemars::JsonSchema;
 
-#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SubscriptionType {
     Worker,
     JobSeeker,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+impl SubscriptionType {
+    /// Value stored in the `subscription_type` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubscriptionType::Worker => "worker",
+            SubscriptionType::JobSeeker => "jobseeker",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SubscriptionStatus {
     Pending,
     Active,
     Expired,
     Cancelled,
+    Superseded, // Replaced mid-period by an upgrade
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MandateStatus {
+    Pending, // Customer has not authorised the mandate yet
+    Active,
+    Revoked,
+}
+
+/// Recurring payment authorisation used for auto-renewal
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PaymentMandate {
+    pub gateway: String, // "razorpay" | "local"
+    pub customer_id: Option<String>,
+    pub order_id: Option<String>, // Authorisation order the customer pays
+    pub token_id: Option<String>, // Set once the mandate is confirmed
+    pub status: MandateStatus,
+    pub created_at: DateTime,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -33,6 +63,34 @@ pub struct Subscription {
     pub payment_id: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub invoice_number: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_subscription_id: Option<ObjectId>, // Subscription this one replaced (upgrade history)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled_plan: Option<String>, // Downgrade to apply at renewal time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mandate: Option<PaymentMandate>,
+    #[serde(default)]
+    pub renewal_attempts: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_renewal_at: Option<DateTime>, // Earliest time for the next charge attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renewal_payment_id: Option<String>, // Renewal charged but the new period not yet created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grace_until: Option<DateTime>, // Access kept until then while renewal is retried
+    #[serde(default)]
+    pub is_trial: bool,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateAutoRenewDto {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ConfirmMandateDto {
+    pub razorpay_order_id: String,
+    pub razorpay_payment_id: String,
+    pub razorpay_signature: String,
+}
@@ -0,0 +1,32 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+use super::GeoLocation;
+
+/// How long live positions are kept (TTL index on `recorded_at`)
+pub const LOCATION_RETENTION_SECS: u64 = 2 * 60 * 60;
+
+/// A worker position shared with the customer during a booking
+/// (`booking_locations` collection)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BookingLocation {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub booking_id: ObjectId,
+    pub worker_user_id: ObjectId,
+    pub location: GeoLocation,
+    pub accuracy_m: Option<f64>,
+    pub heading: Option<f64>,   // Degrees from north
+    pub speed_mps: Option<f64>,
+    pub recorded_at: DateTime,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LocationPingDto {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub accuracy_m: Option<f64>,
+    pub heading: Option<f64>,
+    pub speed_mps: Option<f64>,
+}
@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use rocket_okapi::okapi::schemars;
 use rocket_okapi::okapi::schemars::JsonSchema;
 
+use super::ReliabilityStats;
+
 #[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum KycStatus {
@@ -53,6 +55,9 @@ pub struct User {
 
     /* ---------------------------------------------------------------------- */
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reliability: Option<ReliabilityStats>, // As a customer
+
     pub last_login_at: DateTime,
     pub created_at: DateTime,
     pub updated_at: DateTime,
@@ -2,6 +2,8 @@ use mongodb::bson::{oid::ObjectId, DateTime};
 use serde::{Deserialize, Serialize};
 use rocket_okapi::okapi::schemars::JsonSchema;
 
+use super::{ReliabilityStats, WorkerAvailability};
+
 #[derive(Debug, FromForm, Deserialize, JsonSchema)]
 pub struct NearbyWorkerQuery {
     pub latitude: f64,
@@ -9,6 +11,8 @@ pub struct NearbyWorkerQuery {
 
     pub category: Option<String>,
     pub subcategory: Option<String>,
+    pub available_at: Option<String>, // RFC 3339; only workers free from then on
+    pub duration_minutes: Option<i32>, // Length of the free window needed (default 60)
 
     pub page: Option<i64>,
     pub limit: Option<i64>,
@@ -55,6 +59,12 @@ pub struct WorkerProfile {
     pub rating: f64,
     pub total_reviews: i32,
     pub total_jobs_completed: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub availability: Option<WorkerAvailability>, // None = default calendar
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suspended_until: Option<DateTime>, // Set when a dispute resolution suspends the worker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reliability: Option<ReliabilityStats>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
     pub location: GeoLocation,
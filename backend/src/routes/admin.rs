@@ -1,5 +1,8 @@
 use crate::db::DbConn;
-use crate::models::{CategoryResponse, MainCategory, SubCategory, SubCategoryResponse, WorkerProfile, JobSeekerProfile, User, Subscription, SubscriptionType, SubscriptionStatus};
+use crate::models::{CategoryResponse, MainCategory, SubCategory, SubCategoryResponse, WorkerProfile, JobSeekerProfile, JobStatus, User, Subscription, SubscriptionType, SubscriptionStatus};
+use crate::guards::AdminGuard;
+use crate::services::{AnalyticsService, JobPostService};
+use crate::services::analytics::{AnalyticsRange, Interval};
 use crate::utils::{ApiError, ApiResponse};
 use mongodb::bson::{doc, DateTime, oid::ObjectId};
 use mongodb::options::FindOptions;
@@ -711,36 +714,100 @@ pub async fn create_job(
     )))
 }
 
+/// Moderate a job post. Moves must follow the job lifecycle (see `JobStatus`);
+/// approving starts the post's validity period.
 #[openapi(tag = "Admin - Jobs")]
 #[put("/admin/jobs/<job_id>/status", data = "<dto>")]
 pub async fn update_job_status(
     db: &State<DbConn>,
+    _admin: AdminGuard,
     job_id: String,
     dto: Json<UpdateJobStatusDto>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     let object_id = ObjectId::parse_str(&job_id)
         .map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+    let to = JobStatus::parse(&dto.status)
+        .ok_or_else(|| ApiError::bad_request(format!("Unknown job status '{}'", dto.status)))?;
+
+    // Read loosely: jobs created here before posts had an owner use another shape
+    let job = db.collection::<mongodb::bson::Document>("jobs")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job not found"))?;
+    let current = job.get_str("status").unwrap_or_default().to_string();
+
+    if let Some(from) = JobStatus::parse(&current)
+        && !from.can_move_to(to)
+    {
+        return Err(ApiError::bad_request(format!(
+            "A {} job cannot be moved to {}",
+            from.as_str(),
+            to.as_str()
+        )));
+    }
 
     let mut update_doc = doc! {
-        "status": &dto.status,
-        "updated_at": DateTime::now(),
+        "status": to.as_str(),
+        "updatedAt": DateTime::now(),
     };
-
-    if let Some(ref reason) = dto.rejection_reason {
-        update_doc.insert("rejection_reason", reason);
+    if to == JobStatus::Rejected {
+        let reason = dto.rejection_reason.as_deref().map(str::trim).unwrap_or_default();
+        if reason.is_empty() {
+            return Err(ApiError::bad_request("A rejection reason is required"));
+        }
+        update_doc.insert("rejectionReason", reason);
+    }
+    if to == JobStatus::Approved {
+        if !job.contains_key("approvedAt") {
+            update_doc.insert("approvedAt", DateTime::now());
+        }
+        let expired = job.get_datetime("expiresAt").map_or(true, |at| *at <= DateTime::now());
+        if expired {
+            update_doc.insert("expiresAt", JobPostService::fresh_expiry());
+            update_doc.insert("expiryReminderSent", false);
+        }
     }
 
-    db.collection::<Job>("jobs")
+    let result = db.collection::<mongodb::bson::Document>("jobs")
         .update_one(
-            doc! { "_id": object_id },
+            doc! { "_id": object_id, "status": &current },
             doc! { "$set": update_doc },
             None
         )
         .await
         .map_err(|e| ApiError::internal_error(format!("Failed to update job: {}", e)))?;
+    if result.matched_count == 0 {
+        return Err(ApiError::bad_request("Job was updated by someone else. Please refresh"));
+    }
+
+    if let Ok(owner) = job.get_object_id("postedBy") {
+        let title = job.get_str("title").unwrap_or("your job");
+        let body = match to {
+            JobStatus::Approved => Some(format!("Your job \"{}\" is approved and now live.", title)),
+            JobStatus::Rejected => Some(format!(
+                "Your job \"{}\" was not approved: {}. Edit it to resubmit.",
+                title,
+                dto.rejection_reason.as_deref().unwrap_or_default().trim()
+            )),
+            JobStatus::Inactive => Some(format!("Your job \"{}\" was taken down by our team.", title)),
+            _ => None,
+        };
+        if let Some(body) = body {
+            let _ = crate::routes::notification::create_user_notification(
+                db,
+                owner,
+                "job_moderation".to_string(),
+                "Job Post Update".to_string(),
+                body,
+                Some(object_id),
+            )
+            .await;
+        }
+    }
 
     Ok(Json(ApiResponse::success(serde_json::json!({
-        "message": format!("Job status updated to {}", dto.status)
+        "message": format!("Job status updated to {}", to.as_str())
     }))))
 }
 
@@ -1265,6 +1332,14 @@ pub async fn create_subscription(
         auto_renew: dto.auto_renew.unwrap_or(false),
         payment_id: dto.payment_id.clone(),
         invoice_number: Some(next_invoice_number),
+        previous_subscription_id: None,
+        scheduled_plan: None,
+        mandate: None,
+        renewal_attempts: 0,
+        next_renewal_at: None,
+        renewal_payment_id: None,
+        grace_until: None,
+        is_trial: false,
         created_at: now,
         updated_at: now,
     };
@@ -1519,6 +1594,146 @@ pub async fn delete_subscription(
     }))))
 }
 
+// ==================== ANALYTICS ADMIN ROUTES ====================
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct AnalyticsQuery {
+    pub from: Option<String>,              // YYYY-MM-DD, defaults to 30 days before `to`
+    pub to: Option<String>,                // YYYY-MM-DD (inclusive), defaults to today
+    pub interval: Option<String>,          // "day", "week" or "month"
+    pub subscription_type: Option<String>, // "worker" or "jobseeker"
+}
+
+fn analytics_range(query: &AnalyticsQuery) -> Result<AnalyticsRange, ApiError> {
+    let parse_date = |value: &str| {
+        chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| ApiError::bad_request(format!("Invalid date '{}', expected YYYY-MM-DD", value)))
+    };
+
+    let to = match query.to {
+        Some(ref to) => parse_date(to)?,
+        None => chrono::Utc::now().date_naive(),
+    };
+    let from = match query.from {
+        Some(ref from) => parse_date(from)?,
+        None => to - chrono::Duration::days(29),
+    };
+
+    if from > to {
+        return Err(ApiError::bad_request("'from' must not be after 'to'"));
+    }
+
+    let interval = match query.interval {
+        Some(ref interval) => Interval::parse(interval)
+            .ok_or_else(|| ApiError::bad_request("Invalid interval. Use 'day', 'week' or 'month'"))?,
+        None => Interval::Day,
+    };
+
+    if interval == Interval::Day && (to - from).num_days() > 366 {
+        return Err(ApiError::bad_request("Daily series are limited to one year. Use a weekly or monthly interval"));
+    }
+
+    let subscription_type = match query.subscription_type.as_deref().map(|t| t.to_lowercase()) {
+        None => None,
+        Some(t) if t == "worker" => Some("worker".to_string()),
+        Some(t) if t == "jobseeker" || t == "job_seeker" => Some("jobseeker".to_string()),
+        Some(_) => return Err(ApiError::bad_request("Invalid subscription type. Use 'worker' or 'jobseeker'")),
+    };
+
+    Ok(AnalyticsRange {
+        from,
+        to: to + chrono::Duration::days(1), // Exclusive upper bound
+        interval,
+        subscription_type,
+    })
+}
+
+fn range_json(query: &AnalyticsQuery, range: &AnalyticsRange) -> serde_json::Value {
+    serde_json::json!({
+        "from": range.from.to_string(),
+        "to": (range.to - chrono::Duration::days(1)).to_string(),
+        "interval": query.interval.clone().unwrap_or_else(|| "day".to_string()),
+        "subscription_type": range.subscription_type
+    })
+}
+
+#[openapi(tag = "Admin - Analytics")]
+#[get("/admin/analytics/summary?<query..>")]
+pub async fn get_analytics_summary(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: AnalyticsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let range = analytics_range(&query)?;
+
+    let summary = AnalyticsService::summary(db, &range)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Aggregation error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "range": range_json(&query, &range),
+        "summary": summary
+    }))))
+}
+
+#[openapi(tag = "Admin - Analytics")]
+#[get("/admin/analytics/revenue?<query..>")]
+pub async fn get_revenue_analytics(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: AnalyticsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let range = analytics_range(&query)?;
+
+    let series = AnalyticsService::revenue_series(db, &range)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Aggregation error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "range": range_json(&query, &range),
+        "series": series
+    }))))
+}
+
+#[openapi(tag = "Admin - Analytics")]
+#[get("/admin/analytics/revenue/breakdown?<query..>")]
+pub async fn get_revenue_breakdown(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: AnalyticsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let range = analytics_range(&query)?;
+
+    let breakdown = AnalyticsService::revenue_breakdown(db, &range)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Aggregation error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "range": range_json(&query, &range),
+        "by_plan": breakdown.get("by_plan"),
+        "by_type": breakdown.get("by_type")
+    }))))
+}
+
+#[openapi(tag = "Admin - Analytics")]
+#[get("/admin/analytics/subscribers?<query..>")]
+pub async fn get_subscriber_analytics(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: AnalyticsQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let range = analytics_range(&query)?;
+
+    let series = AnalyticsService::subscriber_series(db, &range)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Aggregation error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "range": range_json(&query, &range),
+        "series": series
+    }))))
+}
+
 // ==================== ADMIN SERVICES CRUD ROUTES ====================
 
 #[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
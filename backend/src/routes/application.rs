@@ -0,0 +1,746 @@
+use crate::db::{DbConn, is_duplicate_key};
+use crate::guards::AuthGuard;
+use crate::models::{
+    ApplicantListQuery, ApplicantNoteDto, ApplicationListQuery, ApplicationStatus,
+    ApplicationStatusChange, ApplyJobDto, JobApplication, JobPost, JobSeekerProfile, JobStatus,
+    JobSeekerProfileResponse, PosterNote, RateApplicantDto, ResumeSnapshot,
+    UpdateApplicationStatusDto, UpdatePipelineDto,
+};
+use crate::routes::notification::create_user_notification;
+use crate::services::{ApplicationService, EntitlementService};
+use crate::utils::{ApiError, ApiResponse};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, Document, doc};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use rocket::State;
+use rocket::futures::TryStreamExt;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+use std::collections::HashMap;
+
+const MAX_COVER_NOTE_CHARS: usize = 2000;
+const MAX_NOTE_CHARS: usize = 2000;
+
+// ============================================================================
+// SHARED APPLICATION LOGIC
+// ============================================================================
+
+async fn find_seeker_profile(db: &DbConn, user_id: ObjectId) -> Result<JobSeekerProfile, ApiError> {
+    db.collection::<JobSeekerProfile>("job_seeker_profiles")
+        .find_one(doc! { "user_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Create a job seeker profile before applying"))
+}
+
+/// Application belonging to the seeker
+async fn find_own_application(
+    db: &DbConn,
+    application_id: &str,
+    user_id: ObjectId,
+) -> Result<JobApplication, ApiError> {
+    let application_oid = ObjectId::parse_str(application_id)
+        .map_err(|_| ApiError::bad_request("Invalid application ID"))?;
+
+    db.collection::<JobApplication>("applications")
+        .find_one(doc! { "_id": application_oid, "seeker_user_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Application not found"))
+}
+
+/// Title, company and status of each job, for listing applications
+async fn job_summaries(db: &DbConn, job_ids: Vec<ObjectId>) -> Result<HashMap<ObjectId, serde_json::Value>, ApiError> {
+    let jobs: Vec<JobPost> = db
+        .collection::<JobPost>("jobs")
+        .find(doc! { "_id": { "$in": job_ids } }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    Ok(jobs
+        .into_iter()
+        .filter_map(|job| {
+            let id = job.id?;
+            Some((
+                id,
+                serde_json::json!({
+                    "id": id.to_hex(),
+                    "title": job.title,
+                    "company_name": job.company_name,
+                    "location": job.location,
+                    "status": job.status
+                }),
+            ))
+        })
+        .collect())
+}
+
+/// Application as the seeker sees it, without the poster's private notes and rating
+fn application_json(application: &JobApplication, job: Option<&serde_json::Value>) -> serde_json::Value {
+    let mut data = serde_json::json!(application);
+    if let Some(fields) = data.as_object_mut() {
+        fields.remove("poster_notes");
+        fields.remove("poster_rating");
+    }
+    data["job"] = job.cloned().unwrap_or(serde_json::Value::Null);
+    data
+}
+
+/// Job the user posted
+pub(crate) async fn find_poster_job(db: &DbConn, job_id: &str, user_id: ObjectId) -> Result<JobPost, ApiError> {
+    let job_oid = ObjectId::parse_str(job_id).map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+
+    db.collection::<JobPost>("jobs")
+        .find_one(doc! { "_id": job_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .filter(|job| job.posted_by == user_id)
+        .ok_or_else(|| ApiError::not_found("Job not found"))
+}
+
+pub(crate) async fn find_applicant(db: &DbConn, job: &JobPost, application_id: &str) -> Result<JobApplication, ApiError> {
+    let application_oid = ObjectId::parse_str(application_id)
+        .map_err(|_| ApiError::bad_request("Invalid application ID"))?;
+
+    db.collection::<JobApplication>("applications")
+        .find_one(doc! { "_id": application_oid, "job_id": job.id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Application not found"))
+}
+
+/// Move an application on from the status it was loaded with, recording the
+/// change in its history
+pub(crate) async fn change_status(
+    db: &DbConn,
+    application: &JobApplication,
+    to: ApplicationStatus,
+    note: Option<String>,
+) -> Result<JobApplication, ApiError> {
+    let now = DateTime::now();
+    let change = mongodb::bson::to_bson(&ApplicationStatusChange {
+        status: to,
+        note,
+        changed_at: now,
+    })
+    .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    let mut set = doc! { "status": to.as_str(), "updated_at": now };
+    if to == ApplicationStatus::Withdrawn {
+        set.insert("withdrawn_at", now);
+    }
+
+    let updated = db
+        .collection::<JobApplication>("applications")
+        .find_one_and_update(
+            doc! { "_id": application.id, "status": application.status.as_str() },
+            doc! { "$set": set, "$push": { "status_history": change } },
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request("Application was updated by someone else. Please refresh"))?;
+
+    sync(db, &updated).await?;
+    Ok(updated)
+}
+
+/// Turn a withdrawn application back into a live one
+async fn reapply(
+    db: &DbConn,
+    existing: &JobApplication,
+    cover_note: Option<String>,
+    resume: &ResumeSnapshot,
+    applied: &ApplicationStatusChange,
+    now: DateTime,
+) -> Result<JobApplication, ApiError> {
+    let resume_bson = mongodb::bson::to_bson(resume)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+    let applied_bson = mongodb::bson::to_bson(applied)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    db.collection::<JobApplication>("applications")
+        .find_one_and_update(
+            doc! { "_id": existing.id, "status": ApplicationStatus::Withdrawn.as_str() },
+            doc! {
+                "$set": {
+                    "status": ApplicationStatus::Applied.as_str(),
+                    "cover_note": cover_note,
+                    "resume": resume_bson,
+                    "applied_at": now,
+                    "withdrawn_at": null,
+                    "updated_at": now
+                },
+                "$push": { "status_history": applied_bson }
+            },
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request("You have already applied to this job"))
+}
+
+async fn insert_application(db: &DbConn, mut application: JobApplication) -> Result<JobApplication, ApiError> {
+    // The unique (job_id, seeker_profile_id) index rejects a concurrent duplicate
+    let res = db
+        .collection::<JobApplication>("applications")
+        .insert_one(&application, None)
+        .await
+        .map_err(|e| {
+            if is_duplicate_key(&e) {
+                ApiError::bad_request("You have already applied to this job")
+            } else {
+                ApiError::internal_error(format!("Failed to submit application: {}", e))
+            }
+        })?;
+    application.id = res.inserted_id.as_object_id();
+
+    Ok(application)
+}
+
+async fn sync(db: &DbConn, application: &JobApplication) -> Result<(), ApiError> {
+    ApplicationService::sync(db, application)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update application counts: {}", e)))
+}
+
+// ============================================================================
+// JOB SEEKER APPLICATION ENDPOINTS
+// ============================================================================
+
+/// Apply to an approved job. A withdrawn application can be submitted again.
+#[openapi(tag = "Applications")]
+#[post("/jobs/<job_id>/apply", data = "<dto>")]
+pub async fn apply_to_job(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    dto: Json<ApplyJobDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job_oid = ObjectId::parse_str(&job_id).map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+
+    let job = db
+        .collection::<JobPost>("jobs")
+        .find_one(doc! { "_id": job_oid, "status": JobStatus::Approved.as_str() }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job not found"))?;
+
+    if job.posted_by == auth.user_id {
+        return Err(ApiError::bad_request("You cannot apply to your own job"));
+    }
+
+    let profile = find_seeker_profile(db, auth.user_id).await?;
+    let profile_id = profile.id.ok_or_else(|| ApiError::internal_error("Profile missing ID"))?;
+    if !profile.is_available {
+        return Err(ApiError::bad_request("Reactivate your job seeker profile to apply"));
+    }
+
+    let cover_note = dto
+        .cover_note
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string);
+    if cover_note.as_ref().is_some_and(|n| n.chars().count() > MAX_COVER_NOTE_CHARS) {
+        return Err(ApiError::bad_request(format!(
+            "Cover note must be at most {} characters",
+            MAX_COVER_NOTE_CHARS
+        )));
+    }
+
+    let existing = db
+        .collection::<JobApplication>("applications")
+        .find_one(doc! { "job_id": job_oid, "seeker_profile_id": profile_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let now = DateTime::now();
+    let resume = ResumeSnapshot::from(&profile);
+    let applied = ApplicationStatusChange {
+        status: ApplicationStatus::Applied,
+        note: None,
+        changed_at: now,
+    };
+
+    if existing.as_ref().is_some_and(|a| a.status != ApplicationStatus::Withdrawn) {
+        return Err(ApiError::bad_request("You have already applied to this job"));
+    }
+
+    // Applying is a job seeker plan entitlement, given back if the application isn't saved
+    EntitlementService::consume(db, auth.user_id, "job_applications").await?;
+
+    let submitted = match existing {
+        Some(existing) => reapply(db, &existing, cover_note, &resume, &applied, now).await,
+        None => {
+            insert_application(
+                db,
+                JobApplication {
+                    id: None,
+                    job_id: job_oid,
+                    seeker_profile_id: profile_id,
+                    seeker_user_id: auth.user_id,
+                    cover_note,
+                    resume,
+                    status: ApplicationStatus::Applied,
+                    status_history: vec![applied],
+                    poster_notes: Vec::new(),
+                    poster_rating: None,
+                    applied_at: now,
+                    withdrawn_at: None,
+                    updated_at: now,
+                },
+            )
+            .await
+        }
+    };
+    let application = match submitted {
+        Ok(application) => application,
+        Err(e) => {
+            let _ = EntitlementService::release(db, auth.user_id, "job_applications").await;
+            return Err(e);
+        }
+    };
+
+    sync(db, &application).await?;
+
+    let _ = create_user_notification(
+        db,
+        job.posted_by,
+        "job_application".to_string(),
+        "New Application".to_string(),
+        format!("{} applied for your job \"{}\".", profile.full_name, job.title),
+        application.id,
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Application submitted".to_string(),
+        application_json(&application, None),
+    )))
+}
+
+#[openapi(tag = "Applications")]
+#[get("/job-seeker/applications?<query..>")]
+pub async fn list_my_applications(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: ApplicationListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! { "seeker_user_id": auth.user_id };
+    if let Some(status) = query.status {
+        filter.insert("status", status);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "applied_at": -1 })
+        .build();
+
+    let applications: Vec<JobApplication> = db
+        .collection::<JobApplication>("applications")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let total = db
+        .collection::<JobApplication>("applications")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    let jobs = job_summaries(db, applications.iter().map(|a| a.job_id).collect()).await?;
+    let applications: Vec<serde_json::Value> = applications
+        .iter()
+        .map(|a| application_json(a, jobs.get(&a.job_id)))
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "applications": applications,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Applications")]
+#[get("/job-seeker/applications/<application_id>")]
+pub async fn get_my_application(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    application_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let application = find_own_application(db, &application_id, auth.user_id).await?;
+    let jobs = job_summaries(db, vec![application.job_id]).await?;
+
+    Ok(Json(ApiResponse::success(application_json(
+        &application,
+        jobs.get(&application.job_id),
+    ))))
+}
+
+#[openapi(tag = "Applications")]
+#[put("/job-seeker/applications/<application_id>/withdraw")]
+pub async fn withdraw_application(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    application_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let application = find_own_application(db, &application_id, auth.user_id).await?;
+    if application.status.is_closed() {
+        return Err(ApiError::bad_request(format!(
+            "This application is {} and can no longer be withdrawn",
+            application.status.as_str()
+        )));
+    }
+
+    let application = change_status(db, &application, ApplicationStatus::Withdrawn, None).await?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Application withdrawn".to_string(),
+        application_json(&application, None),
+    )))
+}
+
+// ============================================================================
+// APPLICANT TRACKING (JOB POSTER)
+// ============================================================================
+
+#[openapi(tag = "Applications")]
+#[get("/jobs/<job_id>/applicants?<query..>")]
+pub async fn list_applicants(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    query: ApplicantListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_poster_job(db, &job_id, auth.user_id).await?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! { "job_id": job.id };
+    match query.status {
+        Some(status) => {
+            filter.insert("status", status);
+        }
+        None => {
+            filter.insert("status", doc! { "$ne": ApplicationStatus::Withdrawn.as_str() });
+        }
+    }
+    if let Some(skill) = query.skill.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        filter.insert(
+            "resume.skills",
+            doc! { "$regex": format!("^{}$", regex::escape(skill)), "$options": "i" },
+        );
+    }
+    if let Some(min_experience) = query.min_experience {
+        filter.insert("resume.experience_years", doc! { "$gte": min_experience });
+    }
+    if let Some(min_rating) = query.min_rating {
+        filter.insert("poster_rating", doc! { "$gte": min_rating });
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "applied_at": -1 })
+        .build();
+
+    let applications: Vec<JobApplication> = db
+        .collection::<JobApplication>("applications")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let total = db
+        .collection::<JobApplication>("applications")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    // Applicants at each stage, for the pipeline view
+    let counts: Vec<Document> = db
+        .collection::<JobApplication>("applications")
+        .aggregate(
+            vec![
+                doc! { "$match": { "job_id": job.id } },
+                doc! { "$group": { "_id": "$status", "count": { "$sum": 1 } } },
+            ],
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+    let by_status: serde_json::Map<String, serde_json::Value> = counts
+        .iter()
+        .filter_map(|c| {
+            let status = c.get_str("_id").ok()?;
+            Some((status.to_string(), serde_json::json!(c.get_i32("count").ok()?)))
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "applicants": applications,
+        "by_status": by_status,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+/// An application with the applicant's current profile
+#[openapi(tag = "Applications")]
+#[get("/jobs/<job_id>/applicants/<application_id>")]
+pub async fn get_applicant(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    application_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_poster_job(db, &job_id, auth.user_id).await?;
+    let application = find_applicant(db, &job, &application_id).await?;
+
+    let profile = db
+        .collection::<JobSeekerProfile>("job_seeker_profiles")
+        .find_one(doc! { "_id": application.seeker_profile_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .map(JobSeekerProfileResponse::from);
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "application": application,
+        "profile": profile
+    }))))
+}
+
+#[openapi(tag = "Applications")]
+#[put("/jobs/<job_id>/applicants/<application_id>/status", data = "<dto>")]
+pub async fn update_applicant_status(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    application_id: String,
+    dto: Json<UpdateApplicationStatusDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_poster_job(db, &job_id, auth.user_id).await?;
+    let application = find_applicant(db, &job, &application_id).await?;
+
+    if !application.status.can_move_to(dto.status, job.pipeline()) {
+        return Err(ApiError::bad_request(format!(
+            "An application that is {} cannot be moved to {}",
+            application.status.as_str(),
+            dto.status.as_str()
+        )));
+    }
+
+    let note = dto.note.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(str::to_string);
+    if note.as_ref().is_some_and(|n| n.chars().count() > MAX_NOTE_CHARS) {
+        return Err(ApiError::bad_request(format!("Note must be at most {} characters", MAX_NOTE_CHARS)));
+    }
+
+    let updated = change_status(db, &application, dto.status, note.clone()).await?;
+
+    let (title, body) = match dto.status {
+        ApplicationStatus::Shortlisted => (
+            "You've Been Shortlisted",
+            format!("You've been shortlisted for \"{}\" at {}.", job.title, job.company_name),
+        ),
+        ApplicationStatus::Interview => (
+            "Interview Stage",
+            format!("Your application for \"{}\" has moved to the interview stage.", job.title),
+        ),
+        ApplicationStatus::Offered => (
+            "Job Offer",
+            format!("{} would like to offer you the \"{}\" role!", job.company_name, job.title),
+        ),
+        ApplicationStatus::Hired => (
+            "You're Hired",
+            format!("Congratulations! You've been hired for \"{}\" at {}.", job.title, job.company_name),
+        ),
+        _ => (
+            "Application Update",
+            format!("Your application for \"{}\" at {} was not taken forward.", job.title, job.company_name),
+        ),
+    };
+    let body = match note {
+        Some(note) => format!("{} {}", body, note),
+        None => body,
+    };
+    let _ = create_user_notification(
+        db,
+        application.seeker_user_id,
+        "job_application_update".to_string(),
+        title.to_string(),
+        body,
+        updated.id,
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success_with_message(
+        format!("Applicant moved to {}", dto.status.as_str()),
+        serde_json::json!(updated),
+    )))
+}
+
+/// Private note on an applicant, never shown to them
+#[openapi(tag = "Applications")]
+#[post("/jobs/<job_id>/applicants/<application_id>/notes", data = "<dto>")]
+pub async fn add_applicant_note(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    application_id: String,
+    dto: Json<ApplicantNoteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_poster_job(db, &job_id, auth.user_id).await?;
+    let application = find_applicant(db, &job, &application_id).await?;
+
+    let body = dto.body.trim();
+    if body.is_empty() {
+        return Err(ApiError::bad_request("Note is empty"));
+    }
+    if body.chars().count() > MAX_NOTE_CHARS {
+        return Err(ApiError::bad_request(format!("Note must be at most {} characters", MAX_NOTE_CHARS)));
+    }
+
+    let note = PosterNote {
+        body: body.to_string(),
+        created_at: DateTime::now(),
+    };
+    let note_bson = mongodb::bson::to_bson(&note)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    db.collection::<JobApplication>("applications")
+        .update_one(
+            doc! { "_id": application.id },
+            doc! { "$push": { "poster_notes": note_bson } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to save note: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Note added".to_string(),
+        serde_json::json!(note),
+    )))
+}
+
+#[openapi(tag = "Applications")]
+#[put("/jobs/<job_id>/applicants/<application_id>/rating", data = "<dto>")]
+pub async fn rate_applicant(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    application_id: String,
+    dto: Json<RateApplicantDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_poster_job(db, &job_id, auth.user_id).await?;
+    let application = find_applicant(db, &job, &application_id).await?;
+
+    if !(1..=5).contains(&dto.rating) {
+        return Err(ApiError::bad_request("Rating must be between 1 and 5"));
+    }
+
+    db.collection::<JobApplication>("applications")
+        .update_one(
+            doc! { "_id": application.id },
+            doc! { "$set": { "poster_rating": dto.rating } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to save rating: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Rating saved".to_string(),
+        serde_json::json!({ "application_id": application_id, "poster_rating": dto.rating }),
+    )))
+}
+
+/// Choose which stages this job's applicants go through between applied and hired
+#[openapi(tag = "Applications")]
+#[put("/jobs/<job_id>/pipeline", data = "<dto>")]
+pub async fn update_pipeline(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    dto: Json<UpdatePipelineDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_poster_job(db, &job_id, auth.user_id).await?;
+
+    for (i, stage) in dto.stages.iter().enumerate() {
+        if !stage.is_pipeline_stage() {
+            return Err(ApiError::bad_request(format!(
+                "'{}' cannot be a pipeline stage. Use shortlisted, interview or offered",
+                stage.as_str()
+            )));
+        }
+        if dto.stages[..i].contains(stage) {
+            return Err(ApiError::bad_request(format!("'{}' is listed twice", stage.as_str())));
+        }
+    }
+
+    // Applicants can't be left in a stage the job no longer has
+    let removed: Vec<&str> = job
+        .pipeline()
+        .iter()
+        .filter(|s| !dto.stages.contains(s))
+        .map(|s| s.as_str())
+        .collect();
+    if !removed.is_empty() {
+        let stranded = db
+            .collection::<JobApplication>("applications")
+            .find_one(doc! { "job_id": job.id, "status": { "$in": &removed } }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+        if let Some(application) = stranded {
+            return Err(ApiError::bad_request(format!(
+                "Move applicants out of {} before removing that stage",
+                application.status.as_str()
+            )));
+        }
+    }
+
+    let stages = mongodb::bson::to_bson(&dto.stages)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+    db.collection::<JobPost>("jobs")
+        .update_one(
+            doc! { "_id": job.id },
+            doc! { "$set": { "pipeline": stages, "updatedAt": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to save pipeline: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Pipeline updated".to_string(),
+        serde_json::json!({ "job_id": job_id, "pipeline": dto.stages }),
+    )))
+}
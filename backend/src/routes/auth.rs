@@ -195,6 +195,7 @@ pub async fn verify_otp(
                 subscription_id: None,
                 subscription_plan: None,
                 subscription_expires_at: None,
+                reliability: None,
                 kyc_status: KycStatus::Pending,
                 is_active: true,
                 fcm_token: None,
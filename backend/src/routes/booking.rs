@@ -0,0 +1,861 @@
+use crate::db::DbConn;
+use crate::guards::{AdminGuard, AuthGuard};
+use crate::models::{
+    Booking, BookingListQuery, BookingReasonDto, BookingStatus, CancellationPolicy,
+    CancellationPolicyDto, CompleteBookingDto, CompletionConfirmation, CompletionProof,
+    ContestCompletionDto, CreateBookingDto, Dispute, DisputeCategory, DisputeStatus, GeoLocation,
+    NoShowReport, NoShowReportDto, WorkerProfile,
+};
+use crate::services::{AuditService, AvailabilityService, BookingService, DisputeService};
+use crate::services::entitlement::{EntitlementError, EntitlementService};
+use crate::utils::time::{
+    DAY_MILLIS, HOUR_MILLIS, MINUTE_MILLIS, add_millis, format_datetime, parse_rfc3339,
+};
+use crate::utils::{ApiError, ApiResponse, is_uploaded_file};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, Document, doc};
+use mongodb::options::{FindOptions, ReplaceOptions};
+use rocket::State;
+use rocket::futures::TryStreamExt;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+/// How far ahead a booking can be requested
+const MAX_BOOKING_DAYS_AHEAD: i64 = 90;
+/// Per list, for completion photos and contest evidence
+const MAX_COMPLETION_PHOTOS: usize = 10;
+
+// ============================================================================
+// SHARED BOOKING LOGIC
+// ============================================================================
+
+#[derive(PartialEq)]
+enum Party {
+    Customer,
+    Worker,
+}
+
+/// Load a booking the user takes part in, and which side they are on
+async fn find_booking(db: &DbConn, booking_id: &str, user_id: ObjectId) -> Result<(Booking, Party), ApiError> {
+    let booking_oid = ObjectId::parse_str(booking_id)
+        .map_err(|_| ApiError::bad_request("Invalid booking ID"))?;
+
+    let booking = db
+        .collection::<Booking>("bookings")
+        .find_one(doc! { "_id": booking_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Booking not found"))?;
+
+    let party = if booking.customer_id == user_id {
+        Party::Customer
+    } else if booking.worker_user_id == user_id {
+        Party::Worker
+    } else {
+        return Err(ApiError::not_found("Booking not found"));
+    };
+
+    Ok((booking, party))
+}
+
+/// Validate a requested time slot against booking limits and the worker's calendar
+pub(crate) async fn check_schedule(
+    db: &DbConn,
+    worker: &WorkerProfile,
+    scheduled_at: &str,
+    duration_minutes: Option<i32>,
+) -> Result<(DateTime, i32), ApiError> {
+    let scheduled_at = parse_rfc3339(scheduled_at)
+        .ok_or_else(|| ApiError::bad_request("Invalid scheduled_at, expected an RFC 3339 timestamp"))?;
+    let now = DateTime::now();
+    if scheduled_at <= now {
+        return Err(ApiError::bad_request("Booking time must be in the future"));
+    }
+    if scheduled_at.timestamp_millis() - now.timestamp_millis() > MAX_BOOKING_DAYS_AHEAD * DAY_MILLIS {
+        return Err(ApiError::bad_request(format!(
+            "Bookings can be made at most {} days ahead",
+            MAX_BOOKING_DAYS_AHEAD
+        )));
+    }
+
+    let duration_minutes = duration_minutes.unwrap_or(60);
+    if !(15..=12 * 60).contains(&duration_minutes) {
+        return Err(ApiError::bad_request("Duration must be between 15 minutes and 12 hours"));
+    }
+
+    if let Some(reason) = AvailabilityService::unavailable_reason(db, worker, scheduled_at, duration_minutes)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+    {
+        return Err(ApiError::bad_request(reason));
+    }
+
+    Ok((scheduled_at, duration_minutes))
+}
+
+/// Apply a status change on behalf of `user_id`, who must be on the `party` side.
+/// `extra` holds further fields to set with the change.
+#[allow(clippy::too_many_arguments)]
+async fn change_status(
+    db: &DbConn,
+    booking_id: &str,
+    user_id: ObjectId,
+    party: Party,
+    from: &[BookingStatus],
+    to: BookingStatus,
+    reason: Option<String>,
+    mut extra: Document,
+) -> Result<serde_json::Value, ApiError> {
+    let (booking, actual_party) = find_booking(db, booking_id, user_id).await?;
+
+    if actual_party != party {
+        let who = if party == Party::Worker { "worker" } else { "customer" };
+        return Err(ApiError::forbidden(format!("Only the {} can do this", who)));
+    }
+
+    if !from.contains(&booking.status) {
+        return Err(ApiError::bad_request(format!(
+            "Booking is {} and cannot be moved to {}",
+            booking.status.as_str(),
+            to.as_str()
+        )));
+    }
+
+    let reason = reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    if let Some(ref reason) = reason
+        && matches!(to, BookingStatus::Cancelled | BookingStatus::Declined)
+    {
+        extra.insert("cancellation_reason", reason);
+    }
+
+    let updated = BookingService::transition(
+        db,
+        booking.id.ok_or_else(|| ApiError::internal_error("Booking missing ID"))?,
+        user_id,
+        from,
+        to,
+        reason,
+        extra,
+    )
+    .await
+    .map_err(|e| ApiError::internal_error(format!("Failed to update booking: {}", e)))?
+    .ok_or_else(|| ApiError::bad_request("Booking was updated by someone else. Please refresh"))?;
+
+    serde_json::to_value(&updated)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))
+}
+
+// ============================================================================
+// BOOKING ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Booking")]
+#[post("/booking", data = "<dto>")]
+pub async fn create_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CreateBookingDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker_oid = ObjectId::parse_str(&dto.worker_id)
+        .map_err(|_| ApiError::bad_request("Invalid worker ID"))?;
+
+    let worker = db
+        .collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "_id": worker_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker not found"))?;
+
+    if worker.user_id == auth.user_id {
+        return Err(ApiError::bad_request("You cannot book yourself"));
+    }
+    if !worker.is_verified || !worker.is_available {
+        return Err(ApiError::bad_request("This worker is not accepting bookings right now"));
+    }
+    if !worker.subcategories.contains(&dto.subcategory) {
+        return Err(ApiError::bad_request("This worker does not offer the selected service"));
+    }
+
+    let address = dto.address.trim();
+    if address.is_empty() {
+        return Err(ApiError::bad_request("Address is required"));
+    }
+    if !(-90.0..=90.0).contains(&dto.latitude) || !(-180.0..=180.0).contains(&dto.longitude) {
+        return Err(ApiError::bad_request("Invalid location"));
+    }
+
+    let (scheduled_at, duration_minutes) =
+        check_schedule(db, &worker, &dto.scheduled_at, dto.duration_minutes).await?;
+    let now = DateTime::now();
+
+    // Receiving bookings is a worker plan entitlement
+    match EntitlementService::consume(db, worker.user_id, "booking_requests").await {
+        Ok(_) => {}
+        Err(EntitlementError::Database(e)) => {
+            return Err(ApiError::internal_error(format!("Database error: {}", e)));
+        }
+        Err(_) => {
+            return Err(ApiError::bad_request("This worker is not accepting bookings right now"));
+        }
+    }
+
+    let booking = Booking {
+        id: None,
+        customer_id: auth.user_id,
+        worker_id: worker_oid,
+        worker_user_id: worker.user_id,
+        category: dto.category.clone(),
+        subcategory: dto.subcategory.clone(),
+        address: address.to_string(),
+        location: GeoLocation {
+            geo_type: String::from("Point"),
+            coordinates: [dto.longitude, dto.latitude],
+        },
+        scheduled_at,
+        duration_minutes,
+        notes: dto.notes.clone(),
+        quote_id: None,
+        service_request_id: None,
+        status: BookingStatus::Requested,
+        status_history: vec![crate::models::BookingStatusChange {
+            status: BookingStatus::Requested,
+            changed_by: auth.user_id,
+            note: None,
+            at: now,
+        }],
+        cancellation_reason: None,
+        cancelled_by: None,
+        late_cancellation: false,
+        no_show: None,
+        completion: None,
+        accepted_at: None,
+        started_at: None,
+        completed_at: None,
+        cancelled_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let booking = match BookingService::create(db, booking).await {
+        Ok(booking) => booking,
+        Err(e) => {
+            let _ = EntitlementService::release(db, worker.user_id, "booking_requests").await;
+            return Err(ApiError::internal_error(format!("Failed to create booking: {}", e)));
+        }
+    };
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Booking requested".to_string(),
+        serde_json::json!({
+            "booking_id": booking.id.map(|id| id.to_hex()),
+            "status": booking.status
+        }),
+    )))
+}
+
+#[openapi(tag = "Booking")]
+#[get("/booking/list?<query..>")]
+pub async fn list_bookings(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: BookingListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = match query.role.as_deref().unwrap_or("customer") {
+        "customer" => doc! { "customer_id": auth.user_id },
+        "worker" => doc! { "worker_user_id": auth.user_id },
+        _ => return Err(ApiError::bad_request("Invalid role. Use 'customer' or 'worker'")),
+    };
+    if let Some(ref status) = query.status {
+        filter.insert("status", status);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "scheduled_at": -1 })
+        .build();
+
+    let bookings: Vec<Booking> = db
+        .collection::<Booking>("bookings")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let total = db
+        .collection::<Booking>("bookings")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "bookings": bookings,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Booking")]
+#[get("/booking/<booking_id>")]
+pub async fn get_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (booking, party) = find_booking(db, &booking_id, auth.user_id).await?;
+
+    // Each side sees the other party's name and phone once the booking is accepted
+    let counterpart_id = if party == Party::Customer {
+        booking.worker_user_id
+    } else {
+        booking.customer_id
+    };
+    let show_contact = matches!(
+        booking.status,
+        BookingStatus::Accepted | BookingStatus::InProgress | BookingStatus::Completed
+    );
+
+    let counterpart = db
+        .collection::<crate::models::User>("users")
+        .find_one(doc! { "_id": counterpart_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .map(|u| {
+            serde_json::json!({
+                "name": u.name,
+                "mobile": if show_contact { Some(u.mobile) } else { None },
+                // Workers see the customer's track record
+                "reliability": if party == Party::Worker { u.reliability } else { None }
+            })
+        });
+
+    let mut data = serde_json::to_value(&booking)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+    if let Some(obj) = data.as_object_mut() {
+        obj.insert(
+            "role".to_string(),
+            serde_json::json!(if party == Party::Customer { "customer" } else { "worker" }),
+        );
+        obj.insert("counterpart".to_string(), serde_json::json!(counterpart));
+    }
+
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "Booking")]
+#[put("/booking/<booking_id>/accept")]
+pub async fn accept_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = change_status(
+        db,
+        &booking_id,
+        auth.user_id,
+        Party::Worker,
+        &[BookingStatus::Requested],
+        BookingStatus::Accepted,
+        None,
+        doc! {},
+    )
+    .await?;
+    Ok(Json(ApiResponse::success_with_message("Booking accepted".to_string(), data)))
+}
+
+#[openapi(tag = "Booking")]
+#[put("/booking/<booking_id>/decline", data = "<dto>")]
+pub async fn decline_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+    dto: Json<BookingReasonDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = change_status(
+        db,
+        &booking_id,
+        auth.user_id,
+        Party::Worker,
+        &[BookingStatus::Requested],
+        BookingStatus::Declined,
+        dto.reason.clone(),
+        doc! {},
+    )
+    .await?;
+    Ok(Json(ApiResponse::success_with_message("Booking declined".to_string(), data)))
+}
+
+#[openapi(tag = "Booking")]
+#[put("/booking/<booking_id>/start")]
+pub async fn start_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = change_status(
+        db,
+        &booking_id,
+        auth.user_id,
+        Party::Worker,
+        &[BookingStatus::Accepted],
+        BookingStatus::InProgress,
+        None,
+        doc! {},
+    )
+    .await?;
+    Ok(Json(ApiResponse::success_with_message("Booking started".to_string(), data)))
+}
+
+/// Worker marks the job done with before/after photos. The customer then has
+/// a window to confirm or contest before it is confirmed automatically.
+#[openapi(tag = "Booking")]
+#[put("/booking/<booking_id>/complete", data = "<dto>")]
+pub async fn complete_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+    dto: Json<CompleteBookingDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let before_photos = dto.before_photos.clone().unwrap_or_default();
+    let after_photos = dto.after_photos.clone();
+    if after_photos.is_empty() {
+        return Err(ApiError::bad_request("Add at least one photo of the finished job"));
+    }
+    if before_photos.len() > MAX_COMPLETION_PHOTOS || after_photos.len() > MAX_COMPLETION_PHOTOS {
+        return Err(ApiError::bad_request(format!(
+            "At most {} before and {} after photos",
+            MAX_COMPLETION_PHOTOS, MAX_COMPLETION_PHOTOS
+        )));
+    }
+    if let Some(url) = before_photos
+        .iter()
+        .chain(&after_photos)
+        .find(|url| !is_uploaded_file(url, "images"))
+    {
+        return Err(ApiError::bad_request(format!(
+            "'{}' is not an uploaded image. Upload it via /upload/image first",
+            url
+        )));
+    }
+    let note = dto.note.trim();
+    if note.is_empty() {
+        return Err(ApiError::bad_request("Please describe the work done"));
+    }
+
+    let now = DateTime::now();
+    let proof = mongodb::bson::to_bson(&CompletionProof {
+        before_photos,
+        after_photos,
+        note: note.to_string(),
+        submitted_at: now,
+        confirm_by: add_millis(now, crate::config::Config::completion_confirm_hours() * HOUR_MILLIS),
+        confirmation: CompletionConfirmation::Pending,
+        confirmed_at: None,
+        dispute_id: None,
+    })
+    .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    let data = change_status(
+        db,
+        &booking_id,
+        auth.user_id,
+        Party::Worker,
+        &[BookingStatus::InProgress],
+        BookingStatus::Completed,
+        None,
+        doc! { "completion": proof },
+    )
+    .await?;
+    Ok(Json(ApiResponse::success_with_message(
+        "Booking completed. Waiting for the customer to confirm".to_string(),
+        data,
+    )))
+}
+
+/// Customer signs off a completed job
+#[openapi(tag = "Booking")]
+#[put("/booking/<booking_id>/confirm-completion")]
+pub async fn confirm_completion(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (booking, party) = find_booking(db, &booking_id, auth.user_id).await?;
+    if party != Party::Customer {
+        return Err(ApiError::forbidden("Only the customer can do this"));
+    }
+    let booking_oid = booking.id.ok_or_else(|| ApiError::internal_error("Booking missing ID"))?;
+
+    let confirmed = BookingService::confirm_completion(
+        db,
+        booking_oid,
+        &[CompletionConfirmation::Pending],
+        CompletionConfirmation::Confirmed,
+    )
+    .await
+    .map_err(|e| ApiError::internal_error(format!("Failed to confirm completion: {}", e)))?;
+    if !confirmed {
+        return Err(ApiError::bad_request("This booking is not awaiting your confirmation"));
+    }
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Thanks for confirming!".to_string(),
+        serde_json::json!({ "booking_id": booking_id, "confirmation": CompletionConfirmation::Confirmed }),
+    )))
+}
+
+/// Customer disputes a completed job. Opens a service-quality dispute; the job
+/// only counts for the worker if support rejects it.
+#[openapi(tag = "Booking")]
+#[put("/booking/<booking_id>/contest-completion", data = "<dto>")]
+pub async fn contest_completion(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+    dto: Json<ContestCompletionDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (booking, party) = find_booking(db, &booking_id, auth.user_id).await?;
+    if party != Party::Customer {
+        return Err(ApiError::forbidden("Only the customer can do this"));
+    }
+    let booking_oid = booking.id.ok_or_else(|| ApiError::internal_error("Booking missing ID"))?;
+
+    let now = DateTime::now();
+    match booking.completion {
+        Some(ref completion) if completion.confirmation == CompletionConfirmation::Pending => {
+            if now > completion.confirm_by {
+                return Err(ApiError::bad_request("The window to contest this job has passed"));
+            }
+        }
+        _ => return Err(ApiError::bad_request("This booking is not awaiting your confirmation")),
+    }
+
+    let reason = dto.reason.trim();
+    if reason.chars().count() < 20 {
+        return Err(ApiError::bad_request("Please describe the problem in at least 20 characters"));
+    }
+    let evidence = dto.evidence.clone().unwrap_or_default();
+    if evidence.len() > MAX_COMPLETION_PHOTOS {
+        return Err(ApiError::bad_request(format!("At most {} evidence files", MAX_COMPLETION_PHOTOS)));
+    }
+    if let Some(url) = evidence
+        .iter()
+        .find(|url| !is_uploaded_file(url, "images") && !is_uploaded_file(url, "documents"))
+    {
+        return Err(ApiError::bad_request(format!("'{}' is not an uploaded file", url)));
+    }
+
+    // Claim the completion first so a concurrent confirm or auto-confirm can't win
+    let claimed = db
+        .collection::<Booking>("bookings")
+        .update_one(
+            doc! { "_id": booking_oid, "completion.confirmation": "pending" },
+            doc! { "$set": { "completion.confirmation": "contested", "updated_at": now } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    if claimed.modified_count == 0 {
+        return Err(ApiError::bad_request("This booking is not awaiting your confirmation"));
+    }
+
+    let (first_response_due_at, resolution_due_at) = DisputeService::sla_deadlines(now);
+    let opened = DisputeService::open(
+        db,
+        Dispute {
+            id: None,
+            raised_by: auth.user_id,
+            against_user_id: Some(booking.worker_user_id),
+            booking_id: Some(booking_oid),
+            subscription_id: None,
+            category: DisputeCategory::ServiceQuality,
+            subject: format!("Completion contested: {}", booking.subcategory),
+            description: reason.to_string(),
+            evidence,
+            status: DisputeStatus::Open,
+            assigned_admin_id: None,
+            first_response_due_at,
+            resolution_due_at,
+            first_responded_at: None,
+            sla_breached: false,
+            resolution: None,
+            created_at: now,
+            updated_at: now,
+        },
+    )
+    .await;
+    let dispute = match opened {
+        Ok(dispute) => dispute,
+        Err(e) => {
+            // Hand the completion back so the customer can retry or auto-confirm still applies
+            let _ = db
+                .collection::<Booking>("bookings")
+                .update_one(
+                    doc! { "_id": booking_oid, "completion.confirmation": "contested" },
+                    doc! { "$set": { "completion.confirmation": "pending", "updated_at": DateTime::now() } },
+                    None,
+                )
+                .await;
+            return Err(ApiError::internal_error(format!("Failed to create dispute: {}", e)));
+        }
+    };
+
+    db.collection::<Booking>("bookings")
+        .update_one(
+            doc! { "_id": booking_oid },
+            doc! { "$set": { "completion.dispute_id": dispute.id } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "We've opened a dispute. Our support team will review the job".to_string(),
+        serde_json::json!({ "booking_id": booking_id, "dispute": dispute }),
+    )))
+}
+
+/// Cancelling an accepted booking inside the category's window counts as a late
+/// cancellation against the canceller's reliability score, and needs a reason
+#[openapi(tag = "Booking")]
+#[put("/booking/<booking_id>/cancel", data = "<dto>")]
+pub async fn cancel_booking(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+    dto: Json<BookingReasonDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (booking, party) = find_booking(db, &booking_id, auth.user_id).await?;
+
+    let policy = BookingService::cancellation_policy(db, booking.category.as_deref())
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    let late = BookingService::is_late_cancellation(&booking, &policy, party == Party::Customer);
+
+    if late && dto.reason.as_deref().is_none_or(|r| r.trim().is_empty()) {
+        return Err(ApiError::bad_request("Please give a reason for cancelling this close to the booking"));
+    }
+
+    let data = change_status(
+        db,
+        &booking_id,
+        auth.user_id,
+        party,
+        &[BookingStatus::Requested, BookingStatus::Accepted],
+        BookingStatus::Cancelled,
+        dto.reason.clone(),
+        doc! { "late_cancellation": late },
+    )
+    .await?;
+
+    let message = if late {
+        "Booking cancelled. This was a late cancellation and affects your reliability score"
+    } else {
+        "Booking cancelled"
+    };
+    Ok(Json(ApiResponse::success_with_message(message.to_string(), data)))
+}
+
+#[openapi(tag = "Booking")]
+#[get("/booking/<booking_id>/cancellation-policy")]
+pub async fn get_cancellation_policy(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (booking, party) = find_booking(db, &booking_id, auth.user_id).await?;
+
+    let policy = BookingService::cancellation_policy(db, booking.category.as_deref())
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "policy": policy,
+        "late_if_cancelled_now": BookingService::is_late_cancellation(&booking, &policy, party == Party::Customer),
+        "no_show_reportable_at": add_millis(booking.scheduled_at, policy.no_show_grace_minutes * MINUTE_MILLIS)
+    }))))
+}
+
+/// Report that the other party didn't turn up to an accepted booking. Contested
+/// reports go through a dispute.
+#[openapi(tag = "Booking")]
+#[put("/booking/<booking_id>/no-show", data = "<dto>")]
+pub async fn report_no_show(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+    dto: Json<NoShowReportDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (booking, party) = find_booking(db, &booking_id, auth.user_id).await?;
+
+    let policy = BookingService::cancellation_policy(db, booking.category.as_deref())
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    let reportable_at = add_millis(booking.scheduled_at, policy.no_show_grace_minutes * MINUTE_MILLIS);
+    if DateTime::now() < reportable_at {
+        return Err(ApiError::bad_request(format!(
+            "A no-show can be reported from {}",
+            format_datetime(reportable_at)
+        )));
+    }
+
+    let evidence = dto.evidence.clone().unwrap_or_default();
+    if evidence.len() > 5 {
+        return Err(ApiError::bad_request("At most 5 evidence files"));
+    }
+    if let Some(url) = evidence
+        .iter()
+        .find(|url| !is_uploaded_file(url, "images") && !is_uploaded_file(url, "documents"))
+    {
+        return Err(ApiError::bad_request(format!("'{}' is not an uploaded file", url)));
+    }
+
+    let note = dto.note.as_deref().map(str::trim).filter(|n| !n.is_empty()).map(str::to_string);
+    let report = mongodb::bson::to_bson(&NoShowReport {
+        absent_party: if party == Party::Customer { "worker" } else { "customer" }.to_string(),
+        reported_by: auth.user_id,
+        note: note.clone(),
+        evidence,
+        reported_at: DateTime::now(),
+    })
+    .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    let data = change_status(
+        db,
+        &booking_id,
+        auth.user_id,
+        party,
+        &[BookingStatus::Accepted],
+        BookingStatus::NoShow,
+        note,
+        doc! { "no_show": report },
+    )
+    .await?;
+    Ok(Json(ApiResponse::success_with_message("No-show reported".to_string(), data)))
+}
+
+// ============================================================================
+// ADMIN CANCELLATION POLICIES
+// ============================================================================
+
+#[openapi(tag = "Admin - Cancellation Policies")]
+#[get("/admin/cancellation-policies")]
+pub async fn list_cancellation_policies(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let policies: Vec<CancellationPolicy> = db
+        .collection::<CancellationPolicy>("cancellation_policies")
+        .find(doc! {}, FindOptions::builder().sort(doc! { "category": 1 }).build())
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let defaults = BookingService::cancellation_policy(db, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "policies": policies,
+        "defaults": defaults
+    }))))
+}
+
+#[openapi(tag = "Admin - Cancellation Policies")]
+#[put("/admin/cancellation-policies/<category>", data = "<dto>")]
+pub async fn upsert_cancellation_policy(
+    db: &State<DbConn>,
+    admin: AdminGuard,
+    category: String,
+    dto: Json<CancellationPolicyDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    if !(0..=7 * 24).contains(&dto.customer_window_hours) || !(0..=7 * 24).contains(&dto.worker_window_hours) {
+        return Err(ApiError::bad_request("Cancellation windows must be between 0 and 168 hours"));
+    }
+    if !(0..=4 * 60).contains(&dto.no_show_grace_minutes) {
+        return Err(ApiError::bad_request("No-show grace must be between 0 and 240 minutes"));
+    }
+
+    let policy = CancellationPolicy {
+        category: category.trim().to_string(),
+        customer_window_hours: dto.customer_window_hours,
+        worker_window_hours: dto.worker_window_hours,
+        no_show_grace_minutes: dto.no_show_grace_minutes,
+    };
+    if policy.category.is_empty() {
+        return Err(ApiError::bad_request("Category is required"));
+    }
+
+    db.collection::<CancellationPolicy>("cancellation_policies")
+        .replace_one(
+            doc! { "category": &policy.category },
+            &policy,
+            ReplaceOptions::builder().upsert(true).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to save policy: {}", e)))?;
+
+    AuditService::record(
+        db,
+        "admin",
+        Some(admin.admin_id),
+        "cancellation_policy.updated",
+        "cancellation_policy",
+        None,
+        serde_json::json!(policy),
+    )
+    .await
+    .map_err(|e| ApiError::internal_error(format!("Failed to write audit log: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Cancellation policy saved".to_string(),
+        serde_json::json!(policy),
+    )))
+}
+
+#[openapi(tag = "Admin - Cancellation Policies")]
+#[delete("/admin/cancellation-policies/<category>")]
+pub async fn delete_cancellation_policy(
+    db: &State<DbConn>,
+    admin: AdminGuard,
+    category: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let result = db
+        .collection::<CancellationPolicy>("cancellation_policies")
+        .delete_one(doc! { "category": &category }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    if result.deleted_count == 0 {
+        return Err(ApiError::not_found("No policy for this category"));
+    }
+
+    AuditService::record(
+        db,
+        "admin",
+        Some(admin.admin_id),
+        "cancellation_policy.deleted",
+        "cancellation_policy",
+        None,
+        serde_json::json!({ "category": category }),
+    )
+    .await
+    .map_err(|e| ApiError::internal_error(format!("Failed to write audit log: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Cancellation policy removed; the category now uses the defaults".to_string(),
+        serde_json::json!({}),
+    )))
+}
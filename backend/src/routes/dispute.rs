@@ -0,0 +1,804 @@
+use crate::db::DbConn;
+use crate::guards::{AdminGuard, AuthGuard};
+use crate::models::{
+    AddEvidenceDto, AdminUser, AssignDisputeDto, AuditLog, AuditLogQuery, Booking,
+    CompletionConfirmation, CreateDisputeDto, Dispute, DisputeListQuery, DisputeMessage,
+    DisputeMessageDto, DisputeResolution, DisputeStatus, RejectDisputeDto, ResolutionActionKind,
+    ResolveDisputeDto, Subscription,
+};
+use crate::routes::notification::create_user_notification;
+use crate::services::{AuditService, BookingService, DisputeService};
+use crate::utils::{ApiError, ApiResponse, is_uploaded_file};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, doc};
+use mongodb::options::FindOptions;
+use rocket::State;
+use rocket::futures::TryStreamExt;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+const MAX_EVIDENCE_FILES: usize = 10;
+const MAX_ATTACHMENTS_PER_MESSAGE: usize = 5;
+
+// ============================================================================
+// SHARED DISPUTE LOGIC
+// ============================================================================
+
+async fn find_dispute(db: &DbConn, dispute_id: &str) -> Result<Dispute, ApiError> {
+    let dispute_oid = ObjectId::parse_str(dispute_id)
+        .map_err(|_| ApiError::bad_request("Invalid dispute ID"))?;
+
+    db.collection::<Dispute>("disputes")
+        .find_one(doc! { "_id": dispute_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Dispute not found"))
+}
+
+/// Dispute visible to one of its parties
+async fn find_party_dispute(db: &DbConn, dispute_id: &str, user_id: ObjectId) -> Result<Dispute, ApiError> {
+    let dispute = find_dispute(db, dispute_id).await?;
+    if !dispute.is_party(user_id) {
+        return Err(ApiError::not_found("Dispute not found"));
+    }
+    Ok(dispute)
+}
+
+/// Evidence and attachments must come from the image or document upload endpoints
+fn validate_files(urls: &[String]) -> Result<(), ApiError> {
+    match urls
+        .iter()
+        .find(|url| !is_uploaded_file(url, "images") && !is_uploaded_file(url, "documents"))
+    {
+        Some(url) => Err(ApiError::bad_request(format!(
+            "'{}' is not an uploaded file. Upload it via /upload/image or /upload/document first",
+            url
+        ))),
+        None => Ok(()),
+    }
+}
+
+async fn dispute_with_thread(db: &DbConn, dispute: &Dispute) -> Result<serde_json::Value, ApiError> {
+    let messages: Vec<DisputeMessage> = db
+        .collection::<DisputeMessage>("dispute_messages")
+        .find(
+            doc! { "dispute_id": dispute.id },
+            FindOptions::builder().sort(doc! { "created_at": 1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    Ok(serde_json::json!({
+        "dispute": dispute,
+        "messages": messages
+    }))
+}
+
+async fn insert_message(
+    db: &DbConn,
+    dispute: &Dispute,
+    author_type: &str,
+    author_id: ObjectId,
+    dto: &DisputeMessageDto,
+) -> Result<DisputeMessage, ApiError> {
+    let body = dto.body.trim();
+    if body.is_empty() {
+        return Err(ApiError::bad_request("Message is empty"));
+    }
+    if dispute.is_closed() {
+        return Err(ApiError::bad_request("This dispute is closed"));
+    }
+
+    let attachments = dto.attachments.clone().unwrap_or_default();
+    if attachments.len() > MAX_ATTACHMENTS_PER_MESSAGE {
+        return Err(ApiError::bad_request(format!(
+            "At most {} attachments per message",
+            MAX_ATTACHMENTS_PER_MESSAGE
+        )));
+    }
+    validate_files(&attachments)?;
+
+    let mut message = DisputeMessage {
+        id: None,
+        dispute_id: dispute.id.ok_or_else(|| ApiError::internal_error("Dispute missing ID"))?,
+        author_type: author_type.to_string(),
+        author_id,
+        body: body.to_string(),
+        attachments,
+        created_at: DateTime::now(),
+    };
+
+    let res = db
+        .collection::<DisputeMessage>("dispute_messages")
+        .insert_one(&message, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to post message: {}", e)))?;
+    message.id = res.inserted_id.as_object_id();
+
+    Ok(message)
+}
+
+async fn notify_parties(db: &DbConn, dispute: &Dispute, except: Option<ObjectId>, title: &str, body: String) {
+    let parties = std::iter::once(dispute.raised_by).chain(dispute.against_user_id);
+
+    for user_id in parties.filter(|id| Some(*id) != except) {
+        let _ = create_user_notification(
+            db,
+            user_id,
+            "dispute_update".to_string(),
+            title.to_string(),
+            body.clone(),
+            dispute.id,
+        )
+        .await;
+    }
+}
+
+async fn audit(
+    db: &DbConn,
+    admin_id: ObjectId,
+    action: &str,
+    dispute: &Dispute,
+    details: serde_json::Value,
+) -> Result<Option<ObjectId>, ApiError> {
+    AuditService::record(db, "admin", Some(admin_id), action, "dispute", dispute.id, details)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to write audit log: {}", e)))
+}
+
+// ============================================================================
+// USER ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Dispute")]
+#[post("/disputes", data = "<dto>")]
+pub async fn create_dispute(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CreateDisputeDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let subject = dto.subject.trim();
+    let description = dto.description.trim();
+    if subject.is_empty() || subject.chars().count() > 120 {
+        return Err(ApiError::bad_request("Subject must be between 1 and 120 characters"));
+    }
+    if description.chars().count() < 20 {
+        return Err(ApiError::bad_request("Please describe the problem in at least 20 characters"));
+    }
+
+    let evidence = dto.evidence.clone().unwrap_or_default();
+    if evidence.len() > MAX_EVIDENCE_FILES {
+        return Err(ApiError::bad_request(format!("At most {} evidence files", MAX_EVIDENCE_FILES)));
+    }
+    validate_files(&evidence)?;
+
+    let (booking_id, subscription_id, against_user_id) = match (&dto.booking_id, &dto.subscription_id) {
+        (Some(booking_id), None) => {
+            let booking_oid = ObjectId::parse_str(booking_id)
+                .map_err(|_| ApiError::bad_request("Invalid booking ID"))?;
+            let booking = db
+                .collection::<Booking>("bookings")
+                .find_one(
+                    doc! {
+                        "_id": booking_oid,
+                        "$or": [{ "customer_id": auth.user_id }, { "worker_user_id": auth.user_id }]
+                    },
+                    None,
+                )
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+                .ok_or_else(|| ApiError::not_found("Booking not found"))?;
+
+            let other = if booking.customer_id == auth.user_id {
+                booking.worker_user_id
+            } else {
+                booking.customer_id
+            };
+            (Some(booking_oid), None, Some(other))
+        }
+        (None, Some(subscription_id)) => {
+            let subscription_oid = ObjectId::parse_str(subscription_id)
+                .map_err(|_| ApiError::bad_request("Invalid subscription ID"))?;
+            db.collection::<Subscription>("subscriptions")
+                .find_one(doc! { "_id": subscription_oid, "user_id": auth.user_id }, None)
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+                .ok_or_else(|| ApiError::not_found("Subscription not found"))?;
+            (None, Some(subscription_oid), None)
+        }
+        _ => return Err(ApiError::bad_request("Provide either a booking_id or a subscription_id")),
+    };
+
+    let open = db
+        .collection::<Dispute>("disputes")
+        .find_one(
+            doc! {
+                "raised_by": auth.user_id,
+                "booking_id": booking_id,
+                "subscription_id": subscription_id,
+                "status": { "$in": ["open", "in_review"] }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    if open.is_some() {
+        return Err(ApiError::bad_request("You already have an open dispute for this"));
+    }
+
+    let now = DateTime::now();
+    let (first_response_due_at, resolution_due_at) = DisputeService::sla_deadlines(now);
+    let dispute = Dispute {
+        id: None,
+        raised_by: auth.user_id,
+        against_user_id,
+        booking_id,
+        subscription_id,
+        category: dto.category,
+        subject: subject.to_string(),
+        description: description.to_string(),
+        evidence,
+        status: DisputeStatus::Open,
+        assigned_admin_id: None,
+        first_response_due_at,
+        resolution_due_at,
+        first_responded_at: None,
+        sla_breached: false,
+        resolution: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let dispute = DisputeService::open(db, dispute)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create dispute: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Dispute submitted. Our support team will respond shortly".to_string(),
+        serde_json::json!(dispute),
+    )))
+}
+
+#[openapi(tag = "Dispute")]
+#[get("/disputes/mine")]
+pub async fn list_my_disputes(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let disputes: Vec<Dispute> = db
+        .collection::<Dispute>("disputes")
+        .find(
+            doc! { "$or": [{ "raised_by": auth.user_id }, { "against_user_id": auth.user_id }] },
+            FindOptions::builder().sort(doc! { "created_at": -1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "disputes": disputes
+    }))))
+}
+
+#[openapi(tag = "Dispute")]
+#[get("/disputes/<dispute_id>")]
+pub async fn get_dispute(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dispute_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let dispute = find_party_dispute(db, &dispute_id, auth.user_id).await?;
+
+    Ok(Json(ApiResponse::success(dispute_with_thread(db, &dispute).await?)))
+}
+
+#[openapi(tag = "Dispute")]
+#[post("/disputes/<dispute_id>/evidence", data = "<dto>")]
+pub async fn add_dispute_evidence(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dispute_id: String,
+    dto: Json<AddEvidenceDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let dispute = find_party_dispute(db, &dispute_id, auth.user_id).await?;
+    if dispute.is_closed() {
+        return Err(ApiError::bad_request("This dispute is closed"));
+    }
+    if dto.urls.is_empty() {
+        return Err(ApiError::bad_request("No files provided"));
+    }
+    if dispute.evidence.len() + dto.urls.len() > MAX_EVIDENCE_FILES {
+        return Err(ApiError::bad_request(format!("At most {} evidence files", MAX_EVIDENCE_FILES)));
+    }
+    validate_files(&dto.urls)?;
+
+    db.collection::<Dispute>("disputes")
+        .update_one(
+            doc! { "_id": dispute.id },
+            doc! {
+                "$push": { "evidence": { "$each": &dto.urls } },
+                "$set": { "updated_at": DateTime::now() }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Evidence added".to_string(),
+        serde_json::json!({ "added": dto.urls.len() }),
+    )))
+}
+
+#[openapi(tag = "Dispute")]
+#[post("/disputes/<dispute_id>/messages", data = "<dto>")]
+pub async fn post_dispute_message(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dispute_id: String,
+    dto: Json<DisputeMessageDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let dispute = find_party_dispute(db, &dispute_id, auth.user_id).await?;
+    let message = insert_message(db, &dispute, "user", auth.user_id, &dto).await?;
+
+    notify_parties(
+        db,
+        &dispute,
+        Some(auth.user_id),
+        "New Dispute Message",
+        format!("There is a new message on the dispute \"{}\".", dispute.subject),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(serde_json::json!(message))))
+}
+
+// ============================================================================
+// ADMIN ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Admin - Disputes")]
+#[get("/admin/disputes?<query..>")]
+pub async fn admin_list_disputes(
+    db: &State<DbConn>,
+    admin: AdminGuard,
+    query: DisputeListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! {};
+    if let Some(ref status) = query.status {
+        filter.insert("status", status);
+    }
+    if query.assigned_to_me == Some(true) {
+        filter.insert("assigned_admin_id", admin.admin_id);
+    }
+    if let Some(breached) = query.sla_breached {
+        filter.insert("sla_breached", breached);
+    }
+
+    // Most urgent first
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "resolution_due_at": 1 })
+        .build();
+
+    let disputes: Vec<Dispute> = db
+        .collection::<Dispute>("disputes")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let total = db
+        .collection::<Dispute>("disputes")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "disputes": disputes,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Admin - Disputes")]
+#[get("/admin/disputes/<dispute_id>")]
+pub async fn admin_get_dispute(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    dispute_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let dispute = find_dispute(db, &dispute_id).await?;
+
+    let audit_trail: Vec<AuditLog> = db
+        .collection::<AuditLog>("audit_logs")
+        .find(
+            doc! { "entity_type": "dispute", "entity_id": dispute.id },
+            FindOptions::builder().sort(doc! { "created_at": 1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let mut data = dispute_with_thread(db, &dispute).await?;
+    data["audit_trail"] = serde_json::json!(audit_trail);
+
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "Admin - Disputes")]
+#[put("/admin/disputes/<dispute_id>/assign", data = "<dto>")]
+pub async fn assign_dispute(
+    db: &State<DbConn>,
+    admin: AdminGuard,
+    dispute_id: String,
+    dto: Json<AssignDisputeDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let dispute = find_dispute(db, &dispute_id).await?;
+    if dispute.is_closed() {
+        return Err(ApiError::bad_request("This dispute is closed"));
+    }
+
+    let assignee = match dto.admin_id {
+        Some(ref id) => ObjectId::parse_str(id).map_err(|_| ApiError::bad_request("Invalid admin ID"))?,
+        None => admin.admin_id,
+    };
+    db.collection::<AdminUser>("admin_users")
+        .find_one(doc! { "_id": assignee, "is_active": true }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Admin not found"))?;
+
+    db.collection::<Dispute>("disputes")
+        .update_one(
+            doc! { "_id": dispute.id },
+            doc! {
+                "$set": {
+                    "assigned_admin_id": assignee,
+                    "status": DisputeStatus::InReview.as_str(),
+                    "updated_at": DateTime::now()
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    audit(
+        db,
+        admin.admin_id,
+        "dispute.assigned",
+        &dispute,
+        serde_json::json!({
+            "from": dispute.assigned_admin_id.map(|id| id.to_hex()),
+            "to": assignee.to_hex()
+        }),
+    )
+    .await?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Dispute assigned".to_string(),
+        serde_json::json!({ "assigned_admin_id": assignee.to_hex() }),
+    )))
+}
+
+/// Support's reply in the thread. The first reply stops the first-response SLA.
+#[openapi(tag = "Admin - Disputes")]
+#[post("/admin/disputes/<dispute_id>/messages", data = "<dto>")]
+pub async fn admin_post_dispute_message(
+    db: &State<DbConn>,
+    admin: AdminGuard,
+    dispute_id: String,
+    dto: Json<DisputeMessageDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let dispute = find_dispute(db, &dispute_id).await?;
+    let message = insert_message(db, &dispute, "admin", admin.admin_id, &dto).await?;
+
+    let mut update = doc! {
+        "status": DisputeStatus::InReview.as_str(),
+        "updated_at": message.created_at
+    };
+    if dispute.first_responded_at.is_none() {
+        update.insert("first_responded_at", message.created_at);
+    }
+    if dispute.assigned_admin_id.is_none() {
+        update.insert("assigned_admin_id", admin.admin_id);
+    }
+
+    db.collection::<Dispute>("disputes")
+        .update_one(doc! { "_id": dispute.id }, doc! { "$set": update }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    notify_parties(
+        db,
+        &dispute,
+        None,
+        "Support Replied",
+        format!("Support has replied on your dispute \"{}\".", dispute.subject),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(serde_json::json!(message))))
+}
+
+#[openapi(tag = "Admin - Disputes")]
+#[put("/admin/disputes/<dispute_id>/resolve", data = "<dto>")]
+pub async fn resolve_dispute(
+    db: &State<DbConn>,
+    admin: AdminGuard,
+    dispute_id: String,
+    dto: Json<ResolveDisputeDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let dispute = find_dispute(db, &dispute_id).await?;
+    let summary = dto.summary.trim();
+    if summary.is_empty() {
+        return Err(ApiError::bad_request("A resolution summary is required"));
+    }
+
+    for (i, action) in dto.actions.iter().enumerate() {
+        if dto.actions[..i].iter().any(|a| a.kind == action.kind) {
+            return Err(ApiError::bad_request("Each action can only be applied once"));
+        }
+        match action.kind {
+            ResolutionActionKind::Refund if action.amount.is_none() => {
+                return Err(ApiError::bad_request("A refund needs an amount"));
+            }
+            ResolutionActionKind::Warning if dispute.against_user_id.is_none() => {
+                return Err(ApiError::bad_request("This dispute has no other party to warn"));
+            }
+            ResolutionActionKind::WorkerSuspension if dispute.booking_id.is_none() => {
+                return Err(ApiError::bad_request("Only booking disputes can suspend a worker"));
+            }
+            ResolutionActionKind::WorkerSuspension if action.suspension_days.is_none() => {
+                return Err(ApiError::bad_request("A suspension needs a number of days"));
+            }
+            _ => {}
+        }
+    }
+
+    let now = DateTime::now();
+    let mut resolution = DisputeResolution {
+        summary: summary.to_string(),
+        actions: Vec::new(),
+        resolved_by: admin.admin_id,
+        resolved_at: now,
+    };
+    let resolution_doc = mongodb::bson::to_bson(&resolution)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    // Claim the dispute so two admins can't apply the same actions
+    let claimed = db
+        .collection::<Dispute>("disputes")
+        .update_one(
+            doc! { "_id": dispute.id, "status": { "$in": ["open", "in_review"] } },
+            doc! {
+                "$set": {
+                    "status": DisputeStatus::Resolved.as_str(),
+                    "resolution": resolution_doc,
+                    "updated_at": now
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    if claimed.modified_count == 0 {
+        return Err(ApiError::bad_request("This dispute is already closed"));
+    }
+
+    let mut failure = None;
+    for action in &dto.actions {
+        match DisputeService::apply_action(db, &dispute, action, admin.admin_id).await {
+            Ok(applied) => resolution.actions.push(applied),
+            Err(e) => {
+                failure = Some(e);
+                break;
+            }
+        }
+    }
+
+    if let (Some(e), true) = (&failure, resolution.actions.is_empty()) {
+        // Nothing was applied; reopen so it can be retried
+        db.collection::<Dispute>("disputes")
+            .update_one(
+                doc! { "_id": dispute.id },
+                doc! {
+                    "$set": { "status": DisputeStatus::InReview.as_str(), "resolution": null, "updated_at": now }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+        return Err(ApiError::bad_request(e.clone()));
+    }
+
+    let actions = mongodb::bson::to_bson(&resolution.actions)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+    db.collection::<Dispute>("disputes")
+        .update_one(
+            doc! { "_id": dispute.id },
+            doc! { "$set": { "resolution.actions": actions } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    audit(
+        db,
+        admin.admin_id,
+        "dispute.resolved",
+        &dispute,
+        serde_json::json!({
+            "summary": summary,
+            "actions": resolution.actions.iter().map(|a| a.kind).collect::<Vec<_>>(),
+            "failed_action": failure
+        }),
+    )
+    .await?;
+
+    DisputeService::notify_closed(db, &dispute, "Dispute Resolved", summary).await;
+
+    if let Some(e) = failure {
+        return Err(ApiError::internal_error(format!(
+            "Dispute resolved, but an action failed after {} succeeded: {}",
+            resolution.actions.len(),
+            e
+        )));
+    }
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Dispute resolved".to_string(),
+        serde_json::json!(resolution),
+    )))
+}
+
+#[openapi(tag = "Admin - Disputes")]
+#[put("/admin/disputes/<dispute_id>/reject", data = "<dto>")]
+pub async fn reject_dispute(
+    db: &State<DbConn>,
+    admin: AdminGuard,
+    dispute_id: String,
+    dto: Json<RejectDisputeDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let dispute = find_dispute(db, &dispute_id).await?;
+    let reason = dto.reason.trim();
+    if reason.is_empty() {
+        return Err(ApiError::bad_request("A reason is required"));
+    }
+
+    let now = DateTime::now();
+    let resolution = mongodb::bson::to_bson(&DisputeResolution {
+        summary: reason.to_string(),
+        actions: Vec::new(),
+        resolved_by: admin.admin_id,
+        resolved_at: now,
+    })
+    .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    let result = db
+        .collection::<Dispute>("disputes")
+        .update_one(
+            doc! { "_id": dispute.id, "status": { "$in": ["open", "in_review"] } },
+            doc! {
+                "$set": {
+                    "status": DisputeStatus::Rejected.as_str(),
+                    "resolution": resolution,
+                    "updated_at": now
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    if result.modified_count == 0 {
+        return Err(ApiError::bad_request("This dispute is already closed"));
+    }
+
+    audit(db, admin.admin_id, "dispute.rejected", &dispute, serde_json::json!({ "reason": reason })).await?;
+
+    // A rejected contest means the job stands, so it now counts for the worker. Other
+    // disputes about the booking leave its completion alone.
+    let booking = match dispute.booking_id {
+        Some(booking_id) => db
+            .collection::<Booking>("bookings")
+            .find_one(doc! { "_id": booking_id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?,
+        None => None,
+    };
+    let contested = booking
+        .filter(|b| b.completion.as_ref().and_then(|c| c.dispute_id) == dispute.id)
+        .and_then(|b| b.id);
+    if let Some(booking_id) = contested {
+        BookingService::confirm_completion(
+            db,
+            booking_id,
+            &[CompletionConfirmation::Contested],
+            CompletionConfirmation::Confirmed,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to confirm completion: {}", e)))?;
+    }
+    DisputeService::notify_closed(db, &dispute, "Dispute Closed", reason).await;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Dispute rejected".to_string(),
+        serde_json::json!({}),
+    )))
+}
+
+#[openapi(tag = "Admin - Disputes")]
+#[get("/admin/audit-logs?<query..>")]
+pub async fn get_audit_logs(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: AuditLogQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(50).min(200);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! {};
+    if let Some(ref entity_type) = query.entity_type {
+        filter.insert("entity_type", entity_type);
+    }
+    if let Some(ref entity_id) = query.entity_id {
+        let entity_oid = ObjectId::parse_str(entity_id)
+            .map_err(|_| ApiError::bad_request("Invalid entity ID"))?;
+        filter.insert("entity_id", entity_oid);
+    }
+    if let Some(ref action) = query.action {
+        filter.insert("action", action);
+    }
+
+    let logs: Vec<AuditLog> = db
+        .collection::<AuditLog>("audit_logs")
+        .find(
+            filter.clone(),
+            FindOptions::builder()
+                .skip(skip as u64)
+                .limit(limit)
+                .sort(doc! { "created_at": -1 })
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let total = db
+        .collection::<AuditLog>("audit_logs")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "audit_logs": logs,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
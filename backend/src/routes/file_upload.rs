@@ -8,7 +8,7 @@ use std::path::Path;
 use tokio::fs;
 use uuid::Uuid;
 use crate::guards::AuthGuard;
-use crate::utils::{ApiResponse, ApiError};
+use crate::utils::{ApiResponse, ApiError, is_uploaded_file};
 
 // ============================================================================
 // BASE64 UPLOAD STRUCTS
@@ -48,6 +48,37 @@ pub(crate) fn is_valid_document_extension(ext: &str) -> bool {
     matches!(ext, "pdf" | "jpg" | "jpeg" | "png")
 }
 
+/// Local path of a file saved by `upload_document`, from the URL it returned.
+/// None for anything `is_uploaded_file` doesn't accept as a document.
+pub(crate) fn uploaded_document_path(url: &str) -> Option<String> {
+    let (_, filename) = url.rsplit_once('/')?;
+    is_uploaded_file(url, "documents").then(|| format!("uploads/documents/{}", filename))
+}
+
+/// Save generated bytes alongside uploaded documents and return their URL, as
+/// `upload_document` would for an uploaded file
+pub(crate) async fn save_document(bytes: &[u8], extension: &str) -> Result<String, ApiError> {
+    let upload_dir = "uploads/documents";
+    fs::create_dir_all(upload_dir)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create directory: {}", e)))?;
+
+    let filename = format!(
+        "{}_{}.{}",
+        Uuid::new_v4(),
+        chrono::Utc::now().timestamp(),
+        extension
+    );
+    let filepath = format!("{}/{}", upload_dir, filename);
+    fs::write(&filepath, bytes)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to save file: {}", e)))?;
+
+    Ok(std::env::var("APP_BASE_URL")
+        .map(|base| format!("{}/{}", base.trim_end_matches('/'), filepath))
+        .unwrap_or_else(|_| format!("/{}", filepath)))
+}
+
 pub(crate) fn extension_from_content_type(content_type: &str) -> Option<String> {
     match content_type {
         "image/jpeg" => Some("jpg".to_string()),
@@ -0,0 +1,457 @@
+use crate::db::DbConn;
+use crate::guards::AuthGuard;
+use crate::models::{
+    ApplicationStatus, CancelInterviewDto, Interview, InterviewListQuery, InterviewSlot,
+    InterviewSlotDto, InterviewStatus, JobApplication, ProposeInterviewDto,
+    RescheduleInterviewDto, SelectInterviewSlotDto,
+};
+use crate::routes::application::{change_status, find_applicant, find_poster_job};
+use crate::routes::notification::create_user_notification;
+use crate::services::InterviewService;
+use crate::utils::ics::IcsMethod;
+use crate::utils::time::{DAY_MILLIS, format_datetime, parse_rfc3339};
+use crate::utils::{ApiError, ApiResponse};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, doc};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use rocket::State;
+use rocket::futures::TryStreamExt;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+const MAX_SLOTS: usize = 5;
+/// How far ahead an interview slot can be proposed
+const MAX_DAYS_AHEAD: i64 = 60;
+
+// ============================================================================
+// SHARED INTERVIEW LOGIC
+// ============================================================================
+
+/// Interview the user takes part in
+async fn find_interview(db: &DbConn, interview_id: &str, user_id: ObjectId) -> Result<Interview, ApiError> {
+    let interview_oid = ObjectId::parse_str(interview_id)
+        .map_err(|_| ApiError::bad_request("Invalid interview ID"))?;
+
+    db.collection::<Interview>("interviews")
+        .find_one(doc! { "_id": interview_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .filter(|interview| interview.is_party(user_id))
+        .ok_or_else(|| ApiError::not_found("Interview not found"))
+}
+
+fn parse_slots(slots: &[InterviewSlotDto]) -> Result<Vec<InterviewSlot>, ApiError> {
+    if slots.is_empty() || slots.len() > MAX_SLOTS {
+        return Err(ApiError::bad_request(format!("Propose between 1 and {} slots", MAX_SLOTS)));
+    }
+
+    let now = DateTime::now();
+    slots
+        .iter()
+        .map(|slot| {
+            let start = parse_rfc3339(&slot.start)
+                .ok_or_else(|| ApiError::bad_request("Invalid slot start, expected an RFC 3339 timestamp"))?;
+            if start <= now {
+                return Err(ApiError::bad_request("Interview slots must be in the future"));
+            }
+            if start.timestamp_millis() - now.timestamp_millis() > MAX_DAYS_AHEAD * DAY_MILLIS {
+                return Err(ApiError::bad_request(format!(
+                    "Interview slots can be at most {} days ahead",
+                    MAX_DAYS_AHEAD
+                )));
+            }
+
+            let duration_minutes = slot.duration_minutes.unwrap_or(30);
+            if !(10..=4 * 60).contains(&duration_minutes) {
+                return Err(ApiError::bad_request("Interview length must be between 10 minutes and 4 hours"));
+            }
+
+            Ok(InterviewSlot { start, duration_minutes })
+        })
+        .collect()
+}
+
+fn trimmed(text: &Option<String>) -> Option<String> {
+    text.as_deref().map(str::trim).filter(|t| !t.is_empty()).map(str::to_string)
+}
+
+/// Apply an update to an interview still in one of `from`, returning the result
+async fn update_interview(
+    db: &DbConn,
+    interview: &Interview,
+    from: &[InterviewStatus],
+    update: mongodb::bson::Document,
+) -> Result<Interview, ApiError> {
+    let from: Vec<&str> = from.iter().map(|s| s.as_str()).collect();
+
+    db.collection::<Interview>("interviews")
+        .find_one_and_update(
+            doc! { "_id": interview.id, "status": { "$in": from } },
+            update,
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request("Interview was updated by someone else. Please refresh"))
+}
+
+async fn notify(db: &DbConn, user_id: ObjectId, title: &str, body: String, interview: &Interview) {
+    let _ = create_user_notification(
+        db,
+        user_id,
+        "interview_update".to_string(),
+        title.to_string(),
+        body,
+        interview.id,
+    )
+    .await;
+}
+
+/// Email a cancellation for the slot that was booked, if there was one
+async fn cancel_invites(db: &DbConn, interview: &Interview) {
+    if let (InterviewStatus::Scheduled, Some(slot)) = (interview.status, &interview.scheduled_slot) {
+        InterviewService::send_invites(db, interview, slot, IcsMethod::Cancel).await;
+    }
+}
+
+// ============================================================================
+// INTERVIEW ENDPOINTS
+// ============================================================================
+
+/// Poster offers a shortlisted applicant one or more interview slots
+#[openapi(tag = "Interviews")]
+#[post("/jobs/<job_id>/applicants/<application_id>/interviews", data = "<dto>")]
+pub async fn propose_interview(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    application_id: String,
+    dto: Json<ProposeInterviewDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_poster_job(db, &job_id, auth.user_id).await?;
+    let application = find_applicant(db, &job, &application_id).await?;
+
+    let pipeline = job.pipeline();
+    if !pipeline.contains(&ApplicationStatus::Interview) {
+        return Err(ApiError::bad_request("This job's pipeline has no interview stage"));
+    }
+    // Applicants are shortlisted first when the job uses that stage
+    let ready = match application.status {
+        ApplicationStatus::Interview => true,
+        ApplicationStatus::Applied => !pipeline.contains(&ApplicationStatus::Shortlisted),
+        status => status.can_move_to(ApplicationStatus::Interview, pipeline),
+    };
+    if !ready {
+        return Err(ApiError::bad_request("Shortlist the applicant before proposing an interview"));
+    }
+
+    let open = db
+        .collection::<Interview>("interviews")
+        .find_one(
+            doc! { "application_id": application.id, "status": { "$in": ["proposed", "scheduled"] } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    if open.is_some() {
+        return Err(ApiError::bad_request(
+            "This applicant already has an upcoming interview. Reschedule it instead",
+        ));
+    }
+
+    let slots = parse_slots(&dto.slots)?;
+    let now = DateTime::now();
+    let mut interview = Interview {
+        id: None,
+        application_id: application.id.ok_or_else(|| ApiError::internal_error("Application missing ID"))?,
+        job_id: application.job_id,
+        poster_user_id: auth.user_id,
+        seeker_user_id: application.seeker_user_id,
+        mode: dto.mode,
+        location: trimmed(&dto.location),
+        notes: trimmed(&dto.notes),
+        slots,
+        scheduled_slot: None,
+        status: InterviewStatus::Proposed,
+        round: 0,
+        reminder_sent: false,
+        cancelled_by: None,
+        cancel_reason: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let res = db
+        .collection::<Interview>("interviews")
+        .insert_one(&interview, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create interview: {}", e)))?;
+    interview.id = res.inserted_id.as_object_id();
+
+    notify(
+        db,
+        interview.seeker_user_id,
+        "Interview Invitation",
+        format!(
+            "{} would like to interview you for \"{}\". Pick a time that suits you.",
+            job.company_name, job.title
+        ),
+        &interview,
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Interview slots sent to the applicant".to_string(),
+        serde_json::json!(interview),
+    )))
+}
+
+#[openapi(tag = "Interviews")]
+#[get("/interviews?<query..>")]
+pub async fn list_interviews(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: InterviewListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = match query.role.as_deref() {
+        Some("poster") => doc! { "poster_user_id": auth.user_id },
+        _ => doc! { "seeker_user_id": auth.user_id },
+    };
+    if let Some(status) = query.status {
+        filter.insert("status", status);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let interviews: Vec<Interview> = db
+        .collection::<Interview>("interviews")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let total = db
+        .collection::<Interview>("interviews")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "interviews": interviews,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Interviews")]
+#[get("/interviews/<interview_id>")]
+pub async fn get_interview(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    interview_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let interview = find_interview(db, &interview_id, auth.user_id).await?;
+    Ok(Json(ApiResponse::success(serde_json::json!(interview))))
+}
+
+/// Applicant books one of the proposed slots. Both parties are emailed a
+/// calendar invite.
+#[openapi(tag = "Interviews")]
+#[put("/interviews/<interview_id>/select", data = "<dto>")]
+pub async fn select_interview_slot(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    interview_id: String,
+    dto: Json<SelectInterviewSlotDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let interview = find_interview(db, &interview_id, auth.user_id).await?;
+    if interview.seeker_user_id != auth.user_id {
+        return Err(ApiError::forbidden("Only the applicant can pick a slot"));
+    }
+    if interview.status != InterviewStatus::Proposed {
+        return Err(ApiError::bad_request("This interview is not waiting for a slot to be picked"));
+    }
+
+    let slot = interview
+        .slots
+        .get(dto.slot_index)
+        .cloned()
+        .ok_or_else(|| ApiError::bad_request("Invalid slot"))?;
+    if slot.start <= DateTime::now() {
+        return Err(ApiError::bad_request("That slot has passed. Ask the poster for new times"));
+    }
+
+    let slot_bson = mongodb::bson::to_bson(&slot)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+    let interview = update_interview(
+        db,
+        &interview,
+        &[InterviewStatus::Proposed],
+        doc! {
+            "$set": {
+                "status": InterviewStatus::Scheduled.as_str(),
+                "scheduled_slot": slot_bson,
+                "updated_at": DateTime::now()
+            }
+        },
+    )
+    .await?;
+
+    // Picking a slot moves the applicant to the interview stage (proposing checked the pipeline)
+    let application = db
+        .collection::<JobApplication>("applications")
+        .find_one(doc! { "_id": interview.application_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    if let Some(application) = application
+        .filter(|a| matches!(a.status, ApplicationStatus::Applied | ApplicationStatus::Shortlisted))
+    {
+        change_status(db, &application, ApplicationStatus::Interview, None).await?;
+    }
+
+    InterviewService::send_invites(db, &interview, &slot, IcsMethod::Request).await;
+    notify(
+        db,
+        interview.poster_user_id,
+        "Interview Scheduled",
+        format!("The applicant picked {} for their interview.", format_datetime(slot.start)),
+        &interview,
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Interview scheduled. A calendar invite is on its way".to_string(),
+        serde_json::json!(interview),
+    )))
+}
+
+/// Poster replaces the slots with new ones. A booked slot is cancelled and the
+/// applicant picks again.
+#[openapi(tag = "Interviews")]
+#[put("/interviews/<interview_id>/reschedule", data = "<dto>")]
+pub async fn reschedule_interview(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    interview_id: String,
+    dto: Json<RescheduleInterviewDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let interview = find_interview(db, &interview_id, auth.user_id).await?;
+    if interview.poster_user_id != auth.user_id {
+        return Err(ApiError::forbidden("Only the job poster can reschedule"));
+    }
+    if interview.status == InterviewStatus::Cancelled {
+        return Err(ApiError::bad_request("This interview was cancelled"));
+    }
+
+    let slots = parse_slots(&dto.slots)?;
+    let slots_bson = mongodb::bson::to_bson(&slots)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    let updated = update_interview(
+        db,
+        &interview,
+        &[interview.status],
+        doc! {
+            "$set": {
+                "status": InterviewStatus::Proposed.as_str(),
+                "slots": slots_bson,
+                "scheduled_slot": null,
+                "reminder_sent": false,
+                "updated_at": DateTime::now()
+            },
+            "$inc": { "round": 1 }
+        },
+    )
+    .await?;
+
+    cancel_invites(db, &interview).await;
+
+    let reason = trimmed(&dto.reason);
+    notify(
+        db,
+        interview.seeker_user_id,
+        "Interview Rescheduled",
+        match reason {
+            Some(reason) => format!("Your interview has new time options. Please pick one. Reason: {}", reason),
+            None => "Your interview has new time options. Please pick one.".to_string(),
+        },
+        &updated,
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "New interview slots sent to the applicant".to_string(),
+        serde_json::json!(updated),
+    )))
+}
+
+/// Either party calls off the interview
+#[openapi(tag = "Interviews")]
+#[put("/interviews/<interview_id>/cancel", data = "<dto>")]
+pub async fn cancel_interview(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    interview_id: String,
+    dto: Json<CancelInterviewDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let interview = find_interview(db, &interview_id, auth.user_id).await?;
+    if interview.status == InterviewStatus::Cancelled {
+        return Err(ApiError::bad_request("This interview is already cancelled"));
+    }
+
+    let reason = trimmed(&dto.reason);
+    let updated = update_interview(
+        db,
+        &interview,
+        &[interview.status],
+        doc! {
+            "$set": {
+                "status": InterviewStatus::Cancelled.as_str(),
+                "cancelled_by": auth.user_id,
+                "cancel_reason": reason.clone(),
+                "updated_at": DateTime::now()
+            }
+        },
+    )
+    .await?;
+
+    cancel_invites(db, &interview).await;
+
+    let other = if auth.user_id == interview.poster_user_id {
+        interview.seeker_user_id
+    } else {
+        interview.poster_user_id
+    };
+    notify(
+        db,
+        other,
+        "Interview Cancelled",
+        match reason {
+            Some(reason) => format!("An interview was cancelled. Reason: {}", reason),
+            None => "An interview was cancelled.".to_string(),
+        },
+        &updated,
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Interview cancelled".to_string(),
+        serde_json::json!(updated),
+    )))
+}
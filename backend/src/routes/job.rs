@@ -1,14 +1,25 @@
 use crate::db::DbConn;
 use crate::guards::{AuthGuard, KycGuard};
 use crate::models::{
-    CreateJobSeekerProfileDto, JobSeekerProfile, JobSeekerSubscriptionPlan, Subscription,
-    SubscriptionStatus, SubscriptionType, UpdateJobSeekerProfileDto,
+    CreateJobSeekerProfileDto, GenerateResumeDto, JobSeekerProfile, JobSeekerSubscriptionPlan,
+    ParseResumeDto, ResumeTemplate, Subscription, SubscriptionStatus, SubscriptionType,
+    UpdateJobSeekerProfileDto, User, find_plan,
 };
-use crate::models::{JobPost, JobPostResponse};
+use crate::models::{
+    GeoLocation, JobPost, JobPostResponse, JobStatus, JobType, MainCategory, SubCategory,
+    UpdateJobPostDto, WorkMode,
+};
+use crate::models::{ApplicationStatus, JobApplication, JobSeekerProfileResponse};
 use crate::routes::file_upload::{
     extension_from_content_type, get_extension_from_filename, is_valid_document_extension,
+    save_document, uploaded_document_path,
+};
+use crate::services::resume::MAX_RESUME_BYTES;
+use crate::services::{
+    BillingService, EntitlementService, JobMatchingService, JobPostService, MatchingService,
+    RazorpayService, ResumeService,
 };
-use crate::services::RazorpayService;
+use crate::utils::search;
 use crate::utils::{ApiError, ApiResponse};
 use hmac::{Hmac, Mac};
 use mongodb::bson::oid::ObjectId;
@@ -16,10 +27,12 @@ use mongodb::bson::{DateTime, doc};
 use mongodb::options::FindOptions;
 use rocket::State;
 use rocket::form::FromForm;
+use rocket::futures::TryStreamExt;
 use rocket::fs::TempFile;
 use rocket::serde::json::Json;
 use rocket_okapi::openapi;
 use sha2::Sha256;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 // ============================================================================
@@ -34,19 +47,14 @@ pub async fn create_job_seeker_subscription(
     plan_name: String,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     // Validate plan and get price
-    let (price, plan_type) = match plan_name.to_lowercase().as_str() {
-        "basic" => (99.0, JobSeekerSubscriptionPlan::Basic),
-        "premium" => (1.5, JobSeekerSubscriptionPlan::Premium),
-        _ => {
-            return Err(ApiError::bad_request(
-                "Invalid plan. Choose 'basic' or 'premium'",
-            ));
-        }
-    };
+    let plan = find_plan(&SubscriptionType::JobSeeker, &plan_name).ok_or_else(|| {
+        ApiError::bad_request("Invalid plan. Choose 'basic' or 'premium'")
+    })?;
+    let price = plan.price;
 
     let now = DateTime::now();
     let expires_at = DateTime::from_millis(
-        chrono::Utc::now().timestamp_millis() + 365 * 24 * 60 * 60 * 1000, // 1 year
+        chrono::Utc::now().timestamp_millis() + plan.duration_days * 24 * 60 * 60 * 1000,
     );
 
     // Check if user already has an active subscription
@@ -56,7 +64,8 @@ pub async fn create_job_seeker_subscription(
             doc! {
                 "user_id": auth.user_id,
                 "subscription_type": "jobseeker",
-                "status": "active"
+                "status": "active",
+                "is_trial": { "$ne": true } // A trial can be converted to a paid plan
             },
             None,
         )
@@ -65,7 +74,7 @@ pub async fn create_job_seeker_subscription(
 
     if existing.is_some() {
         return Err(ApiError::bad_request(
-            "You already have an active job seeker subscription",
+            "You already have an active job seeker subscription. Use /job-seeker/subscription/change/<plan_name> to switch plans",
         ));
     }
 
@@ -111,6 +120,14 @@ pub async fn create_job_seeker_subscription(
         auto_renew: false,
         payment_id: None,
         invoice_number: Some(next_invoice_number),
+        previous_subscription_id: None,
+        scheduled_plan: None,
+        mandate: None,
+        renewal_attempts: 0,
+        next_renewal_at: None,
+        renewal_payment_id: None,
+        grace_until: None,
+        is_trial: false,
         created_at: now,
         updated_at: now,
     };
@@ -218,6 +235,10 @@ pub async fn verify_job_seeker_payment(
         return Err(ApiError::not_found("Subscription not found"));
     }
 
+    BillingService::supersede_trials(db, auth.user_id, &SubscriptionType::JobSeeker, sub_id)
+        .await
+        .map_err(ApiError::internal_error)?;
+
     // Get the subscription details
     let subscription = db
         .collection::<Subscription>("subscriptions")
@@ -267,7 +288,8 @@ pub async fn get_job_seeker_subscription_status(
                 "plan_name": sub.plan_name,
                 "status": format!("{:?}", sub.status),
                 "expires_at": sub.expires_at,
-                "auto_renew": sub.auto_renew
+                "auto_renew": sub.auto_renew,
+                "is_trial": sub.is_trial
             }
         }))))
     } else {
@@ -454,12 +476,96 @@ pub struct CreateJobDto {
     pub salary_min: Option<f64>,
     pub salary_max: Option<f64>,
     pub location: Option<String>,
+    pub latitude: Option<f64>, // Both or neither
+    pub longitude: Option<f64>,
+    pub job_type: Option<JobType>,
+    pub work_mode: Option<WorkMode>,
+    pub experience_min: Option<i32>,
+    pub experience_max: Option<i32>,
+    pub category_id: Option<String>,
+    pub subcategory_id: Option<String>,
+    pub openings: Option<i32>, // Defaults to 1
     pub hr_name: Option<String>,
     pub hr_email: Option<String>,
     pub hr_contact: Option<String>,
     pub company_document_url: Option<String>,
 }
 
+/// GeoJSON point from optional coordinates; both or neither must be given
+fn geo_point(latitude: Option<f64>, longitude: Option<f64>) -> Result<Option<GeoLocation>, ApiError> {
+    match (latitude, longitude) {
+        (None, None) => Ok(None),
+        (Some(latitude), Some(longitude)) => {
+            if !(-90.0..=90.0).contains(&latitude) {
+                return Err(ApiError::bad_request("Invalid latitude"));
+            }
+            if !(-180.0..=180.0).contains(&longitude) {
+                return Err(ApiError::bad_request("Invalid longitude"));
+            }
+            Ok(Some(GeoLocation {
+                geo_type: "Point".to_string(),
+                coordinates: [longitude, latitude],
+            }))
+        }
+        _ => Err(ApiError::bad_request("Give both latitude and longitude")),
+    }
+}
+
+fn parse_optional_id(value: Option<&str>, name: &str) -> Result<Option<ObjectId>, ApiError> {
+    value
+        .filter(|v| !v.is_empty())
+        .map(|v| ObjectId::parse_str(v).map_err(|_| ApiError::bad_request(format!("Invalid {}", name))))
+        .transpose()
+}
+
+/// Check the structured fields of a new or edited post. A subcategory given on
+/// its own also sets its category.
+async fn check_job_attributes(db: &DbConn, job: &mut JobPost) -> Result<(), ApiError> {
+    if let (Some(min), Some(max)) = (job.salary_min, job.salary_max)
+        && min > max
+    {
+        return Err(ApiError::bad_request("Minimum salary cannot exceed maximum salary"));
+    }
+    if job.experience_min.is_some_and(|y| y < 0) || job.experience_max.is_some_and(|y| y < 0) {
+        return Err(ApiError::bad_request("Experience cannot be negative"));
+    }
+    if let (Some(min), Some(max)) = (job.experience_min, job.experience_max)
+        && min > max
+    {
+        return Err(ApiError::bad_request("Minimum experience cannot exceed maximum experience"));
+    }
+    if job.openings < 1 {
+        return Err(ApiError::bad_request("There must be at least one opening"));
+    }
+
+    if let Some(subcategory_id) = job.subcategory_id {
+        let subcategory = db
+            .collection::<SubCategory>("sub_categories")
+            .find_one(doc! { "_id": subcategory_id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+            .ok_or_else(|| ApiError::bad_request("Unknown subcategory"))?;
+        match job.category_id {
+            Some(category_id) if category_id != subcategory.main_category_id => {
+                return Err(ApiError::bad_request("Subcategory does not belong to the category"));
+            }
+            _ => job.category_id = Some(subcategory.main_category_id),
+        }
+    }
+    if let Some(category_id) = job.category_id {
+        let exists = db
+            .collection::<MainCategory>("main_categories")
+            .count_documents(doc! { "_id": category_id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+        if exists == 0 {
+            return Err(ApiError::bad_request("Unknown category"));
+        }
+    }
+
+    Ok(())
+}
+
 #[openapi(tag = "Jobs")]
 #[post("/jobs", data = "<dto>")]
 pub async fn create_job_post(
@@ -477,7 +583,7 @@ pub async fn create_job_post(
 
     let now = DateTime::now();
 
-    let job = crate::models::JobPost {
+    let mut job = crate::models::JobPost {
         id: None,
         title: dto.title.clone(),
         company_name: dto.company_name.clone(),
@@ -488,16 +594,30 @@ pub async fn create_job_post(
         salary_min: dto.salary_min,
         salary_max: dto.salary_max,
         location: dto.location.clone(),
+        geo_location: geo_point(dto.latitude, dto.longitude)?,
+        job_type: dto.job_type,
+        work_mode: dto.work_mode,
+        experience_min: dto.experience_min,
+        experience_max: dto.experience_max,
+        category_id: parse_optional_id(dto.category_id.as_deref(), "category ID")?,
+        subcategory_id: parse_optional_id(dto.subcategory_id.as_deref(), "subcategory ID")?,
+        openings: dto.openings.unwrap_or(1),
         hr_name: dto.hr_name.clone(),
         hr_email: dto.hr_email.clone(),
         hr_contact: dto.hr_contact.clone(),
         company_document_url: dto.company_document_url.clone(),
-        status: "pending".to_string(),
+        status: JobStatus::Pending,
         posted_by: auth.user_id,
         applications: Vec::new(),
+        rejection_reason: None,
+        expires_at: None,
+        expiry_reminder_sent: false,
+        approved_at: None,
+        pipeline: None,
         created_at: now,
         updated_at: now,
     };
+    check_job_attributes(db, &mut job).await?;
 
     let res = db
         .collection::<crate::models::JobPost>("jobs")
@@ -519,7 +639,7 @@ pub async fn create_job_post(
 
 #[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
 pub struct MyJobsQuery {
-    pub status: Option<String>, // pending, approved, inactive
+    pub status: Option<String>, // pending, approved, rejected, inactive, closed, expired
     pub page: Option<i64>,
     pub limit: Option<i64>,
 }
@@ -535,7 +655,7 @@ pub async fn get_my_jobs(
     let limit = query.limit.unwrap_or(20).min(100);
     let skip = (page - 1) * limit;
 
-    let mut filter = doc! { "posted_by": auth.user_id };
+    let mut filter = doc! { "postedBy": auth.user_id };
     if let Some(status) = query.status {
         filter.insert("status", status);
     }
@@ -543,7 +663,7 @@ pub async fn get_my_jobs(
     let find_options = FindOptions::builder()
         .skip(skip as u64)
         .limit(limit)
-        .sort(doc! { "created_at": -1 })
+        .sort(doc! { "createdAt": -1 })
         .build();
 
     let mut cursor = db
@@ -587,8 +707,140 @@ pub struct PublicJobsQuery {
     pub limit: Option<i64>,
     pub location: Option<String>,
     pub q: Option<String>,
+    pub job_role: Option<String>,
+    pub salary_band: Option<String>, // under_25k, 25k_50k, 50k_1l, 1l_plus
+    pub job_type: Option<String>,    // fulltime, parttime, contract, freelance
+    pub work_mode: Option<String>,   // onsite, remote, hybrid
+    pub category_id: Option<String>,
+    pub subcategory_id: Option<String>,
+    pub experience: Option<i32>, // Years; jobs whose range includes it
+    pub salary_min: Option<f64>, // Jobs whose offered range overlaps this one
+    pub salary_max: Option<f64>,
+    pub latitude: Option<f64>, // With longitude, jobs within radius_km, nearest first
+    pub longitude: Option<f64>,
+    pub radius_km: Option<f64>, // Default 25, at most 200
 }
 
+/// Salary bands for faceting and filtering, by the top of the offered range
+const SALARY_BANDS: [(&str, f64, f64); 4] = [
+    ("under_25k", 0.0, 25_000.0),
+    ("25k_50k", 25_000.0, 50_000.0),
+    ("50k_1l", 50_000.0, 100_000.0),
+    ("1l_plus", 100_000.0, f64::MAX),
+];
+
+const DEFAULT_RADIUS_KM: f64 = 25.0;
+const MAX_RADIUS_KM: f64 = 200.0;
+
+/// Fields matched by the typo-tolerant fallback; the text index covers the same ones
+const JOB_SEARCH_FIELDS: [&str; 5] = ["title", "jobRole", "companyName", "requirements", "companyBrief"];
+
+/// Top of the offered range, or the bottom if there is no top
+fn salary_top() -> mongodb::bson::Bson {
+    mongodb::bson::Bson::Document(doc! { "$ifNull": ["$salaryMax", "$salaryMin"] })
+}
+
+/// Bottom of the offered range, or the top if there is no bottom
+fn salary_bottom() -> mongodb::bson::Bson {
+    mongodb::bson::Bson::Document(doc! { "$ifNull": ["$salaryMin", "$salaryMax"] })
+}
+
+/// Name of the salary band a job falls in, or "unspecified"
+fn salary_band_expr() -> mongodb::bson::Document {
+    let branches: Vec<mongodb::bson::Document> = SALARY_BANDS
+        .iter()
+        .map(|(name, min, max)| {
+            doc! {
+                "case": { "$and": [
+                    { "$gte": [salary_top(), *min] },
+                    { "$lt": [salary_top(), *max] }
+                ] },
+                "then": *name
+            }
+        })
+        .collect();
+    doc! { "$switch": { "branches": branches, "default": "unspecified" } }
+}
+
+struct JobSearchHit {
+    job: JobPost,
+    relevance: Option<f64>,  // When ranked by text relevance
+    distance_m: Option<f64>, // When searching by radius
+}
+
+struct JobSearchPage {
+    hits: Vec<JobSearchHit>,
+    total: i64,
+    facets: serde_json::Value,
+}
+
+/// One page of jobs from the `head` stages plus facet counts over all matches
+async fn search_jobs(
+    db: &DbConn,
+    mut pipeline: Vec<mongodb::bson::Document>,
+    sort: mongodb::bson::Document,
+    skip: i64,
+    limit: i64,
+) -> Result<JobSearchPage, ApiError> {
+    pipeline.push(doc! {
+        "$facet": {
+            "results": [{ "$sort": sort }, { "$skip": skip }, { "$limit": limit }],
+            "total": [{ "$count": "count" }],
+            "job_role": search::facet("jobRole", false),
+            "job_type": search::facet("jobType", false),
+            "work_mode": search::facet("workMode", false),
+            "location": search::facet("location", false),
+            "salary_band": [
+                { "$group": { "_id": salary_band_expr(), "count": { "$sum": 1 } } },
+                { "$sort": { "count": -1 } }
+            ]
+        }
+    });
+
+    let result = db
+        .collection::<JobPost>("jobs")
+        .aggregate(pipeline, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_next()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?
+        .unwrap_or_default();
+
+    let mut hits = Vec::new();
+    for item in result.get_array("results").map(|a| a.as_slice()).unwrap_or_default() {
+        let Some(job_doc) = item.as_document() else { continue };
+        let relevance = job_doc.get_f64("relevance").ok();
+        let distance_m = job_doc.get_f64("distance").ok();
+        let job: JobPost = mongodb::bson::from_document(job_doc.clone())
+            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
+        hits.push(JobSearchHit { job, relevance, distance_m });
+    }
+    let total = result
+        .get_array("total")
+        .ok()
+        .and_then(|t| t.first())
+        .and_then(|t| t.as_document())
+        .and_then(|t| t.get_i32("count").ok())
+        .unwrap_or_default() as i64;
+
+    Ok(JobSearchPage {
+        hits,
+        total,
+        facets: serde_json::json!({
+            "job_role": search::facet_counts(result.get("job_role")),
+            "job_type": search::facet_counts(result.get("job_type")),
+            "work_mode": search::facet_counts(result.get("work_mode")),
+            "location": search::facet_counts(result.get("location")),
+            "salary_band": search::facet_counts(result.get("salary_band")),
+        }),
+    })
+}
+
+/// Approved jobs with facet counts. With `q`, results are ranked by relevance
+/// and carry highlights; if nothing matches exactly, a typo-tolerant prefix
+/// match is used instead and `fuzzy` is set. With coordinates, jobs are
+/// limited to the radius and ordered by distance.
 #[openapi(tag = "Jobs")]
 #[get("/jobs?<query..>")]
 pub async fn get_public_jobs(
@@ -596,60 +848,153 @@ pub async fn get_public_jobs(
     query: PublicJobsQuery,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     let page = query.page.unwrap_or(1).max(1);
-    let limit = query.limit.unwrap_or(20).min(100);
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
     let skip = (page - 1) * limit;
 
-    let mut filter = doc! { "status": "approved" };
+    let mut filter = doc! { "status": JobStatus::Approved.as_str() };
+    let mut conditions: Vec<mongodb::bson::Document> = Vec::new();
+    let mut exprs: Vec<mongodb::bson::Document> = Vec::new();
 
-    if let Some(location) = query.location {
-        filter.insert("location", location);
-    }
-
-    if let Some(q) = query.q {
-        if !q.is_empty() {
-            filter.insert(
-                "$or",
-                vec![
-                    doc! { "title": { "$regex": &q, "$options": "i" } },
-                    doc! { "company_name": { "$regex": &q, "$options": "i" } },
-                    doc! { "job_role": { "$regex": &q, "$options": "i" } },
-                ],
-            );
-        }
+    if let Some(location) = query.location.as_deref().map(str::trim).filter(|l| !l.is_empty()) {
+        filter.insert("location", doc! { "$regex": regex::escape(location), "$options": "i" });
     }
 
-    let find_options = FindOptions::builder()
-        .skip(skip as u64)
-        .limit(limit)
-        .sort(doc! { "created_at": -1 })
-        .build();
+    if let Some(job_role) = query.job_role.as_deref().filter(|r| !r.is_empty()) {
+        filter.insert("jobRole", job_role);
+    }
 
-    let mut cursor = db
-        .collection::<crate::models::JobPost>("jobs")
-        .find(filter.clone(), find_options)
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    if let Some(job_type) = query.job_type.as_deref().filter(|t| !t.is_empty()) {
+        let job_type = JobType::parse(job_type).ok_or_else(|| ApiError::bad_request("Unknown job type"))?;
+        filter.insert("jobType", job_type.as_str());
+    }
 
-    let mut jobs: Vec<crate::models::JobPostResponse> = Vec::new();
-    while cursor
-        .advance()
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?
+    if let Some(work_mode) = query.work_mode.as_deref().filter(|m| !m.is_empty()) {
+        let work_mode = WorkMode::parse(work_mode).ok_or_else(|| ApiError::bad_request("Unknown work mode"))?;
+        filter.insert("workMode", work_mode.as_str());
+    }
+
+    if let Some(category_id) = parse_optional_id(query.category_id.as_deref(), "category ID")? {
+        filter.insert("categoryId", category_id);
+    }
+
+    if let Some(subcategory_id) = parse_optional_id(query.subcategory_id.as_deref(), "subcategory ID")? {
+        filter.insert("subcategoryId", subcategory_id);
+    }
+
+    if let Some(years) = query.experience {
+        // An open end of the job's range accepts anyone on that side
+        conditions.push(doc! { "$or": [{ "experienceMin": null }, { "experienceMin": { "$lte": years } }] });
+        conditions.push(doc! { "$or": [{ "experienceMax": null }, { "experienceMax": { "$gte": years } }] });
+    }
+
+    if let Some(band) = query.salary_band.as_deref().filter(|b| !b.is_empty()) {
+        let (_, min, max) = SALARY_BANDS
+            .iter()
+            .find(|(name, _, _)| *name == band)
+            .ok_or_else(|| ApiError::bad_request("Unknown salary band"))?;
+        exprs.push(doc! { "$gte": [salary_top(), *min] });
+        exprs.push(doc! { "$lt": [salary_top(), *max] });
+    }
+
+    // Offered and wanted ranges overlap; jobs without a salary don't match
+    if let (Some(min), Some(max)) = (query.salary_min, query.salary_max)
+        && min > max
     {
-        let job = cursor
-            .deserialize_current()
-            .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
-        jobs.push(job.into());
+        return Err(ApiError::bad_request("Minimum salary cannot exceed maximum salary"));
+    }
+    if let Some(min) = query.salary_min {
+        exprs.push(doc! { "$gte": [salary_top(), min] });
+    }
+    if let Some(max) = query.salary_max {
+        exprs.push(doc! { "$and": [{ "$ne": [salary_bottom(), null] }, { "$lte": [salary_bottom(), max] }] });
     }
 
-    let total = db
-        .collection::<crate::models::JobPost>("jobs")
-        .count_documents(filter, None)
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+    if !conditions.is_empty() {
+        filter.insert("$and", conditions);
+    }
+    if !exprs.is_empty() {
+        filter.insert("$expr", doc! { "$and": exprs });
+    }
+
+    let geo_near = match geo_point(query.latitude, query.longitude)? {
+        Some(point) => {
+            let radius_km = query.radius_km.unwrap_or(DEFAULT_RADIUS_KM);
+            if radius_km <= 0.0 || radius_km > MAX_RADIUS_KM {
+                return Err(ApiError::bad_request(format!(
+                    "Radius must be more than 0 and at most {} km",
+                    MAX_RADIUS_KM
+                )));
+            }
+            let [longitude, latitude] = point.coordinates;
+            Some(MatchingService::geo_near_stage_on("geoLocation", longitude, latitude, radius_km * 1000.0))
+        }
+        None => None,
+    };
+
+    let terms = search::terms(query.q.as_deref().unwrap_or_default());
+    let with_fuzzy = |filter: &mongodb::bson::Document| {
+        doc! { "$and": [filter.clone(), search::fuzzy_filter(&terms, &JOB_SEARCH_FIELDS)] }
+    };
+    let newest = doc! { "createdAt": -1 };
+
+    let (found, fuzzy) = if let Some(geo_near) = geo_near {
+        // $text can't follow $geoNear, so radius searches match words by prefix
+        let filter = if terms.is_empty() { filter } else { with_fuzzy(&filter) };
+        let pipeline = vec![geo_near, doc! { "$match": filter }];
+        (search_jobs(db, pipeline, doc! { "distance": 1 }, skip, limit).await?, !terms.is_empty())
+    } else if terms.is_empty() {
+        (search_jobs(db, vec![doc! { "$match": filter }], newest, skip, limit).await?, false)
+    } else {
+        let mut text_filter = filter.clone();
+        text_filter.extend(search::text_filter(&terms));
+        let pipeline = vec![
+            doc! { "$match": text_filter },
+            doc! { "$addFields": { "relevance": { "$meta": "textScore" } } },
+        ];
+        let found = search_jobs(db, pipeline, doc! { "relevance": -1, "createdAt": -1 }, skip, limit).await?;
+        if found.total > 0 {
+            (found, false)
+        } else {
+            let pipeline = vec![doc! { "$match": with_fuzzy(&filter) }];
+            (search_jobs(db, pipeline, newest, skip, limit).await?, true)
+        }
+    };
+
+    let jobs: Vec<serde_json::Value> = found
+        .hits
+        .into_iter()
+        .map(|hit| {
+            let job = hit.job;
+            let highlights = (!terms.is_empty()).then(|| {
+                let requirements = job.requirements.clone().unwrap_or_default().join("; ");
+                search::highlights(
+                    &[
+                        ("title", job.title.as_str()),
+                        ("job_role", job.job_role.as_str()),
+                        ("company_name", job.company_name.as_str()),
+                        ("requirements", requirements.as_str()),
+                        ("company_brief", job.company_brief.as_deref().unwrap_or_default()),
+                    ],
+                    &terms,
+                )
+            });
+            let mut data = serde_json::json!(JobPostResponse::from(job));
+            if let Some(highlights) = highlights {
+                data["highlights"] = highlights;
+                data["relevance"] = serde_json::json!(hit.relevance);
+            }
+            if let Some(distance_m) = hit.distance_m {
+                data["distance_km"] = serde_json::json!((distance_m / 100.0).round() / 10.0);
+            }
+            data
+        })
+        .collect();
+    let total = found.total;
 
     Ok(Json(ApiResponse::success(serde_json::json!({
         "jobs": jobs,
+        "facets": found.facets,
+        "fuzzy": fuzzy,
         "pagination": {
             "page": page,
             "limit": limit,
@@ -677,7 +1022,7 @@ pub async fn get_job_by_id(
         .ok_or_else(|| ApiError::not_found("Job not found"))?;
 
     // If job is not approved, only owner or admin should see
-    if job.status != "approved" {
+    if job.status != JobStatus::Approved {
         if let Some(auth) = auth {
             if auth.user_id != job.posted_by {
                 return Err(ApiError::not_found("Job not found"));
@@ -692,6 +1037,45 @@ pub async fn get_job_by_id(
     Ok(Json(ApiResponse::success(serde_json::json!(response))))
 }
 
+/// The poster's own phone and email, beyond the job's HR contact. The first reveal
+/// of a poster uses one of the caller's plan `contact_reveals`.
+#[openapi(tag = "Jobs")]
+#[post("/jobs/<job_id>/contact")]
+pub async fn reveal_job_contact(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let object_id =
+        ObjectId::parse_str(&job_id).map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+
+    let job = db
+        .collection::<JobPost>("jobs")
+        .find_one(doc! { "_id": object_id, "status": JobStatus::Approved.as_str() }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job not found"))?;
+    if job.posted_by == auth.user_id {
+        return Err(ApiError::bad_request("This is your own job post"));
+    }
+
+    let reveal_id = EntitlementService::reveal_contact(db, auth.user_id, job.posted_by).await?;
+
+    let poster = db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": job.posted_by }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Poster not found"))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "name": poster.name,
+        "mobile": poster.mobile,
+        "email": poster.email,
+        "reveal_id": reveal_id.to_hex()
+    }))))
+}
+
 #[openapi(tag = "Jobs")]
 #[post("/jobs/<job_id>/document", data = "<file>")]
 pub async fn upload_job_document(
@@ -763,6 +1147,386 @@ pub async fn upload_job_document(
     )))
 }
 
+// ==================== JOB POST LIFECYCLE (OWNER) ====================
+
+/// Job posted by the user
+async fn find_own_job(db: &DbConn, job_id: &str, user_id: ObjectId) -> Result<JobPost, ApiError> {
+    let object_id =
+        ObjectId::parse_str(job_id).map_err(|_| ApiError::bad_request("Invalid job ID"))?;
+
+    db.collection::<JobPost>("jobs")
+        .find_one(doc! { "_id": object_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .filter(|job| job.posted_by == user_id)
+        .ok_or_else(|| ApiError::not_found("Job not found"))
+}
+
+/// Move an owned post to `to` if its lifecycle allows it
+async fn owner_transition(
+    db: &DbConn,
+    job: &JobPost,
+    to: JobStatus,
+    extra: mongodb::bson::Document,
+) -> Result<JobPostResponse, ApiError> {
+    if !job.status.can_move_to(to) {
+        return Err(ApiError::bad_request(format!(
+            "A {} job cannot be moved to {}",
+            job.status.as_str(),
+            to.as_str()
+        )));
+    }
+
+    JobPostService::transition(db, job, to, extra)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update job: {}", e)))?
+        .map(JobPostResponse::from)
+        .ok_or_else(|| ApiError::bad_request("Job was updated by someone else. Please refresh"))
+}
+
+/// Edit a pending, approved or rejected post. It goes back to moderation and is
+/// hidden until approved again.
+#[openapi(tag = "Jobs")]
+#[put("/jobs/<job_id>", data = "<dto>")]
+pub async fn update_job_post(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    dto: Json<UpdateJobPostDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_own_job(db, &job_id, auth.user_id).await?;
+    if !matches!(job.status, JobStatus::Pending | JobStatus::Approved | JobStatus::Rejected) {
+        return Err(ApiError::bad_request(format!(
+            "A {} job cannot be edited. Reopen it first",
+            job.status.as_str()
+        )));
+    }
+
+    let previous_status = job.status;
+    let mut edited = job.clone();
+    // Stored (camelCase) names of the fields this edit touches
+    let mut changed: Vec<&str> = Vec::new();
+    if let Some(ref title) = dto.title {
+        if title.trim().is_empty() {
+            return Err(ApiError::bad_request("Title cannot be empty"));
+        }
+        edited.title = title.clone();
+        changed.push("title");
+    }
+    if let Some(ref company_name) = dto.company_name {
+        if company_name.trim().is_empty() {
+            return Err(ApiError::bad_request("Company name cannot be empty"));
+        }
+        edited.company_name = company_name.clone();
+        changed.push("companyName");
+    }
+    if let Some(ref job_role) = dto.job_role {
+        edited.job_role = job_role.clone();
+        changed.push("jobRole");
+    }
+    if dto.company_brief.is_some() {
+        edited.company_brief = dto.company_brief.clone();
+        changed.push("companyBrief");
+    }
+    if dto.eligibility.is_some() {
+        edited.eligibility = dto.eligibility.clone();
+        changed.push("eligibility");
+    }
+    if dto.requirements.is_some() {
+        edited.requirements = dto.requirements.clone();
+        changed.push("requirements");
+    }
+    if dto.salary_min.is_some() {
+        edited.salary_min = dto.salary_min;
+        changed.push("salaryMin");
+    }
+    if dto.salary_max.is_some() {
+        edited.salary_max = dto.salary_max;
+        changed.push("salaryMax");
+    }
+    if dto.location.is_some() {
+        edited.location = dto.location.clone();
+        changed.push("location");
+    }
+    if dto.latitude.is_some() || dto.longitude.is_some() {
+        edited.geo_location = geo_point(dto.latitude, dto.longitude)?;
+        changed.push("geoLocation");
+    }
+    if dto.job_type.is_some() {
+        edited.job_type = dto.job_type;
+        changed.push("jobType");
+    }
+    if dto.work_mode.is_some() {
+        edited.work_mode = dto.work_mode;
+        changed.push("workMode");
+    }
+    if dto.experience_min.is_some() {
+        edited.experience_min = dto.experience_min;
+        changed.push("experienceMin");
+    }
+    if dto.experience_max.is_some() {
+        edited.experience_max = dto.experience_max;
+        changed.push("experienceMax");
+    }
+    if dto.category_id.is_some() {
+        edited.category_id = parse_optional_id(dto.category_id.as_deref(), "category ID")?;
+        // A new category needs its own subcategory
+        if dto.subcategory_id.is_none() && edited.category_id != job.category_id {
+            edited.subcategory_id = None;
+        }
+    }
+    if dto.subcategory_id.is_some() {
+        edited.subcategory_id = parse_optional_id(dto.subcategory_id.as_deref(), "subcategory ID")?;
+        if dto.category_id.is_none() && edited.subcategory_id.is_some() {
+            // Taken from the subcategory by check_job_attributes
+            edited.category_id = None;
+        }
+    }
+    if dto.category_id.is_some() || dto.subcategory_id.is_some() {
+        // Either one can change the other
+        changed.extend(["categoryId", "subcategoryId"]);
+    }
+    if let Some(openings) = dto.openings {
+        edited.openings = openings;
+        changed.push("openings");
+    }
+    if dto.hr_name.is_some() {
+        edited.hr_name = dto.hr_name.clone();
+        changed.push("hrName");
+    }
+    if dto.hr_email.is_some() {
+        edited.hr_email = dto.hr_email.clone();
+        changed.push("hrEmail");
+    }
+    if dto.hr_contact.is_some() {
+        edited.hr_contact = dto.hr_contact.clone();
+        changed.push("hrContact");
+    }
+    check_job_attributes(db, &mut edited).await?;
+
+    edited.status = JobStatus::Pending;
+    edited.rejection_reason = None;
+    edited.updated_at = DateTime::now();
+
+    // Write only what was edited, so concurrent updates to other fields
+    // (e.g. new applications) aren't reverted
+    let stored = mongodb::bson::to_document(&edited)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+    let mut set = doc! {
+        "status": edited.status.as_str(),
+        "rejectionReason": null,
+        "updatedAt": edited.updated_at
+    };
+    let mut unset = doc! {};
+    for field in changed {
+        match stored.get(field) {
+            Some(value) => set.insert(field, value.clone()),
+            None => unset.insert(field, ""),
+        };
+    }
+    let mut update = doc! { "$set": set };
+    if !unset.is_empty() {
+        update.insert("$unset", unset);
+    }
+
+    let result = db
+        .collection::<JobPost>("jobs")
+        .update_one(
+            doc! { "_id": job.id, "status": previous_status.as_str() },
+            update,
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update job: {}", e)))?;
+    if result.matched_count == 0 {
+        return Err(ApiError::bad_request("Job was updated by someone else. Please refresh"));
+    }
+
+    let _ = crate::routes::notification::create_admin_notification(
+        db.inner(),
+        "job_edited".to_string(),
+        "Job Post Edited".to_string(),
+        format!("The job \"{}\" was edited and is pending review.", edited.title),
+        job.id,
+    )
+    .await;
+
+    let response: JobPostResponse = edited.into();
+    Ok(Json(ApiResponse::success_with_message(
+        "Job updated and sent for review".to_string(),
+        serde_json::json!(response),
+    )))
+}
+
+/// Stop accepting applications
+#[openapi(tag = "Jobs")]
+#[put("/jobs/<job_id>/close")]
+pub async fn close_job_post(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_own_job(db, &job_id, auth.user_id).await?;
+    let response = owner_transition(db, &job, JobStatus::Closed, doc! {}).await?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Job closed".to_string(),
+        serde_json::json!(response),
+    )))
+}
+
+/// Put a closed or expired post back up. Expired posts get a fresh expiry date.
+#[openapi(tag = "Jobs")]
+#[put("/jobs/<job_id>/reopen")]
+pub async fn reopen_job_post(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_own_job(db, &job_id, auth.user_id).await?;
+    if !matches!(job.status, JobStatus::Closed | JobStatus::Expired) {
+        return Err(ApiError::bad_request("Only closed or expired jobs can be reopened"));
+    }
+
+    let mut extra = doc! {};
+    if job.expires_at.is_none_or(|at| at <= DateTime::now()) {
+        extra.insert("expiresAt", JobPostService::fresh_expiry());
+        extra.insert("expiryReminderSent", false);
+    }
+    let response = owner_transition(db, &job, JobStatus::Approved, extra).await?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Job reopened".to_string(),
+        serde_json::json!(response),
+    )))
+}
+
+/// Push back the expiry of a live post
+#[openapi(tag = "Jobs")]
+#[put("/jobs/<job_id>/renew")]
+pub async fn renew_job_post(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let job = find_own_job(db, &job_id, auth.user_id).await?;
+    if job.status != JobStatus::Approved {
+        return Err(ApiError::bad_request("Only live jobs can be renewed. Reopen it instead"));
+    }
+
+    let expires_at = JobPostService::fresh_expiry();
+    let result = db
+        .collection::<JobPost>("jobs")
+        .update_one(
+            doc! { "_id": job.id, "status": JobStatus::Approved.as_str() },
+            doc! {
+                "$set": {
+                    "expiresAt": expires_at,
+                    "expiryReminderSent": false,
+                    "updatedAt": DateTime::now()
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to renew job: {}", e)))?;
+    if result.matched_count == 0 {
+        return Err(ApiError::bad_request("Job was updated by someone else. Please refresh"));
+    }
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Job renewed".to_string(),
+        serde_json::json!({ "job_id": job_id, "expires_at": expires_at }),
+    )))
+}
+
+// ==================== MATCHING ====================
+
+const MAX_MATCHES: i64 = 50;
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct MatchQuery {
+    pub limit: Option<i64>, // Default 20, at most 50
+}
+
+/// Jobs ranked for the signed-in job seeker, each with its match score and reasons
+#[openapi(tag = "Jobs")]
+#[get("/jobs/recommended?<query..>")]
+pub async fn get_recommended_jobs(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: MatchQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let limit = query.limit.unwrap_or(20).clamp(1, MAX_MATCHES);
+
+    let profile = db
+        .collection::<JobSeekerProfile>("job_seeker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Create a job seeker profile to get recommendations"))?;
+
+    let ranked = JobMatchingService::recommend_jobs(db, &profile, limit as usize)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to match jobs: {}", e)))?;
+
+    let jobs: Vec<serde_json::Value> = ranked
+        .into_iter()
+        .map(|(job, score)| {
+            let mut data = serde_json::json!(JobPostResponse::from(job));
+            data["match"] = serde_json::json!(score);
+            data
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({ "jobs": jobs }))))
+}
+
+/// Job seekers ranked for one of the user's posts. Premium seekers are boosted,
+/// and seekers who already applied show their application status.
+#[openapi(tag = "Jobs")]
+#[get("/jobs/<job_id>/candidates?<query..>")]
+pub async fn get_job_candidates(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    job_id: String,
+    query: MatchQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let limit = query.limit.unwrap_or(20).clamp(1, MAX_MATCHES);
+    let job = find_own_job(db, &job_id, auth.user_id).await?;
+
+    let ranked = JobMatchingService::rank_candidates(db, &job, limit as usize)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to match candidates: {}", e)))?;
+
+    let profile_ids: Vec<ObjectId> = ranked.iter().filter_map(|(profile, _)| profile.id).collect();
+    let applied: HashMap<ObjectId, ApplicationStatus> = db
+        .collection::<JobApplication>("applications")
+        .find(doc! { "job_id": job.id, "seeker_profile_id": { "$in": profile_ids } }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect::<Vec<JobApplication>>()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?
+        .into_iter()
+        .map(|application| (application.seeker_profile_id, application.status))
+        .collect();
+
+    let candidates: Vec<serde_json::Value> = ranked
+        .into_iter()
+        .map(|(profile, score)| {
+            let application_status = profile.id.and_then(|id| applied.get(&id).copied());
+            serde_json::json!({
+                "profile": JobSeekerProfileResponse::from(profile),
+                "match": score,
+                "application_status": application_status
+            })
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({ "candidates": candidates }))))
+}
+
 #[openapi(tag = "JobSeeker")]
 #[put("/job-seeker/profile", data = "<dto>")]
 pub async fn update_job_seeker_profile(
@@ -913,7 +1677,7 @@ pub async fn search_job_seekers(
         .skip(skip as u64)
         .limit(limit)
         .sort(doc! {
-            "subscription_plan": -1,
+            "priority_listing": -1,
             "profile_views": -1,
             "created_at": -1
         })
@@ -983,3 +1747,91 @@ pub async fn delete_job_seeker_profile(
         "message": "Job seeker profile deactivated successfully"
     }))))
 }
+
+/// Draft profile fields read from a resume PDF uploaded through /upload/document.
+/// Nothing is saved; review the draft and send it to POST /job-seeker/profile.
+#[openapi(tag = "JobSeeker")]
+#[post("/job-seeker/resume/parse", data = "<dto>")]
+pub async fn parse_resume(
+    _auth: AuthGuard,
+    dto: Json<ParseResumeDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let path = uploaded_document_path(dto.resume_url.trim())
+        .ok_or_else(|| ApiError::bad_request("Upload the resume with /upload/document first"))?;
+    if get_extension_from_filename(&path).as_deref() != Some("pdf") {
+        return Err(ApiError::bad_request("Only PDF resumes can be parsed"));
+    }
+
+    let metadata = tokio::fs::metadata(&path)
+        .await
+        .map_err(|_| ApiError::not_found("Uploaded resume not found"))?;
+    if metadata.len() > MAX_RESUME_BYTES {
+        return Err(ApiError::bad_request(format!(
+            "Resumes over {} MB can't be parsed",
+            MAX_RESUME_BYTES / (1024 * 1024)
+        )));
+    }
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to read resume: {}", e)))?;
+
+    let text = ResumeService::extract_text(bytes).await.map_err(ApiError::bad_request)?;
+    if text.trim().is_empty() {
+        return Err(ApiError::bad_request(
+            "No text found in this PDF. Scanned resumes can't be read; please fill in your profile manually",
+        ));
+    }
+
+    let mut draft = ResumeService::parse(&text);
+    draft.resume_url = Some(dto.resume_url.trim().to_string());
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Check the details read from your resume before saving your profile".to_string(),
+        serde_json::json!(draft),
+    )))
+}
+
+/// Render the seeker's profile as a resume PDF, save it with their uploaded
+/// documents and make it the profile's resume. Replaces any earlier resume_url.
+#[openapi(tag = "JobSeeker")]
+#[post("/job-seeker/resume/generate", data = "<dto>")]
+pub async fn generate_resume(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<GenerateResumeDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let profile = db
+        .collection::<JobSeekerProfile>("job_seeker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Job seeker profile not found"))?;
+
+    let user = db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+
+    let template = dto.template.unwrap_or(ResumeTemplate::Classic);
+    let bytes = ResumeService::render_pdf(&profile, &user, template).map_err(ApiError::bad_request)?;
+    let resume_url = save_document(&bytes, "pdf").await?;
+
+    db.collection::<JobSeekerProfile>("job_seeker_profiles")
+        .update_one(
+            doc! { "_id": profile.id },
+            doc! { "$set": { "resume_url": &resume_url, "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update profile: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Resume created".to_string(),
+        serde_json::json!({
+            "resume_url": resume_url,
+            "template": template.as_str()
+        }),
+    )))
+}
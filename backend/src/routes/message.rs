@@ -0,0 +1,460 @@
+use crate::db::DbConn;
+use crate::guards::AuthGuard;
+use crate::models::{
+    Booking, Conversation, Message, MessageAttachment, MessageListQuery, SendMessageDto,
+    StartConversationDto, User, WorkerProfile,
+};
+use crate::services::{EntitlementService, EventHub, MessagingService};
+use crate::utils::{ApiError, ApiResponse, is_uploaded_file};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, doc};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use rocket::futures::TryStreamExt;
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::Json;
+use rocket::tokio::select;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::{Shutdown, State};
+use rocket_okapi::openapi;
+
+const MAX_MESSAGE_LENGTH: usize = 2000;
+const MAX_IMAGES_PER_MESSAGE: usize = 5;
+const MESSAGE_EVENTS: [&str; 2] = ["message", "read"];
+
+// ============================================================================
+// SHARED MESSAGING LOGIC
+// ============================================================================
+
+/// Load a conversation the user takes part in
+async fn find_conversation(
+    db: &DbConn,
+    conversation_id: &str,
+    user_id: ObjectId,
+) -> Result<Conversation, ApiError> {
+    let conversation_oid = ObjectId::parse_str(conversation_id)
+        .map_err(|_| ApiError::bad_request("Invalid conversation ID"))?;
+
+    db.collection::<Conversation>("conversations")
+        .find_one(doc! { "_id": conversation_oid, "participants": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Conversation not found"))
+}
+
+async fn contact_unlocked(db: &DbConn, conversation: &Conversation) -> Result<bool, ApiError> {
+    MessagingService::contact_unlocked(db, conversation)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))
+}
+
+/// Only images stored by the upload endpoints can be attached
+fn image_attachment(url: &str) -> Result<MessageAttachment, ApiError> {
+    if !is_uploaded_file(url, "images") {
+        return Err(ApiError::bad_request("Attachments must be images uploaded via /upload/image"));
+    }
+
+    Ok(MessageAttachment {
+        kind: "image".to_string(),
+        url: url.to_string(),
+    })
+}
+
+// ============================================================================
+// MESSAGING ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Messaging")]
+#[post("/messages/conversations", data = "<dto>")]
+pub async fn start_conversation(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<StartConversationDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (customer_id, worker_id, worker_user_id) = match (&dto.booking_id, &dto.worker_id) {
+        (Some(booking_id), _) => {
+            let booking_oid = ObjectId::parse_str(booking_id)
+                .map_err(|_| ApiError::bad_request("Invalid booking ID"))?;
+            let booking = db
+                .collection::<Booking>("bookings")
+                .find_one(
+                    doc! {
+                        "_id": booking_oid,
+                        "$or": [{ "customer_id": auth.user_id }, { "worker_user_id": auth.user_id }]
+                    },
+                    None,
+                )
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+                .ok_or_else(|| ApiError::not_found("Booking not found"))?;
+            (booking.customer_id, booking.worker_id, booking.worker_user_id)
+        }
+        (None, Some(worker_id)) => {
+            let worker_oid = ObjectId::parse_str(worker_id)
+                .map_err(|_| ApiError::bad_request("Invalid worker ID"))?;
+            let worker = db
+                .collection::<WorkerProfile>("worker_profiles")
+                .find_one(doc! { "_id": worker_oid }, None)
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+                .ok_or_else(|| ApiError::not_found("Worker not found"))?;
+            if worker.user_id == auth.user_id {
+                return Err(ApiError::bad_request("You cannot message yourself"));
+            }
+            (auth.user_id, worker_oid, worker.user_id)
+        }
+        (None, None) => return Err(ApiError::bad_request("Provide a worker_id or booking_id")),
+    };
+
+    let now = DateTime::now();
+    let conversation = Conversation {
+        id: None,
+        customer_id,
+        worker_id,
+        worker_user_id,
+        participants: vec![customer_id, worker_user_id],
+        last_message_preview: None,
+        last_message_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+    let on_insert = mongodb::bson::to_document(&conversation)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    // Reuse the pair's conversation if there is one
+    let conversation = db
+        .collection::<Conversation>("conversations")
+        .find_one_and_update(
+            doc! { "customer_id": customer_id, "worker_user_id": worker_user_id },
+            doc! { "$setOnInsert": on_insert },
+            FindOneAndUpdateOptions::builder()
+                .upsert(true)
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::internal_error("Failed to open conversation"))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "conversation_id": conversation.id.map(|id| id.to_hex()),
+        "contact_unlocked": contact_unlocked(db, &conversation).await?
+    }))))
+}
+
+#[openapi(tag = "Messaging")]
+#[get("/messages/conversations")]
+pub async fn list_conversations(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let conversations: Vec<Conversation> = db
+        .collection::<Conversation>("conversations")
+        .find(
+            doc! { "participants": auth.user_id },
+            FindOptions::builder().sort(doc! { "last_message_at": -1, "updated_at": -1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let other_ids: Vec<ObjectId> = conversations
+        .iter()
+        .map(|c| c.other_participant(auth.user_id))
+        .collect();
+    let users: Vec<User> = db
+        .collection::<User>("users")
+        .find(doc! { "_id": { "$in": other_ids } }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    // Unread counts per conversation in one query
+    let unread: Vec<mongodb::bson::Document> = db
+        .collection::<Message>("messages")
+        .aggregate(
+            vec![
+                doc! { "$match": { "recipient_id": auth.user_id, "read_at": null } },
+                doc! { "$group": { "_id": "$conversation_id", "count": { "$sum": 1 } } },
+            ],
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let conversations: Vec<serde_json::Value> = conversations
+        .iter()
+        .map(|c| {
+            let other_id = c.other_participant(auth.user_id);
+            let other = users.iter().find(|u| u.id == Some(other_id));
+            let unread_count = unread
+                .iter()
+                .find(|d| d.get_object_id("_id").ok() == c.id)
+                .and_then(|d| d.get_i32("count").ok())
+                .unwrap_or(0);
+
+            serde_json::json!({
+                "_id": c.id.map(|id| id.to_hex()),
+                "role": if c.customer_id == auth.user_id { "customer" } else { "worker" },
+                "worker_id": c.worker_id.to_hex(),
+                "counterpart": {
+                    "user_id": other_id.to_hex(),
+                    "name": other.and_then(|u| u.name.clone()),
+                    "profile_photo": other.and_then(|u| u.profile_photo.clone()),
+                },
+                "last_message_preview": c.last_message_preview,
+                "last_message_at": c.last_message_at,
+                "unread_count": unread_count,
+            })
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "conversations": conversations
+    }))))
+}
+
+#[openapi(tag = "Messaging")]
+#[get("/messages/conversations/<conversation_id>/messages?<query..>")]
+pub async fn get_messages(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    conversation_id: String,
+    query: MessageListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let conversation = find_conversation(db, &conversation_id, auth.user_id).await?;
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+
+    let parse_cursor = |id: &str| {
+        ObjectId::parse_str(id).map_err(|_| ApiError::bad_request("Invalid message ID"))
+    };
+
+    // ObjectIds grow with insertion time, so they double as the paging cursor
+    let mut filter = doc! { "conversation_id": conversation.id };
+    let ascending = if let Some(ref after) = query.after {
+        filter.insert("_id", doc! { "$gt": parse_cursor(after)? });
+        true
+    } else {
+        if let Some(ref before) = query.before {
+            filter.insert("_id", doc! { "$lt": parse_cursor(before)? });
+        }
+        false
+    };
+
+    let messages: Vec<Message> = db
+        .collection::<Message>("messages")
+        .find(
+            filter,
+            FindOptions::builder()
+                .sort(doc! { "_id": if ascending { 1 } else { -1 } })
+                .limit(limit)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let unlocked = contact_unlocked(db, &conversation).await?;
+    let messages: Vec<serde_json::Value> = messages
+        .iter()
+        .map(|m| MessagingService::message_json(m, unlocked))
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "messages": messages,
+        "contact_unlocked": unlocked,
+        "has_more": messages.len() as i64 == limit
+    }))))
+}
+
+#[openapi(tag = "Messaging")]
+#[post("/messages/conversations/<conversation_id>/messages", data = "<dto>")]
+pub async fn send_message(
+    db: &State<DbConn>,
+    hub: &State<EventHub>,
+    auth: AuthGuard,
+    conversation_id: String,
+    dto: Json<SendMessageDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let conversation = find_conversation(db, &conversation_id, auth.user_id).await?;
+
+    let body = dto
+        .body
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+        .map(str::to_string);
+    if body.as_ref().is_some_and(|b| b.chars().count() > MAX_MESSAGE_LENGTH) {
+        return Err(ApiError::bad_request(format!(
+            "Messages can be at most {} characters",
+            MAX_MESSAGE_LENGTH
+        )));
+    }
+
+    let image_urls = dto.image_urls.clone().unwrap_or_default();
+    if image_urls.len() > MAX_IMAGES_PER_MESSAGE {
+        return Err(ApiError::bad_request(format!(
+            "At most {} images per message",
+            MAX_IMAGES_PER_MESSAGE
+        )));
+    }
+    let attachments = image_urls
+        .iter()
+        .map(|url| image_attachment(url))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if body.is_none() && attachments.is_empty() {
+        return Err(ApiError::bad_request("Message is empty"));
+    }
+
+    let now = DateTime::now();
+    let recipient_id = conversation.other_participant(auth.user_id);
+    let mut message = Message {
+        id: None,
+        conversation_id: conversation
+            .id
+            .ok_or_else(|| ApiError::internal_error("Conversation missing ID"))?,
+        sender_id: auth.user_id,
+        recipient_id,
+        body,
+        attachments,
+        read_at: None,
+        created_at: now,
+    };
+
+    let res = db
+        .collection::<Message>("messages")
+        .insert_one(&message, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to send message: {}", e)))?;
+    message.id = res.inserted_id.as_object_id();
+
+    db.collection::<Conversation>("conversations")
+        .update_one(
+            doc! { "_id": conversation.id },
+            doc! {
+                "$set": {
+                    "last_message_preview": MessagingService::preview(&message),
+                    "last_message_at": now,
+                    "updated_at": now
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let data = MessagingService::message_json(&message, contact_unlocked(db, &conversation).await?);
+    hub.publish(vec![recipient_id, auth.user_id], "message", data.clone());
+
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "Messaging")]
+#[put("/messages/conversations/<conversation_id>/read")]
+pub async fn mark_conversation_read(
+    db: &State<DbConn>,
+    hub: &State<EventHub>,
+    auth: AuthGuard,
+    conversation_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let conversation = find_conversation(db, &conversation_id, auth.user_id).await?;
+    let now = DateTime::now();
+
+    let result = db
+        .collection::<Message>("messages")
+        .update_many(
+            doc! { "conversation_id": conversation.id, "recipient_id": auth.user_id, "read_at": null },
+            doc! { "$set": { "read_at": now } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    // Read receipt for the sender
+    if result.modified_count > 0 {
+        hub.publish(
+            vec![conversation.other_participant(auth.user_id)],
+            "read",
+            serde_json::json!({
+                "conversation_id": conversation_id,
+                "reader_id": auth.user_id.to_hex(),
+                "read_at": now
+            }),
+        );
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "marked_read": result.modified_count
+    }))))
+}
+
+/// The other participant's phone number. Free once a booking unlocks the chat,
+/// otherwise the first reveal uses one of the caller's plan `contact_reveals`.
+#[openapi(tag = "Messaging")]
+#[post("/messages/conversations/<conversation_id>/contact")]
+pub async fn reveal_contact(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    conversation_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let conversation = find_conversation(db, &conversation_id, auth.user_id).await?;
+    let other_id = conversation.other_participant(auth.user_id);
+
+    let reveal_id = if contact_unlocked(db, &conversation).await? {
+        EntitlementService::find_reveal(db, auth.user_id, other_id)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+    } else {
+        Some(EntitlementService::reveal_contact(db, auth.user_id, other_id).await?)
+    };
+
+    let other = db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": other_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "user_id": other_id.to_hex(),
+        "name": other.name,
+        "mobile": other.mobile,
+        "reveal_id": reveal_id.map(|id| id.to_hex())
+    }))))
+}
+
+/// Server-sent events for the signed-in user: "message" when one arrives and "read"
+/// when the other side reads a conversation. Clients that can't hold the stream
+/// open poll `get_messages` with `after` instead.
+#[get("/messages/stream")]
+pub async fn message_stream(hub: &State<EventHub>, auth: AuthGuard, mut shutdown: Shutdown) -> EventStream![] {
+    let mut receiver = hub.subscribe();
+    let user_id = auth.user_id;
+
+    EventStream! {
+        loop {
+            let event = select! {
+                event = receiver.recv() => match event {
+                    Ok(event) => event,
+                    Err(RecvError::Closed) => break,
+                    Err(RecvError::Lagged(_)) => continue,
+                },
+                _ = &mut shutdown => break,
+            };
+
+            // The hub also carries other streams' events, e.g. live "location" pings
+            if MESSAGE_EVENTS.contains(&event.event.as_str()) && event.recipients.contains(&user_id) {
+                yield Event::json(&event.data).event(event.event);
+            }
+        }
+    }
+}
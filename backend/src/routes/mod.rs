@@ -3,10 +3,21 @@ pub mod user;
 pub mod kyc;
 pub mod worker;
 pub mod job;
+pub mod application;
+pub mod interview;
+pub mod saved_search;
 pub mod category;
 pub mod review;
 pub mod file_upload;
 pub mod service;
 pub mod admin;
 pub mod notification;
-pub mod admin_auth;
\ No newline at end of file
+pub mod admin_auth;
+pub mod subscription;
+pub mod payment_link;
+pub mod booking;
+pub mod quote;
+pub mod service_request;
+pub mod message;
+pub mod dispute;
+pub mod tracking;
@@ -33,6 +33,34 @@ pub async fn create_admin_notification(
     Ok(())
 }
 
+// Helper function to create a notification for a specific user
+pub async fn create_user_notification(
+    db: &mongodb::Database,
+    user_id: ObjectId,
+    notification_type: String,
+    title: String,
+    body: String,
+    related_id: Option<ObjectId>,
+) -> Result<(), String> {
+    let notification = Notification {
+        id: None,
+        user_id,
+        notification_type,
+        title,
+        body,
+        related_id,
+        is_read: false,
+        created_at: BsonDateTime::now(),
+    };
+
+    db.collection::<Notification>("notifications")
+        .insert_one(notification, None)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 // Get all notifications
 #[get("/notifications?<page>&<limit>&<is_read>")]
 pub async fn get_notifications(
@@ -0,0 +1,370 @@
+use crate::db::DbConn;
+use crate::guards::AdminGuard;
+use crate::models::{
+    CreatePaymentLinkDto, PaymentLink, PaymentLinkStatus, ResendPaymentLinkDto, Subscription,
+    SubscriptionType, User, find_plan,
+};
+use crate::services::{PaymentLinkService, RazorpayService, payment_gateway};
+use crate::utils::{ApiError, ApiResponse};
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::options::FindOptions;
+use rocket::State;
+use rocket::data::{Data, ToByteUnit};
+use rocket::futures::TryStreamExt;
+use rocket::http::Status;
+use rocket::request::{self, FromRequest, Outcome, Request};
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+const SEND_CHANNELS: [&str; 2] = ["sms", "email"];
+
+/// Razorpay payloads run past Rocket's default 8 KiB string limit
+const MAX_WEBHOOK_BYTES: u64 = 1024 * 1024;
+
+fn validate_channels(send_via: &[String]) -> Result<(), ApiError> {
+    match send_via.iter().find(|c| !SEND_CHANNELS.contains(&c.as_str())) {
+        Some(c) => Err(ApiError::bad_request(format!(
+            "Invalid channel '{}'. Use 'sms' or 'email'",
+            c
+        ))),
+        None => Ok(()),
+    }
+}
+
+async fn find_link(db: &DbConn, link_id: &str) -> Result<PaymentLink, ApiError> {
+    let link_oid = ObjectId::parse_str(link_id)
+        .map_err(|_| ApiError::bad_request("Invalid payment link ID"))?;
+
+    db.collection::<PaymentLink>("payment_links")
+        .find_one(doc! { "_id": link_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Payment link not found"))
+}
+
+// ==================== PAYMENT LINK ADMIN ROUTES ====================
+
+#[openapi(tag = "Admin - Payment Links")]
+#[post("/admin/payment-links", data = "<dto>")]
+pub async fn create_payment_link(
+    db: &State<DbConn>,
+    admin: AdminGuard,
+    dto: Json<CreatePaymentLinkDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let user_oid = ObjectId::parse_str(&dto.user_id)
+        .map_err(|_| ApiError::bad_request("Invalid user ID"))?;
+
+    let user = db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": user_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+
+    let subscription_type = match dto.subscription_type.to_lowercase().as_str() {
+        "worker" => SubscriptionType::Worker,
+        "jobseeker" | "job_seeker" => SubscriptionType::JobSeeker,
+        _ => return Err(ApiError::bad_request("Invalid subscription type. Use 'worker' or 'jobseeker'")),
+    };
+
+    let plan = find_plan(&subscription_type, &dto.plan_name)
+        .ok_or_else(|| ApiError::bad_request("Invalid plan"))?;
+
+    let send_via = dto.send_via.clone().unwrap_or_else(|| vec!["sms".to_string()]);
+    validate_channels(&send_via)?;
+
+    let expires_in_hours = dto
+        .expires_in_hours
+        .unwrap_or_else(crate::config::Config::payment_link_expiry_hours);
+    if !(1..=24 * 30).contains(&expires_in_hours) {
+        return Err(ApiError::bad_request("Link expiry must be between 1 hour and 30 days"));
+    }
+
+    let active = db
+        .collection::<Subscription>("subscriptions")
+        .find_one(
+            doc! {
+                "user_id": user_oid,
+                "subscription_type": subscription_type.as_str(),
+                "status": "active",
+                "is_trial": { "$ne": true }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    if active.is_some() {
+        return Err(ApiError::bad_request("User already has an active subscription of this type"));
+    }
+
+    let open_link = db
+        .collection::<PaymentLink>("payment_links")
+        .find_one(
+            doc! {
+                "user_id": user_oid,
+                "subscription_type": subscription_type.as_str(),
+                "status": "created"
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    if open_link.is_some() {
+        return Err(ApiError::bad_request(
+            "User already has an open payment link. Resend or cancel it first",
+        ));
+    }
+
+    let link = PaymentLinkService::create(
+        db,
+        admin.admin_id,
+        &user,
+        subscription_type,
+        plan.name,
+        &send_via,
+        expires_in_hours,
+    )
+    .await
+    .map_err(|e| ApiError::internal_error(format!("Failed to create payment link: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Payment link created".to_string(),
+        serde_json::to_value(&link)
+            .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?,
+    )))
+}
+
+#[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
+pub struct PaymentLinkListQuery {
+    pub status: Option<String>,
+    pub user_id: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[openapi(tag = "Admin - Payment Links")]
+#[get("/admin/payment-links?<query..>")]
+pub async fn get_payment_links(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    query: PaymentLinkListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! {};
+    if let Some(ref status) = query.status {
+        filter.insert("status", status);
+    }
+    if let Some(ref user_id) = query.user_id {
+        let user_oid = ObjectId::parse_str(user_id)
+            .map_err(|_| ApiError::bad_request("Invalid user ID"))?;
+        filter.insert("user_id", user_oid);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let links: Vec<PaymentLink> = db
+        .collection::<PaymentLink>("payment_links")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let total = db
+        .collection::<PaymentLink>("payment_links")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "payment_links": links,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+/// Fetches the link, first syncing its status with the gateway if still open
+#[openapi(tag = "Admin - Payment Links")]
+#[get("/admin/payment-links/<link_id>")]
+pub async fn get_payment_link(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    link_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let link = find_link(db, &link_id).await?;
+
+    PaymentLinkService::refresh(db, &link)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to refresh payment link: {}", e)))?;
+
+    let link = find_link(db, &link_id).await?;
+
+    Ok(Json(ApiResponse::success(
+        serde_json::to_value(&link)
+            .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?,
+    )))
+}
+
+#[openapi(tag = "Admin - Payment Links")]
+#[post("/admin/payment-links/<link_id>/resend", data = "<dto>")]
+pub async fn resend_payment_link(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    link_id: String,
+    dto: Json<ResendPaymentLinkDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    validate_channels(&dto.send_via)?;
+
+    let link = find_link(db, &link_id).await?;
+    if link.status != PaymentLinkStatus::Created {
+        return Err(ApiError::bad_request("Only open payment links can be resent"));
+    }
+
+    let user = db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": link.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+
+    let delivered = PaymentLinkService::deliver(db, &link, &user, &dto.send_via)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to send payment link: {}", e)))?;
+
+    if delivered.is_empty() {
+        return Err(ApiError::internal_error("Payment link could not be delivered"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "delivered_via": delivered
+    }))))
+}
+
+#[openapi(tag = "Admin - Payment Links")]
+#[post("/admin/payment-links/<link_id>/cancel")]
+pub async fn cancel_payment_link(
+    db: &State<DbConn>,
+    _admin: AdminGuard,
+    link_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let link = find_link(db, &link_id).await?;
+    if link.status != PaymentLinkStatus::Created {
+        return Err(ApiError::bad_request("Only open payment links can be cancelled"));
+    }
+
+    payment_gateway()
+        .cancel_payment_link(&link.gateway_link_id)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to cancel payment link: {}", e)))?;
+
+    PaymentLinkService::close(db, &link, PaymentLinkStatus::Cancelled)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Payment link cancelled".to_string(),
+        serde_json::json!({ "id": link_id, "status": "cancelled" }),
+    )))
+}
+
+// ==================== PAYMENT GATEWAY WEBHOOK ====================
+
+/// `X-Razorpay-Signature` header of a webhook delivery
+pub struct WebhookSignature(String);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for WebhookSignature {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        match req.headers().get_one("X-Razorpay-Signature") {
+            Some(signature) => Outcome::Success(WebhookSignature(signature.to_string())),
+            None => Outcome::Error((Status::BadRequest, ())),
+        }
+    }
+}
+
+/// Razorpay webhook for payment link events (configure `payment_link.*` events)
+#[post("/payments/razorpay/webhook", data = "<body>")]
+pub async fn razorpay_webhook(
+    db: &State<DbConn>,
+    signature: WebhookSignature,
+    body: Data<'_>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let body = body
+        .open(MAX_WEBHOOK_BYTES.bytes())
+        .into_string()
+        .await
+        .map_err(|_| ApiError::bad_request("Invalid webhook payload"))?;
+    if !body.is_complete() {
+        return Err(ApiError::bad_request("Webhook payload too large"));
+    }
+    let body = body.into_inner();
+
+    RazorpayService::verify_webhook_signature(&body, &signature.0).map_err(ApiError::unauthorized)?;
+
+    let event: serde_json::Value = serde_json::from_str(&body)
+        .map_err(|_| ApiError::bad_request("Invalid webhook payload"))?;
+
+    let event_type = event.get("event").and_then(|v| v.as_str()).unwrap_or_default();
+    let link_id = event
+        .pointer("/payload/payment_link/entity/id")
+        .and_then(|v| v.as_str());
+
+    let Some(link_id) = link_id else {
+        // Not a payment link event; acknowledge so Razorpay does not retry
+        return Ok(Json(ApiResponse::success(serde_json::json!({ "handled": false }))));
+    };
+
+    match event_type {
+        "payment_link.paid" => {
+            let payment_id = event
+                .pointer("/payload/payment/entity/id")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+
+            PaymentLinkService::mark_paid(db, link_id, payment_id)
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Failed to activate subscription: {}", e)))?;
+        }
+        "payment_link.expired" | "payment_link.cancelled" => {
+            let link = db
+                .collection::<PaymentLink>("payment_links")
+                .find_one(doc! { "gateway_link_id": link_id }, None)
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+            if let Some(link) = link {
+                let status = if event_type == "payment_link.expired" {
+                    PaymentLinkStatus::Expired
+                } else {
+                    PaymentLinkStatus::Cancelled
+                };
+
+                PaymentLinkService::close(db, &link, status)
+                    .await
+                    .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+            }
+        }
+        _ => {
+            return Ok(Json(ApiResponse::success(serde_json::json!({ "handled": false }))));
+        }
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({ "handled": true }))))
+}
@@ -0,0 +1,474 @@
+use crate::db::DbConn;
+use crate::guards::AuthGuard;
+use crate::models::{
+    AcceptQuoteDto, Booking, BookingReasonDto, BookingStatus, CreateQuoteDto, Quote,
+    QuoteListQuery, QuoteStatus, UpdateQuoteDto, User, WorkerProfile,
+};
+use crate::routes::booking::check_schedule;
+use crate::services::QuoteService;
+use crate::utils::time::{DAY_MILLIS, add_millis};
+use crate::utils::{ApiError, ApiResponse, PdfFile};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, doc};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use rocket::State;
+use rocket::futures::TryStreamExt;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+/// Longest validity a quote can be sent with
+const MAX_VALID_DAYS: i32 = 60;
+
+// ============================================================================
+// SHARED QUOTE LOGIC
+// ============================================================================
+
+#[derive(PartialEq)]
+enum Party {
+    Customer,
+    Worker,
+}
+
+/// Load a quote the user takes part in, and which side they are on. Drafts are
+/// only visible to the worker.
+async fn find_quote(db: &DbConn, quote_id: &str, user_id: ObjectId) -> Result<(Quote, Party), ApiError> {
+    let quote_oid =
+        ObjectId::parse_str(quote_id).map_err(|_| ApiError::bad_request("Invalid quote ID"))?;
+
+    let quote = db
+        .collection::<Quote>("quotes")
+        .find_one(doc! { "_id": quote_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Quote not found"))?;
+
+    let party = if quote.worker_user_id == user_id {
+        Party::Worker
+    } else if quote.customer_id == user_id && quote.status != QuoteStatus::Draft {
+        Party::Customer
+    } else {
+        return Err(ApiError::not_found("Quote not found"));
+    };
+
+    Ok((quote, party))
+}
+
+fn check_tax(tax_percent: f64) -> Result<f64, ApiError> {
+    if !(0.0..=50.0).contains(&tax_percent) {
+        return Err(ApiError::bad_request("Tax must be between 0 and 50 percent"));
+    }
+    Ok(tax_percent)
+}
+
+fn check_valid_days(valid_days: i32) -> Result<i32, ApiError> {
+    if !(1..=MAX_VALID_DAYS).contains(&valid_days) {
+        return Err(ApiError::bad_request(format!(
+            "Validity must be between 1 and {} days",
+            MAX_VALID_DAYS
+        )));
+    }
+    Ok(valid_days)
+}
+
+fn quote_json(quote: &Quote) -> Result<serde_json::Value, ApiError> {
+    serde_json::to_value(quote)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))
+}
+
+// ============================================================================
+// QUOTE ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Quote")]
+#[post("/quote", data = "<dto>")]
+pub async fn create_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CreateQuoteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking_oid = ObjectId::parse_str(&dto.booking_id)
+        .map_err(|_| ApiError::bad_request("Invalid booking ID"))?;
+
+    // Quotes follow up on a request the worker already has with the customer
+    let booking = db
+        .collection::<Booking>("bookings")
+        .find_one(doc! { "_id": booking_oid, "worker_user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Booking not found"))?;
+
+    if matches!(
+        booking.status,
+        BookingStatus::Declined | BookingStatus::Cancelled | BookingStatus::NoShow
+    ) {
+        return Err(ApiError::bad_request("Cannot quote on a closed booking"));
+    }
+
+    let line_items = QuoteService::line_items(&dto.line_items).map_err(ApiError::bad_request)?;
+    let tax_percent = check_tax(dto.tax_percent.unwrap_or(0.0))?;
+    let valid_days = check_valid_days(dto.valid_days.unwrap_or(7))?;
+    let (subtotal, tax_amount, total) = QuoteService::totals(&line_items, tax_percent);
+
+    let now = DateTime::now();
+
+    let mut quote = Quote {
+        id: None,
+        quote_number: 0, // Assigned on insert
+        customer_id: booking.customer_id,
+        worker_id: booking.worker_id,
+        worker_user_id: booking.worker_user_id,
+        booking_id: Some(booking_oid),
+        category: booking.category.clone(),
+        subcategory: booking.subcategory.clone(),
+        address: booking.address.clone(),
+        location: booking.location.clone(),
+        line_items,
+        subtotal,
+        tax_percent,
+        tax_amount,
+        total,
+        notes: dto.notes.clone(),
+        valid_days,
+        valid_until: None,
+        status: QuoteStatus::Draft,
+        rejection_reason: None,
+        converted_booking_id: None,
+        sent_at: None,
+        responded_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    QuoteService::insert(db, &mut quote)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create quote: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Quote saved as draft".to_string(),
+        quote_json(&quote)?,
+    )))
+}
+
+#[openapi(tag = "Quote")]
+#[put("/quote/<quote_id>", data = "<dto>")]
+pub async fn update_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+    dto: Json<UpdateQuoteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (mut quote, party) = find_quote(db, &quote_id, auth.user_id).await?;
+
+    if party != Party::Worker {
+        return Err(ApiError::forbidden("Only the worker can edit a quote"));
+    }
+    if quote.status != QuoteStatus::Draft {
+        return Err(ApiError::bad_request("Only draft quotes can be edited"));
+    }
+
+    if let Some(ref items) = dto.line_items {
+        quote.line_items = QuoteService::line_items(items).map_err(ApiError::bad_request)?;
+    }
+    if let Some(tax_percent) = dto.tax_percent {
+        quote.tax_percent = check_tax(tax_percent)?;
+    }
+    if let Some(valid_days) = dto.valid_days {
+        quote.valid_days = check_valid_days(valid_days)?;
+    }
+    if dto.notes.is_some() {
+        quote.notes = dto.notes.clone();
+    }
+    (quote.subtotal, quote.tax_amount, quote.total) =
+        QuoteService::totals(&quote.line_items, quote.tax_percent);
+    quote.updated_at = DateTime::now();
+
+    let line_items = mongodb::bson::to_bson(&quote.line_items)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    let result = db
+        .collection::<Quote>("quotes")
+        .update_one(
+            doc! { "_id": quote.id, "status": "draft" },
+            doc! {
+                "$set": {
+                    "line_items": line_items,
+                    "subtotal": quote.subtotal,
+                    "tax_percent": quote.tax_percent,
+                    "tax_amount": quote.tax_amount,
+                    "total": quote.total,
+                    "valid_days": quote.valid_days,
+                    "notes": quote.notes.clone(),
+                    "updated_at": quote.updated_at
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    if result.matched_count == 0 {
+        return Err(ApiError::bad_request("Quote was updated by someone else. Please refresh"));
+    }
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Quote updated".to_string(),
+        quote_json(&quote)?,
+    )))
+}
+
+#[openapi(tag = "Quote")]
+#[put("/quote/<quote_id>/send")]
+pub async fn send_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (quote, party) = find_quote(db, &quote_id, auth.user_id).await?;
+
+    if party != Party::Worker {
+        return Err(ApiError::forbidden("Only the worker can send a quote"));
+    }
+    if quote.status != QuoteStatus::Draft {
+        return Err(ApiError::bad_request(format!(
+            "Quote is {} and cannot be sent",
+            quote.status.as_str()
+        )));
+    }
+
+    // Validity runs from when the customer receives the quote
+    let now = DateTime::now();
+    let valid_until = add_millis(now, quote.valid_days as i64 * DAY_MILLIS);
+
+    let sent = db
+        .collection::<Quote>("quotes")
+        .find_one_and_update(
+            doc! { "_id": quote.id, "status": "draft" },
+            doc! {
+                "$set": {
+                    "status": "sent",
+                    "sent_at": now,
+                    "valid_until": valid_until,
+                    "updated_at": now
+                }
+            },
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request("Quote was updated by someone else. Please refresh"))?;
+
+    QuoteService::notify(db, &sent).await;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Quote sent to customer".to_string(),
+        quote_json(&sent)?,
+    )))
+}
+
+#[openapi(tag = "Quote")]
+#[get("/quote/list?<query..>")]
+pub async fn list_quotes(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: QuoteListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = match query.role.as_deref().unwrap_or("customer") {
+        "customer" => doc! { "customer_id": auth.user_id, "status": { "$ne": "draft" } },
+        "worker" => doc! { "worker_user_id": auth.user_id },
+        _ => return Err(ApiError::bad_request("Invalid role. Use 'customer' or 'worker'")),
+    };
+    if let Some(ref status) = query.status {
+        if status == "draft" && filter.contains_key("customer_id") {
+            return Err(ApiError::bad_request("Customers cannot list draft quotes"));
+        }
+        filter.insert("status", status);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let quotes: Vec<Quote> = db
+        .collection::<Quote>("quotes")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let total = db
+        .collection::<Quote>("quotes")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "quotes": quotes,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Quote")]
+#[get("/quote/<quote_id>")]
+pub async fn get_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (quote, party) = find_quote(db, &quote_id, auth.user_id).await?;
+
+    let mut data = quote_json(&quote)?;
+    if let Some(obj) = data.as_object_mut() {
+        obj.insert(
+            "role".to_string(),
+            serde_json::json!(if party == Party::Customer { "customer" } else { "worker" }),
+        );
+    }
+
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "Quote")]
+#[put("/quote/<quote_id>/accept", data = "<dto>")]
+pub async fn accept_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+    dto: Json<AcceptQuoteDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (quote, party) = find_quote(db, &quote_id, auth.user_id).await?;
+
+    if party != Party::Customer {
+        return Err(ApiError::forbidden("Only the customer can accept a quote"));
+    }
+    if quote.status != QuoteStatus::Sent {
+        return Err(ApiError::bad_request(format!(
+            "Quote is {} and cannot be accepted",
+            quote.status.as_str()
+        )));
+    }
+    if quote.valid_until.is_some_and(|v| v <= DateTime::now()) {
+        return Err(ApiError::bad_request("This quote has expired"));
+    }
+
+    let worker = db
+        .collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "_id": quote.worker_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker not found"))?;
+
+    let (scheduled_at, duration_minutes) =
+        check_schedule(db, &worker, &dto.scheduled_at, dto.duration_minutes).await?;
+
+    let (quote, booking) = QuoteService::accept(db, &quote, scheduled_at, duration_minutes)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to accept quote: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request("Quote is no longer open. Please refresh"))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Quote accepted and booking requested".to_string(),
+        serde_json::json!({
+            "quote": quote,
+            "booking_id": booking.id.map(|id| id.to_hex()),
+            "booking_status": booking.status
+        }),
+    )))
+}
+
+#[openapi(tag = "Quote")]
+#[put("/quote/<quote_id>/reject", data = "<dto>")]
+pub async fn reject_quote(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+    dto: Json<BookingReasonDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let (quote, party) = find_quote(db, &quote_id, auth.user_id).await?;
+
+    if party != Party::Customer {
+        return Err(ApiError::forbidden("Only the customer can reject a quote"));
+    }
+    if quote.status != QuoteStatus::Sent {
+        return Err(ApiError::bad_request(format!(
+            "Quote is {} and cannot be rejected",
+            quote.status.as_str()
+        )));
+    }
+
+    let reason = dto.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    let now = DateTime::now();
+
+    let rejected = db
+        .collection::<Quote>("quotes")
+        .find_one_and_update(
+            doc! { "_id": quote.id, "status": "sent" },
+            doc! {
+                "$set": {
+                    "status": "rejected",
+                    "rejection_reason": reason,
+                    "responded_at": now,
+                    "updated_at": now
+                }
+            },
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request("Quote is no longer open. Please refresh"))?;
+
+    QuoteService::notify(db, &rejected).await;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Quote rejected".to_string(),
+        quote_json(&rejected)?,
+    )))
+}
+
+#[openapi(tag = "Quote")]
+#[get("/quote/<quote_id>/pdf")]
+pub async fn download_quote_pdf(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    quote_id: String,
+) -> Result<PdfFile, ApiError> {
+    let (quote, _) = find_quote(db, &quote_id, auth.user_id).await?;
+
+    let users: Vec<User> = db
+        .collection::<User>("users")
+        .find(doc! { "_id": { "$in": [quote.worker_user_id, quote.customer_id] } }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let name_of = |id: ObjectId| {
+        users
+            .iter()
+            .find(|u| u.id == Some(id))
+            .and_then(|u| u.name.clone())
+            .unwrap_or_default()
+    };
+
+    Ok(PdfFile {
+        filename: format!("quote-{}.pdf", quote.quote_number),
+        bytes: QuoteService::render_pdf(&quote, &name_of(quote.worker_user_id), &name_of(quote.customer_id)),
+    })
+}
@@ -1,45 +1,119 @@
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket_okapi::openapi;
-use mongodb::bson::{doc, DateTime};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
 use mongodb::options::FindOptions;
-use crate::db::DbConn;
-use crate::models::{Review, CreateReviewDto, WorkerProfile};
+use crate::db::{DbConn, is_duplicate_key};
+use crate::models::{Booking, BookingStatus, ContactReveal, Review, CreateReviewDto, WorkerProfile};
 use crate::guards::AuthGuard;
 use crate::utils::{ApiResponse, ApiError};
+use crate::utils::time::DAY_MILLIS;
 use rocket::futures::TryStreamExt;
 
-#[openapi(tag = "Review")]
-#[post("/review/create", data = "<dto>")]
-pub async fn create_review(
-    db: &State<DbConn>,
-    auth: AuthGuard,
-    dto: Json<CreateReviewDto>,
-) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
-    // Validate rating
-    if dto.rating < 1 || dto.rating > 5 {
-        return Err(ApiError::bad_request("Rating must be between 1 and 5"));
+/// Recalculate a worker's average rating and review count from their reviews
+async fn refresh_worker_rating(db: &DbConn, worker_id: ObjectId) -> Result<(), ApiError> {
+    let all_reviews: Vec<Review> = db.collection::<Review>("reviews")
+        .find(doc! { "worker_id": worker_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Collection error: {}", e)))?;
+    
+    let total_reviews = all_reviews.len() as i32;
+    let avg_rating = if total_reviews > 0 {
+        all_reviews.iter().map(|r| r.rating).sum::<i32>() as f64 / total_reviews as f64
+    } else {
+        0.0
+    };
+    
+    db.collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "_id": worker_id },
+            doc! { 
+                "$set": { 
+                    "rating": avg_rating,
+                    "total_reviews": total_reviews,
+                    "updated_at": DateTime::now()
+                }
+            },
+            None
+        )
+        .await
+        .ok();
+    
+    Ok(())
+}
+
+/// Worker and booking behind a booking review: the customer's own booking, completed
+/// within the review window and not reviewed yet
+async fn booked_worker(db: &DbConn, user_id: ObjectId, booking_id: &str) -> Result<(ObjectId, ObjectId), ApiError> {
+    let booking_id = ObjectId::parse_str(booking_id)
+        .map_err(|_| ApiError::bad_request("Invalid booking ID"))?;
+    
+    // Only the customer of a completed booking can review it
+    let booking = db.collection::<Booking>("bookings")
+        .find_one(doc! { "_id": booking_id, "customer_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Booking not found"))?;
+    
+    let completed_at = match (booking.status, booking.completed_at) {
+        (BookingStatus::Completed, Some(completed_at)) => completed_at,
+        _ => return Err(ApiError::bad_request("You can review a worker once the booking is completed")),
+    };
+    
+    let window_days = crate::config::Config::review_window_days();
+    if DateTime::now().timestamp_millis() > completed_at.timestamp_millis() + window_days * DAY_MILLIS {
+        return Err(ApiError::bad_request(format!(
+            "Reviews can be left up to {} days after the booking is completed",
+            window_days
+        )));
     }
     
-    let worker_id = mongodb::bson::oid::ObjectId::parse_str(&dto.worker_id)
-        .map_err(|_| ApiError::bad_request("Invalid worker ID"))?;
+    // One review per booking
+    let existing_review = db.collection::<Review>("reviews")
+        .find_one(doc! { "booking_id": booking_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+    
+    if existing_review.is_some() {
+        return Err(ApiError::bad_request("You have already reviewed this booking"));
+    }
+    
+    Ok((booking.worker_id, booking_id))
+}
+
+/// Worker and reveal behind a contact review: the user unlocked the worker's contact
+/// details within the review window and hasn't reviewed that reveal yet
+async fn revealed_worker(db: &DbConn, user_id: ObjectId, reveal_id: &str) -> Result<(ObjectId, ObjectId), ApiError> {
+    let reveal_id = ObjectId::parse_str(reveal_id)
+        .map_err(|_| ApiError::bad_request("Invalid reveal ID"))?;
+    
+    let reveal = db.collection::<ContactReveal>("contact_reveals")
+        .find_one(doc! { "_id": reveal_id, "user_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Contact reveal not found"))?;
     
-    // Check if worker exists
     let worker = db.collection::<WorkerProfile>("worker_profiles")
-        .find_one(doc! { "_id": worker_id }, None)
+        .find_one(doc! { "user_id": reveal.target_user_id }, None)
         .await
         .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
-        .ok_or_else(|| ApiError::not_found("Worker not found"))?;
+        .ok_or_else(|| ApiError::bad_request("Only workers can be reviewed"))?;
+    let worker_id = worker.id.ok_or_else(|| ApiError::internal_error("Worker profile missing ID"))?;
+    
+    let window_days = crate::config::Config::review_window_days();
+    if DateTime::now().timestamp_millis() > reveal.created_at.timestamp_millis() + window_days * DAY_MILLIS {
+        return Err(ApiError::bad_request(format!(
+            "Reviews can be left up to {} days after contact details are revealed",
+            window_days
+        )));
+    }
     
-    // Check if user already reviewed this worker
+    // One review per reveal
     let existing_review = db.collection::<Review>("reviews")
-        .find_one(
-            doc! { 
-                "worker_id": worker_id,
-                "user_id": auth.user_id 
-            },
-            None
-        )
+        .find_one(doc! { "reveal_id": reveal_id }, None)
         .await
         .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
     
@@ -47,11 +121,42 @@ pub async fn create_review(
         return Err(ApiError::bad_request("You have already reviewed this worker"));
     }
     
+    Ok((worker_id, reveal_id))
+}
+
+#[openapi(tag = "Review")]
+#[post("/review/create", data = "<dto>")]
+pub async fn create_review(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CreateReviewDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    // Validate rating
+    if dto.rating < 1 || dto.rating > 5 {
+        return Err(ApiError::bad_request("Rating must be between 1 and 5"));
+    }
+    
+    // A review needs a verified engagement: a completed booking or a contact reveal
+    let (worker_id, booking_id, reveal_id) = match (&dto.booking_id, &dto.reveal_id) {
+        (Some(booking_id), None) => {
+            let (worker_id, booking_id) = booked_worker(db, auth.user_id, booking_id).await?;
+            (worker_id, Some(booking_id), None)
+        }
+        (None, Some(reveal_id)) => {
+            let (worker_id, reveal_id) = revealed_worker(db, auth.user_id, reveal_id).await?;
+            (worker_id, None, Some(reveal_id))
+        }
+        _ => return Err(ApiError::bad_request("Provide either a booking_id or a reveal_id")),
+    };
+    
     // Create review
     let review = Review {
         id: None,
         worker_id,
         user_id: auth.user_id,
+        booking_id,
+        reveal_id,
+        is_verified: true,
         rating: dto.rating,
         comment: dto.comment.clone(),
         helpful_count: 0,
@@ -62,45 +167,80 @@ pub async fn create_review(
     let result = db.collection::<Review>("reviews")
         .insert_one(&review, None)
         .await
-        .map_err(|e| ApiError::internal_error(format!("Failed to create review: {}", e)))?;
+        .map_err(|e| {
+            if is_duplicate_key(&e) {
+                ApiError::bad_request("You have already left this review")
+            } else {
+                ApiError::internal_error(format!("Failed to create review: {}", e))
+            }
+        })?;
     
     // Update worker rating
-    let all_reviews: Vec<Review> = db.collection::<Review>("reviews")
-        .find(doc! { "worker_id": worker_id }, None)
+    refresh_worker_rating(db, worker_id).await?;
+    
+    Ok(Json(ApiResponse::success_with_message(
+        "Review submitted successfully".to_string(),
+        serde_json::json!({
+            "review_id": result.inserted_id.as_object_id().unwrap().to_hex(),
+            "is_verified": true
+        })
+    )))
+}
+
+#[openapi(tag = "Review")]
+#[get("/review/pending")]
+pub async fn get_pending_reviews(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let window_days = crate::config::Config::review_window_days();
+    let since = DateTime::from_millis(DateTime::now().timestamp_millis() - window_days * DAY_MILLIS);
+    
+    // Completed bookings still in the review window that have no review yet
+    let bookings: Vec<Booking> = db.collection::<Booking>("bookings")
+        .find(
+            doc! {
+                "customer_id": auth.user_id,
+                "status": "completed",
+                "completed_at": { "$gte": since }
+            },
+            FindOptions::builder().sort(doc! { "completed_at": -1 }).build()
+        )
         .await
         .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
         .try_collect()
         .await
         .map_err(|e| ApiError::internal_error(format!("Collection error: {}", e)))?;
     
-    let total_reviews = all_reviews.len() as i32;
-    let avg_rating = all_reviews.iter().map(|r| r.rating).sum::<i32>() as f64 / total_reviews as f64;
-    
-    db.collection::<WorkerProfile>("worker_profiles")
-        .update_one(
-            doc! { "_id": worker_id },
-            doc! { 
-                "$set": { 
-                    "rating": avg_rating,
-                    "total_reviews": total_reviews,
-                    "updated_at": DateTime::now()
-                }
-            },
-            None
-        )
+    let booking_ids: Vec<ObjectId> = bookings.iter().filter_map(|b| b.id).collect();
+    let reviewed: Vec<Review> = db.collection::<Review>("reviews")
+        .find(doc! { "booking_id": { "$in": booking_ids } }, None)
         .await
-        .ok();
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Collection error: {}", e)))?;
     
-    Ok(Json(ApiResponse::success_with_message(
-        "Review submitted successfully".to_string(),
-        serde_json::json!({
-            "review_id": result.inserted_id.as_object_id().unwrap().to_hex()
-        })
-    )))
+    let pending: Vec<serde_json::Value> = bookings
+        .iter()
+        .filter(|b| !reviewed.iter().any(|r| r.booking_id == b.id))
+        .map(|b| serde_json::json!({
+            "booking_id": b.id.map(|id| id.to_hex()),
+            "worker_id": b.worker_id.to_hex(),
+            "subcategory": b.subcategory,
+            "completed_at": b.completed_at,
+            "review_by": b.completed_at.map(|c| DateTime::from_millis(c.timestamp_millis() + window_days * DAY_MILLIS)),
+        }))
+        .collect();
+    
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "pending": pending
+    }))))
 }
 
 #[derive(FromForm,serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
 pub struct WorkerReviewsQuery {
+    pub verified_only: Option<bool>,
     pub page: Option<i64>,
     pub limit: Option<i64>,
 }
@@ -119,7 +259,10 @@ pub async fn get_worker_reviews(
     let object_id = mongodb::bson::oid::ObjectId::parse_str(&worker_id)
         .map_err(|_| ApiError::bad_request("Invalid worker ID"))?;
     
-    let filter = doc! { "worker_id": object_id };
+    let mut filter = doc! { "worker_id": object_id };
+    if query.verified_only.unwrap_or(false) {
+        filter.insert("is_verified", true);
+    }
     
     let find_options = FindOptions::builder()
         .skip(skip as u64)
@@ -144,8 +287,14 @@ pub async fn get_worker_reviews(
         .await
         .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
     
+    let verified_total = db.collection::<Review>("reviews")
+        .count_documents(doc! { "worker_id": object_id, "is_verified": true }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+    
     Ok(Json(ApiResponse::success(serde_json::json!({
         "reviews": reviews,
+        "verified_reviews": verified_total,
         "pagination": {
             "page": page,
             "limit": limit,
@@ -182,35 +331,7 @@ pub async fn delete_review(
         .map_err(|e| ApiError::internal_error(format!("Failed to delete review: {}", e)))?;
     
     // Recalculate worker rating
-    let all_reviews: Vec<Review> = db.collection::<Review>("reviews")
-        .find(doc! { "worker_id": review.worker_id }, None)
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
-        .try_collect()
-        .await
-        .map_err(|e| ApiError::internal_error(format!("Collection error: {}", e)))?;
-    
-    let total_reviews = all_reviews.len() as i32;
-    let avg_rating = if total_reviews > 0 {
-        all_reviews.iter().map(|r| r.rating).sum::<i32>() as f64 / total_reviews as f64
-    } else {
-        0.0
-    };
-    
-    db.collection::<WorkerProfile>("worker_profiles")
-        .update_one(
-            doc! { "_id": review.worker_id },
-            doc! { 
-                "$set": { 
-                    "rating": avg_rating,
-                    "total_reviews": total_reviews,
-                    "updated_at": DateTime::now()
-                }
-            },
-            None
-        )
-        .await
-        .ok();
+    refresh_worker_rating(db, review.worker_id).await?;
     
     Ok(Json(ApiResponse::success(serde_json::json!({
         "message": "Review deleted successfully"
@@ -0,0 +1,285 @@
+use crate::db::DbConn;
+use crate::guards::AuthGuard;
+use crate::models::{
+    AlertFrequency, CreateSavedSearchDto, JobPost, JobPostResponse, SavedSearch,
+    SavedSearchResponse, UpdateSavedSearchDto,
+};
+use crate::services::JobAlertService;
+use crate::utils::{ApiError, ApiResponse};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, doc};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use rocket::State;
+use rocket::futures::TryStreamExt;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+use uuid::Uuid;
+
+const MAX_SAVED_SEARCHES: u64 = 20;
+const MAX_NAME_CHARS: usize = 100;
+
+async fn find_own_search(db: &DbConn, search_id: &str, user_id: ObjectId) -> Result<SavedSearch, ApiError> {
+    let search_oid = ObjectId::parse_str(search_id)
+        .map_err(|_| ApiError::bad_request("Invalid saved search ID"))?;
+
+    db.collection::<SavedSearch>("saved_searches")
+        .find_one(doc! { "_id": search_oid, "user_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Saved search not found"))
+}
+
+/// Trimmed value, or None if blank
+fn non_blank(value: &Option<String>) -> Option<String> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+fn validate_name(name: &str) -> Result<(), ApiError> {
+    if name.trim().is_empty() {
+        return Err(ApiError::bad_request("Name is required"));
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(ApiError::bad_request(format!("Name must be at most {} characters", MAX_NAME_CHARS)));
+    }
+    Ok(())
+}
+
+/// Save a job search. Alerts cover jobs approved from now on.
+#[openapi(tag = "SavedSearches")]
+#[post("/saved-searches", data = "<dto>")]
+pub async fn create_saved_search(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CreateSavedSearchDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    validate_name(&dto.name)?;
+    let q = non_blank(&dto.q);
+    let location = non_blank(&dto.location);
+    let job_role = non_blank(&dto.job_role);
+    if q.is_none() && location.is_none() && job_role.is_none() && dto.salary_min.is_none() {
+        return Err(ApiError::bad_request("Add at least one search criterion"));
+    }
+
+    let count = db
+        .collection::<SavedSearch>("saved_searches")
+        .count_documents(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+    if count >= MAX_SAVED_SEARCHES {
+        return Err(ApiError::bad_request(format!(
+            "You can save up to {} searches. Delete one to add another",
+            MAX_SAVED_SEARCHES
+        )));
+    }
+
+    let now = DateTime::now();
+    let mut search = SavedSearch {
+        id: None,
+        user_id: auth.user_id,
+        name: dto.name.trim().to_string(),
+        q,
+        location,
+        job_role,
+        salary_min: dto.salary_min,
+        alert_frequency: dto.alert_frequency.unwrap_or(AlertFrequency::Daily),
+        email_alerts: dto.email_alerts.unwrap_or(true),
+        unsubscribe_token: Uuid::new_v4().simple().to_string(),
+        last_checked_at: now,
+        pending_job_ids: Vec::new(),
+        last_digest_at: now,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let result = db
+        .collection::<SavedSearch>("saved_searches")
+        .insert_one(&search, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to save search: {}", e)))?;
+    search.id = result.inserted_id.as_object_id();
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Search saved".to_string(),
+        serde_json::json!(SavedSearchResponse::from(search)),
+    )))
+}
+
+#[openapi(tag = "SavedSearches")]
+#[get("/saved-searches")]
+pub async fn list_saved_searches(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let searches: Vec<SavedSearchResponse> = db
+        .collection::<SavedSearch>("saved_searches")
+        .find(
+            doc! { "user_id": auth.user_id },
+            FindOptions::builder().sort(doc! { "created_at": -1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect::<Vec<SavedSearch>>()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?
+        .into_iter()
+        .map(SavedSearchResponse::from)
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({ "searches": searches }))))
+}
+
+/// Change a saved search. Blank text fields clear that criterion.
+#[openapi(tag = "SavedSearches")]
+#[put("/saved-searches/<search_id>", data = "<dto>")]
+pub async fn update_saved_search(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    search_id: String,
+    dto: Json<UpdateSavedSearchDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let search = find_own_search(db, &search_id, auth.user_id).await?;
+
+    let mut set = doc! { "updated_at": DateTime::now() };
+    if let Some(ref name) = dto.name {
+        validate_name(name)?;
+        set.insert("name", name.trim());
+    }
+    let q = if dto.q.is_some() { non_blank(&dto.q) } else { search.q.clone() };
+    let location = if dto.location.is_some() { non_blank(&dto.location) } else { search.location.clone() };
+    let job_role = if dto.job_role.is_some() { non_blank(&dto.job_role) } else { search.job_role.clone() };
+    let salary_min = dto.salary_min.or(search.salary_min);
+    if q.is_none() && location.is_none() && job_role.is_none() && salary_min.is_none() {
+        return Err(ApiError::bad_request("Add at least one search criterion"));
+    }
+    set.insert("q", q);
+    set.insert("location", location);
+    set.insert("job_role", job_role);
+    set.insert("salary_min", salary_min);
+    if let Some(frequency) = dto.alert_frequency {
+        set.insert("alert_frequency", frequency.as_str());
+        if frequency != search.alert_frequency {
+            // Start the new schedule from now rather than alerting on the backlog
+            set.insert("last_checked_at", DateTime::now());
+            set.insert("last_digest_at", DateTime::now());
+            set.insert("pending_job_ids", Vec::<ObjectId>::new());
+        }
+    }
+    if let Some(email_alerts) = dto.email_alerts {
+        set.insert("email_alerts", email_alerts);
+    }
+
+    let updated = db
+        .collection::<SavedSearch>("saved_searches")
+        .find_one_and_update(
+            doc! { "_id": search.id },
+            doc! { "$set": set },
+            FindOneAndUpdateOptions::builder()
+                .return_document(ReturnDocument::After)
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to update saved search: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Saved search not found"))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Saved search updated".to_string(),
+        serde_json::json!(SavedSearchResponse::from(updated)),
+    )))
+}
+
+#[openapi(tag = "SavedSearches")]
+#[delete("/saved-searches/<search_id>")]
+pub async fn delete_saved_search(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    search_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let search = find_own_search(db, &search_id, auth.user_id).await?;
+
+    db.collection::<SavedSearch>("saved_searches")
+        .delete_one(doc! { "_id": search.id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to delete saved search: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Saved search deleted"
+    }))))
+}
+
+/// Run a saved search now, newest approved jobs first
+#[openapi(tag = "SavedSearches")]
+#[get("/saved-searches/<search_id>/jobs?<page>&<limit>")]
+pub async fn run_saved_search(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    search_id: String,
+    page: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let search = find_own_search(db, &search_id, auth.user_id).await?;
+    let page = page.unwrap_or(1).max(1);
+    let limit = limit.unwrap_or(20).clamp(1, 100);
+    let skip = (page - 1) * limit;
+
+    let filter = JobAlertService::job_filter(&search);
+    let jobs: Vec<JobPostResponse> = db
+        .collection::<JobPost>("jobs")
+        .find(
+            filter.clone(),
+            FindOptions::builder()
+                .skip(skip as u64)
+                .limit(limit)
+                .sort(doc! { "createdAt": -1 })
+                .build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect::<Vec<JobPost>>()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?
+        .into_iter()
+        .map(JobPostResponse::from)
+        .collect();
+
+    let total = db
+        .collection::<JobPost>("jobs")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "jobs": jobs,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+/// Link from alert emails: stops emails for that search without signing in.
+/// In-app alerts continue until the search is changed or deleted.
+#[openapi(tag = "SavedSearches")]
+#[get("/saved-searches/unsubscribe?<token>")]
+pub async fn unsubscribe_saved_search(
+    db: &State<DbConn>,
+    token: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let result = db
+        .collection::<SavedSearch>("saved_searches")
+        .update_one(
+            doc! { "unsubscribe_token": &token },
+            doc! { "$set": { "email_alerts": false, "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to unsubscribe: {}", e)))?;
+    if result.matched_count == 0 {
+        return Err(ApiError::not_found("This unsubscribe link is no longer valid"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "You will no longer receive emails for this saved search"
+    }))))
+}
@@ -1,4 +1,5 @@
 use mongodb::bson::doc;
+use rocket::futures::TryStreamExt;
 use rocket::serde::json::Json;
 use rocket::{State, get};
 use rocket_okapi::openapi;
@@ -6,6 +7,7 @@ use rocket_okapi::openapi;
 use crate::models::Service;
 use crate::db::DbConn;
 use crate::guards::AuthGuard;
+use crate::utils::search;
 use crate::utils::{ApiResponse, ApiError};
 
 /// Get all services
@@ -98,40 +100,98 @@ pub async fn get_all_categories(
     )))
 }
 
-/// Search services by name or description
+const MAX_SEARCH_RESULTS: i64 = 100;
+
+/// Fields matched by the typo-tolerant fallback; the text index covers the same ones
+const SERVICE_SEARCH_FIELDS: [&str; 3] = ["name", "serviceCategory", "description"];
+
+/// Search services by name, category or description, best match first. If
+/// nothing matches exactly, a typo-tolerant prefix match is used and `fuzzy` is set.
 #[openapi(tag = "Services")]
-#[get("/services/search/<query>")]
+#[get("/services/search/<query>?<category>")]
 pub async fn search_services(
     query: String,
+    category: Option<String>,
     db: &State<DbConn>,
     _auth: AuthGuard,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
-    let filter = doc! {
-        "$or": [
-            { "name": { "$regex": &query, "$options": "i" } },
-            { "description": { "$regex": &query, "$options": "i" } }
-        ]
-    };
-    
-    let mut cursor = db
+    let terms = search::terms(&query);
+    if terms.is_empty() {
+        return Err(ApiError::bad_request("Enter something to search for"));
+    }
+
+    let mut filter = doc! {};
+    if let Some(category) = category.filter(|c| !c.is_empty()) {
+        filter.insert("serviceCategory", category);
+    }
+
+    let mut text_filter = filter.clone();
+    text_filter.extend(search::text_filter(&terms));
+    let matched = db
         .collection::<Service>("services")
-        .find(filter, None)
+        .count_documents(text_filter.clone(), None)
         .await
-        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
-    
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+    let fuzzy = matched == 0;
+
+    let mut pipeline = Vec::new();
+    if fuzzy {
+        filter.extend(search::fuzzy_filter(&terms, &SERVICE_SEARCH_FIELDS));
+        pipeline.push(doc! { "$match": filter });
+        pipeline.push(doc! { "$sort": { "name": 1 } });
+    } else {
+        pipeline.push(doc! { "$match": text_filter });
+        pipeline.push(doc! { "$addFields": { "relevance": { "$meta": "textScore" } } });
+        pipeline.push(doc! { "$sort": { "relevance": -1, "name": 1 } });
+    }
+    pipeline.push(doc! {
+        "$facet": {
+            "results": [{ "$limit": MAX_SEARCH_RESULTS }],
+            "category": search::facet("serviceCategory", false)
+        }
+    });
+
+    let result = db
+        .collection::<Service>("services")
+        .aggregate(pipeline, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_next()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?
+        .unwrap_or_default();
+
     let mut services = Vec::new();
-    while cursor.advance().await.map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))? {
-        let service = cursor.deserialize_current()
+    for item in result.get_array("results").map(|a| a.as_slice()).unwrap_or_default() {
+        let Some(service_doc) = item.as_document() else { continue };
+        let relevance = service_doc.get_f64("relevance").ok();
+        let service: Service = mongodb::bson::from_document(service_doc.clone())
             .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
-        services.push(service);
+
+        let highlights = search::highlights(
+            &[
+                ("name", service.name.as_str()),
+                ("service_category", service.service_category.as_str()),
+                ("description", service.description.as_str()),
+            ],
+            &terms,
+        );
+        let mut data = serde_json::json!(service);
+        data["highlights"] = highlights;
+        data["relevance"] = serde_json::json!(relevance);
+        services.push(data);
     }
-    
+
     Ok(Json(ApiResponse::success_with_message(
         format!("Found {} services matching '{}'", services.len(), query),
         serde_json::json!({
             "query": query,
             "services": services,
-            "total": services.len()
+            "total": services.len(),
+            "facets": {
+                "category": search::facet_counts(result.get("category")),
+            },
+            "fuzzy": fuzzy
         })
     )))
 }
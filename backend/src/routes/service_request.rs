@@ -0,0 +1,640 @@
+use crate::db::DbConn;
+use crate::guards::AuthGuard;
+use crate::models::{
+    CreateOfferDto, CreateServiceRequestDto, GeoLocation, OfferStatus, ServiceOffer,
+    ServiceRequest, ServiceRequestListQuery, ServiceRequestStatus, WorkerProfile,
+};
+use crate::routes::booking::check_schedule;
+use crate::routes::notification::create_user_notification;
+use crate::services::ServiceRequestService;
+use crate::utils::time::{DAY_MILLIS, add_millis, parse_rfc3339};
+use crate::utils::{ApiError, ApiResponse};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, Document, doc};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use rocket::State;
+use rocket::futures::TryStreamExt;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+/// How long a request collects offers when no preferred time is given
+const REQUEST_OPEN_DAYS: i64 = 7;
+/// Largest radius a customer can ask workers from
+const MAX_RADIUS_KM: f64 = 50.0;
+
+// ============================================================================
+// SHARED SERVICE REQUEST LOGIC
+// ============================================================================
+
+async fn find_request(db: &DbConn, request_id: &str) -> Result<ServiceRequest, ApiError> {
+    let request_oid = ObjectId::parse_str(request_id)
+        .map_err(|_| ApiError::bad_request("Invalid request ID"))?;
+
+    db.collection::<ServiceRequest>("service_requests")
+        .find_one(doc! { "_id": request_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Service request not found"))
+}
+
+async fn own_worker_profile(db: &DbConn, user_id: ObjectId) -> Result<WorkerProfile, ApiError> {
+    db.collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "user_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker profile not found"))
+}
+
+/// A request as a matched worker sees it: no exact address or other matches.
+/// `None` if the worker isn't matched or their plan's access time hasn't come.
+fn worker_view(request: &ServiceRequest, worker_id: ObjectId) -> Option<serde_json::Value> {
+    let m = request
+        .matches
+        .iter()
+        .find(|m| m.worker_id == worker_id && m.access_at <= DateTime::now())?;
+
+    Some(serde_json::json!({
+        "_id": request.id.map(|id| id.to_hex()),
+        "category": request.category,
+        "subcategory": request.subcategory,
+        "description": request.description,
+        "pincode": request.pincode,
+        "distance_m": m.distance_m,
+        "budget_min": request.budget_min,
+        "budget_max": request.budget_max,
+        "preferred_at": request.preferred_at,
+        "offers_count": request.offers_count,
+        "status": request.status,
+        "expires_at": request.expires_at,
+        "created_at": request.created_at,
+    }))
+}
+
+fn request_json(request: &ServiceRequest) -> Result<serde_json::Value, ApiError> {
+    let mut data = serde_json::to_value(request)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+    if let Some(obj) = data.as_object_mut() {
+        let matched = obj.remove("matches").and_then(|m| m.as_array().map(|a| a.len())).unwrap_or(0);
+        obj.insert("matched_workers".to_string(), serde_json::json!(matched));
+    }
+    Ok(data)
+}
+
+// ============================================================================
+// CUSTOMER ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Service Request")]
+#[post("/service-request", data = "<dto>")]
+pub async fn create_service_request(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<CreateServiceRequestDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let description = dto.description.trim();
+    let address = dto.address.trim();
+    if description.is_empty() {
+        return Err(ApiError::bad_request("Please describe what you need"));
+    }
+    if address.is_empty() {
+        return Err(ApiError::bad_request("Address is required"));
+    }
+    if !(-90.0..=90.0).contains(&dto.latitude) || !(-180.0..=180.0).contains(&dto.longitude) {
+        return Err(ApiError::bad_request("Invalid location"));
+    }
+
+    let radius_km = dto.radius_km.unwrap_or(10.0);
+    if !(1.0..=MAX_RADIUS_KM).contains(&radius_km) {
+        return Err(ApiError::bad_request(format!(
+            "Radius must be between 1 and {} km",
+            MAX_RADIUS_KM
+        )));
+    }
+
+    match (dto.budget_min, dto.budget_max) {
+        (Some(min), _) if min < 0.0 => return Err(ApiError::bad_request("Invalid budget")),
+        (Some(min), Some(max)) if max < min => {
+            return Err(ApiError::bad_request("budget_max must not be below budget_min"));
+        }
+        _ => {}
+    }
+
+    let now = DateTime::now();
+    let preferred_at = match dto.preferred_at.as_deref() {
+        Some(value) => {
+            let at = parse_rfc3339(value)
+                .ok_or_else(|| ApiError::bad_request("Invalid preferred_at, expected an RFC 3339 timestamp"))?;
+            if at <= now {
+                return Err(ApiError::bad_request("Preferred time must be in the future"));
+            }
+            Some(at)
+        }
+        None => None,
+    };
+
+    // Offers stop at the preferred time, or after a week
+    let default_expiry = add_millis(now, REQUEST_OPEN_DAYS * DAY_MILLIS);
+    let expires_at = preferred_at.map_or(default_expiry, |at| at.min(default_expiry));
+
+    let request = ServiceRequest {
+        id: None,
+        customer_id: auth.user_id,
+        category: dto.category.clone(),
+        subcategory: dto.subcategory.clone(),
+        description: description.to_string(),
+        address: address.to_string(),
+        pincode: dto.pincode.clone(),
+        location: GeoLocation {
+            geo_type: String::from("Point"),
+            coordinates: [dto.longitude, dto.latitude],
+        },
+        radius_km,
+        budget_min: dto.budget_min,
+        budget_max: dto.budget_max,
+        preferred_at,
+        matches: Vec::new(),
+        offers_count: 0,
+        status: ServiceRequestStatus::Open,
+        accepted_offer_id: None,
+        booking_id: None,
+        expires_at,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let request = ServiceRequestService::create(db, request)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to post request: {}", e)))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Service request posted".to_string(),
+        request_json(&request)?,
+    )))
+}
+
+#[openapi(tag = "Service Request")]
+#[get("/service-request/mine?<query..>")]
+pub async fn list_my_service_requests(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: ServiceRequestListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+
+    let mut filter = doc! { "customer_id": auth.user_id };
+    if let Some(ref status) = query.status {
+        filter.insert("status", status);
+    }
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let requests: Vec<ServiceRequest> = db
+        .collection::<ServiceRequest>("service_requests")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let total = db
+        .collection::<ServiceRequest>("service_requests")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    let requests = requests
+        .iter()
+        .map(request_json)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "requests": requests,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Service Request")]
+#[put("/service-request/<request_id>/cancel")]
+pub async fn cancel_service_request(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    request_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let request = find_request(db, &request_id).await?;
+    if request.customer_id != auth.user_id {
+        return Err(ApiError::not_found("Service request not found"));
+    }
+
+    let closed = db
+        .collection::<ServiceRequest>("service_requests")
+        .update_one(
+            doc! { "_id": request.id, "status": "open" },
+            doc! { "$set": { "status": "cancelled", "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    if closed.modified_count == 0 {
+        return Err(ApiError::bad_request(format!(
+            "Request is {} and cannot be cancelled",
+            request.status.as_str()
+        )));
+    }
+
+    ServiceRequestService::decline_pending_offers(db, request.id, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Service request cancelled"
+    }))))
+}
+
+#[openapi(tag = "Service Request")]
+#[put("/service-request/<request_id>/offers/<offer_id>/accept")]
+pub async fn accept_service_offer(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    request_id: String,
+    offer_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let request = find_request(db, &request_id).await?;
+    if request.customer_id != auth.user_id {
+        return Err(ApiError::not_found("Service request not found"));
+    }
+    if request.status != ServiceRequestStatus::Open {
+        return Err(ApiError::bad_request(format!(
+            "Request is {} and no longer takes offers",
+            request.status.as_str()
+        )));
+    }
+
+    let offer_oid =
+        ObjectId::parse_str(&offer_id).map_err(|_| ApiError::bad_request("Invalid offer ID"))?;
+    let offer = db
+        .collection::<ServiceOffer>("service_offers")
+        .find_one(doc! { "_id": offer_oid, "request_id": request.id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Offer not found"))?;
+
+    if offer.status != OfferStatus::Pending {
+        return Err(ApiError::bad_request("This offer is no longer available"));
+    }
+
+    // The offered slot may have been taken since the worker responded
+    let worker = db
+        .collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "_id": offer.worker_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker not found"))?;
+    let scheduled_at = offer
+        .scheduled_at
+        .try_to_rfc3339_string()
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    check_schedule(db, &worker, &scheduled_at, Some(offer.duration_minutes)).await?;
+
+    let booking = ServiceRequestService::accept_offer(db, &request, &offer)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to accept offer: {}", e)))?
+        .ok_or_else(|| ApiError::bad_request("Request or offer changed. Please refresh"))?;
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Offer accepted and booking confirmed".to_string(),
+        serde_json::json!({
+            "booking_id": booking.id.map(|id| id.to_hex()),
+            "status": booking.status
+        }),
+    )))
+}
+
+// ============================================================================
+// WORKER ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Service Request")]
+#[get("/service-request/open?<query..>")]
+pub async fn list_open_service_requests(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    query: ServiceRequestListQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = own_worker_profile(db, auth.user_id).await?;
+    let worker_id = worker.id.ok_or_else(|| ApiError::internal_error("Worker missing ID"))?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(20).min(100);
+    let skip = (page - 1) * limit;
+    let now = DateTime::now();
+
+    let filter = doc! {
+        "status": "open",
+        "expires_at": { "$gt": now },
+        "matches": { "$elemMatch": { "worker_id": worker_id, "access_at": { "$lte": now } } }
+    };
+
+    let find_options = FindOptions::builder()
+        .skip(skip as u64)
+        .limit(limit)
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    let requests: Vec<ServiceRequest> = db
+        .collection::<ServiceRequest>("service_requests")
+        .find(filter.clone(), find_options)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let total = db
+        .collection::<ServiceRequest>("service_requests")
+        .count_documents(filter, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+
+    // Flag the requests this worker already responded to
+    let request_ids: Vec<ObjectId> = requests.iter().filter_map(|r| r.id).collect();
+    let offered: Vec<ServiceOffer> = db
+        .collection::<ServiceOffer>("service_offers")
+        .find(doc! { "request_id": { "$in": request_ids }, "worker_id": worker_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let requests: Vec<serde_json::Value> = requests
+        .iter()
+        .filter_map(|r| {
+            let mut view = worker_view(r, worker_id)?;
+            let offer = offered.iter().find(|o| Some(o.request_id) == r.id);
+            view["my_offer_status"] = serde_json::json!(offer.map(|o| o.status));
+            Some(view)
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "requests": requests,
+        "pagination": {
+            "page": page,
+            "limit": limit,
+            "total": total,
+            "pages": (total as f64 / limit as f64).ceil() as i64,
+        }
+    }))))
+}
+
+#[openapi(tag = "Service Request")]
+#[post("/service-request/<request_id>/offers", data = "<dto>")]
+pub async fn create_service_offer(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    request_id: String,
+    dto: Json<CreateOfferDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let request = find_request(db, &request_id).await?;
+    let worker = own_worker_profile(db, auth.user_id).await?;
+    let worker_id = worker.id.ok_or_else(|| ApiError::internal_error("Worker missing ID"))?;
+
+    if worker_view(&request, worker_id).is_none() {
+        return Err(ApiError::not_found("Service request not found"));
+    }
+    if request.status != ServiceRequestStatus::Open || request.expires_at <= DateTime::now() {
+        return Err(ApiError::bad_request("This request no longer takes offers"));
+    }
+    if !worker.is_verified || !worker.is_available {
+        return Err(ApiError::bad_request("Your profile must be verified and available to send offers"));
+    }
+    if !dto.price.is_finite() || dto.price <= 0.0 {
+        return Err(ApiError::bad_request("Invalid price"));
+    }
+
+    let (scheduled_at, duration_minutes) =
+        check_schedule(db, &worker, &dto.scheduled_at, dto.duration_minutes).await?;
+    let now = DateTime::now();
+
+    let existing = db
+        .collection::<ServiceOffer>("service_offers")
+        .find_one(doc! { "request_id": request.id, "worker_id": worker_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let offer = match existing {
+        // A pending or withdrawn offer can be revised
+        Some(existing) => {
+            if !matches!(existing.status, OfferStatus::Pending | OfferStatus::Withdrawn) {
+                return Err(ApiError::bad_request("You already responded to this request"));
+            }
+
+            db.collection::<ServiceOffer>("service_offers")
+                .find_one_and_update(
+                    doc! { "_id": existing.id, "status": { "$in": ["pending", "withdrawn"] } },
+                    doc! {
+                        "$set": {
+                            "price": dto.price,
+                            "message": dto.message.clone(),
+                            "scheduled_at": scheduled_at,
+                            "duration_minutes": duration_minutes,
+                            "status": "pending",
+                            "updated_at": now
+                        }
+                    },
+                    FindOneAndUpdateOptions::builder()
+                        .return_document(ReturnDocument::After)
+                        .build(),
+                )
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+                .ok_or_else(|| ApiError::bad_request("You already responded to this request"))?
+        }
+        None => {
+            let mut offer = ServiceOffer {
+                id: None,
+                request_id: request.id.ok_or_else(|| ApiError::internal_error("Request missing ID"))?,
+                worker_id,
+                worker_user_id: auth.user_id,
+                price: dto.price,
+                message: dto.message.clone(),
+                scheduled_at,
+                duration_minutes,
+                status: OfferStatus::Pending,
+                created_at: now,
+                updated_at: now,
+            };
+
+            let res = db
+                .collection::<ServiceOffer>("service_offers")
+                .insert_one(&offer, None)
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Failed to send offer: {}", e)))?;
+            offer.id = res.inserted_id.as_object_id();
+
+            db.collection::<ServiceRequest>("service_requests")
+                .update_one(
+                    doc! { "_id": request.id },
+                    doc! { "$inc": { "offers_count": 1 }, "$set": { "updated_at": now } },
+                    None,
+                )
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+            let _ = create_user_notification(
+                db,
+                request.customer_id,
+                "service_offer_received".to_string(),
+                "New Offer".to_string(),
+                format!("You received an offer of ₹{:.2} for your {} request.", offer.price, request.subcategory),
+                request.id,
+            )
+            .await;
+
+            offer
+        }
+    };
+
+    Ok(Json(ApiResponse::success_with_message(
+        "Offer sent".to_string(),
+        serde_json::to_value(&offer)
+            .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?,
+    )))
+}
+
+#[openapi(tag = "Service Request")]
+#[put("/service-request/<request_id>/offers/withdraw")]
+pub async fn withdraw_service_offer(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    request_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let request = find_request(db, &request_id).await?;
+
+    let withdrawn = db
+        .collection::<ServiceOffer>("service_offers")
+        .update_one(
+            doc! { "request_id": request.id, "worker_user_id": auth.user_id, "status": "pending" },
+            doc! { "$set": { "status": "withdrawn", "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    if withdrawn.modified_count == 0 {
+        return Err(ApiError::not_found("No pending offer on this request"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Offer withdrawn"
+    }))))
+}
+
+// ============================================================================
+// SHARED ENDPOINTS
+// ============================================================================
+
+#[openapi(tag = "Service Request")]
+#[get("/service-request/<request_id>")]
+pub async fn get_service_request(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    request_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let request = find_request(db, &request_id).await?;
+
+    if request.customer_id != auth.user_id {
+        // Workers see the redacted request and their own offer
+        let worker = own_worker_profile(db, auth.user_id)
+            .await
+            .map_err(|_| ApiError::not_found("Service request not found"))?;
+        let worker_id = worker.id.ok_or_else(|| ApiError::internal_error("Worker missing ID"))?;
+
+        let mut view = worker_view(&request, worker_id)
+            .ok_or_else(|| ApiError::not_found("Service request not found"))?;
+
+        let offer = db
+            .collection::<ServiceOffer>("service_offers")
+            .find_one(doc! { "request_id": request.id, "worker_id": worker_id }, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+        if offer.as_ref().is_some_and(|o| o.status == OfferStatus::Accepted) {
+            view["address"] = serde_json::json!(request.address);
+            view["booking_id"] = serde_json::json!(request.booking_id.map(|id| id.to_hex()));
+        }
+        view["my_offer"] = serde_json::to_value(&offer)
+            .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+        return Ok(Json(ApiResponse::success(view)));
+    }
+
+    // The customer sees every offer with the worker's public details
+    let offers: Vec<Document> = db
+        .collection::<ServiceOffer>("service_offers")
+        .aggregate(
+            vec![
+                doc! { "$match": { "request_id": request.id, "status": { "$ne": "withdrawn" } } },
+                doc! {
+                    "$lookup": {
+                        "from": "worker_profiles",
+                        "localField": "worker_id",
+                        "foreignField": "_id",
+                        "as": "worker"
+                    }
+                },
+                doc! { "$unwind": "$worker" },
+                doc! {
+                    "$lookup": {
+                        "from": "users",
+                        "localField": "worker_user_id",
+                        "foreignField": "_id",
+                        "as": "user"
+                    }
+                },
+                doc! { "$unwind": { "path": "$user", "preserveNullAndEmptyArrays": true } },
+                doc! { "$sort": { "price": 1, "created_at": 1 } },
+                doc! {
+                    "$project": {
+                        "price": 1,
+                        "message": 1,
+                        "scheduled_at": 1,
+                        "duration_minutes": 1,
+                        "status": 1,
+                        "created_at": 1,
+                        "worker_id": 1,
+                        "worker_name": "$user.name",
+                        "rating": "$worker.rating",
+                        "total_reviews": "$worker.total_reviews",
+                        "total_jobs_completed": "$worker.total_jobs_completed",
+                        "subscription_plan": "$worker.subscription_plan"
+                    }
+                },
+            ],
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let mut data = request_json(&request)?;
+    data["offers"] = serde_json::to_value(&offers)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(data)))
+}
+
@@ -0,0 +1,958 @@
+use crate::db::DbConn;
+use crate::guards::AuthGuard;
+use crate::models::{
+    ConfirmMandateDto, Feature, FeatureLimit, MandateStatus, PLANS, PlanChange, PlanChangeStatus, PlanChangeType,
+    Subscription, SubscriptionStatus, SubscriptionType, UpdateAutoRenewDto, User,
+    VerifyPlanChangeDto, find_plan,
+};
+use crate::services::{BillingService, EntitlementService, payment_gateway};
+use crate::utils::{ApiError, ApiResponse};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, doc};
+use mongodb::options::FindOptions;
+use rocket::State;
+use rocket::futures::TryStreamExt;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+// ============================================================================
+// SHARED PLAN CHANGE LOGIC
+// ============================================================================
+
+async fn find_active_subscription(
+    db: &DbConn,
+    user_id: ObjectId,
+    subscription_type: &SubscriptionType,
+) -> Result<Option<Subscription>, ApiError> {
+    db.collection::<Subscription>("subscriptions")
+        .find_one(
+            doc! {
+                "user_id": user_id,
+                "subscription_type": subscription_type.as_str(),
+                "status": "active"
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))
+}
+
+/// Activate the upgraded subscription, retire the old one and sync the profile
+async fn complete_upgrade(
+    db: &DbConn,
+    change: &PlanChange,
+    payment_id: Option<String>,
+) -> Result<Subscription, ApiError> {
+    let change_id = change
+        .id
+        .ok_or_else(|| ApiError::internal_error("Plan change missing ID"))?;
+    let new_sub_id = change
+        .to_subscription_id
+        .ok_or_else(|| ApiError::internal_error("Plan change missing target subscription"))?;
+    let now = DateTime::now();
+
+    let activated = db
+        .collection::<Subscription>("subscriptions")
+        .update_one(
+            doc! { "_id": new_sub_id, "status": "pending" },
+            doc! {
+                "$set": {
+                    "status": "active",
+                    "payment_id": payment_id,
+                    "updated_at": now
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    if activated.matched_count == 0 {
+        return Err(ApiError::bad_request("Upgrade already processed"));
+    }
+
+    db.collection::<Subscription>("subscriptions")
+        .update_one(
+            doc! { "_id": change.from_subscription_id, "status": "active" },
+            doc! {
+                "$set": {
+                    "status": "superseded",
+                    "auto_renew": false,
+                    "updated_at": now
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    db.collection::<PlanChange>("plan_changes")
+        .update_one(
+            doc! { "_id": change_id },
+            doc! {
+                "$set": {
+                    "status": "completed",
+                    "effective_at": now,
+                    "updated_at": now
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let subscription = db
+        .collection::<Subscription>("subscriptions")
+        .find_one(doc! { "_id": new_sub_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Subscription not found"))?;
+
+    BillingService::apply_subscription(db, &subscription)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to sync subscription: {}", e)))?;
+
+    Ok(subscription)
+}
+
+async fn change_plan(
+    db: &DbConn,
+    user_id: ObjectId,
+    subscription_type: SubscriptionType,
+    plan_name: &str,
+) -> Result<serde_json::Value, ApiError> {
+    let target = find_plan(&subscription_type, plan_name)
+        .ok_or_else(|| ApiError::bad_request("Invalid plan"))?;
+
+    let current = find_active_subscription(db, user_id, &subscription_type)
+        .await?
+        .ok_or_else(|| ApiError::bad_request("No active subscription to change"))?;
+    let current_id = current
+        .id
+        .ok_or_else(|| ApiError::internal_error("Subscription missing ID"))?;
+    if current.is_trial {
+        return Err(ApiError::bad_request(
+            "Trials cannot be changed. Subscribe to a paid plan to end your trial",
+        ));
+    }
+    let current_plan = find_plan(&subscription_type, &current.plan_name)
+        .ok_or_else(|| ApiError::bad_request("Current plan cannot be changed"))?;
+
+    if current_plan.name == target.name {
+        return Err(ApiError::bad_request("You are already on this plan"));
+    }
+
+    let changes = db.collection::<PlanChange>("plan_changes");
+
+    let scheduled = changes
+        .find_one(
+            doc! { "from_subscription_id": current_id, "status": "scheduled" },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    if scheduled.is_some() {
+        return Err(ApiError::bad_request(
+            "A downgrade is already scheduled. Cancel it before changing plans",
+        ));
+    }
+
+    // Abandon any upgrade that was started but never paid for
+    let stale: Vec<PlanChange> = changes
+        .find(
+            doc! { "from_subscription_id": current_id, "status": "pending" },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    let now = DateTime::now();
+    for change in stale {
+        changes
+            .update_one(
+                doc! { "_id": change.id },
+                doc! { "$set": { "status": "cancelled", "updated_at": now } },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        if let Some(sub_id) = change.to_subscription_id {
+            db.collection::<Subscription>("subscriptions")
+                .update_one(
+                    doc! { "_id": sub_id, "status": "pending" },
+                    doc! { "$set": { "status": "cancelled", "updated_at": now } },
+                    None,
+                )
+                .await
+                .map_err(|e| ApiError::internal_error(e.to_string()))?;
+        }
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* DOWNGRADE: schedule for renewal time                               */
+    /* ------------------------------------------------------------------ */
+
+    if target.tier < current_plan.tier {
+        let change = PlanChange {
+            id: None,
+            user_id,
+            subscription_type,
+            change_type: PlanChangeType::Downgrade,
+            status: PlanChangeStatus::Scheduled,
+            from_plan: current_plan.name.to_string(),
+            to_plan: target.name.to_string(),
+            from_subscription_id: current_id,
+            to_subscription_id: None,
+            prorated_amount: 0.0,
+            order_id: None,
+            effective_at: current.expires_at,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let res = changes
+            .insert_one(&change, None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Failed to schedule downgrade: {}", e)))?;
+
+        db.collection::<Subscription>("subscriptions")
+            .update_one(
+                doc! { "_id": current_id },
+                doc! { "$set": { "scheduled_plan": target.name, "updated_at": now } },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        return Ok(serde_json::json!({
+            "plan_change_id": res.inserted_id.as_object_id().map(|id| id.to_hex()),
+            "change_type": "downgrade",
+            "plan_name": target.name,
+            "effective_at": current.expires_at,
+            "message": format!("Your plan will change to {} at renewal", target.name)
+        }));
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* UPGRADE: charge the prorated difference for the remaining period   */
+    /* ------------------------------------------------------------------ */
+
+    let amount = BillingService::prorated_amount(
+        current_plan.price,
+        target.price,
+        current_plan.duration_days,
+        current.expires_at,
+        now,
+    );
+
+    let invoice_number = BillingService::next_invoice_number(db)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let upgraded = Subscription {
+        id: None,
+        user_id,
+        subscription_type: subscription_type.clone(),
+        plan_name: target.name.to_string(),
+        price: amount,
+        status: SubscriptionStatus::Pending,
+        starts_at: now,
+        expires_at: current.expires_at,
+        auto_renew: current.auto_renew,
+        payment_id: None,
+        invoice_number: Some(invoice_number),
+        previous_subscription_id: Some(current_id),
+        scheduled_plan: None,
+        mandate: current.mandate.clone(),
+        renewal_attempts: 0,
+        next_renewal_at: None,
+        renewal_payment_id: None,
+        grace_until: None,
+        is_trial: false,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let sub_res = db
+        .collection::<Subscription>("subscriptions")
+        .insert_one(&upgraded, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create subscription: {}", e)))?;
+    let upgraded_id = sub_res
+        .inserted_id
+        .as_object_id()
+        .ok_or_else(|| ApiError::internal_error("Invalid subscription ID"))?;
+
+    let mut change = PlanChange {
+        id: None,
+        user_id,
+        subscription_type,
+        change_type: PlanChangeType::Upgrade,
+        status: PlanChangeStatus::Pending,
+        from_plan: current_plan.name.to_string(),
+        to_plan: target.name.to_string(),
+        from_subscription_id: current_id,
+        to_subscription_id: Some(upgraded_id),
+        prorated_amount: amount,
+        order_id: None,
+        effective_at: now,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let change_res = changes
+        .insert_one(&change, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to record plan change: {}", e)))?;
+    change.id = change_res.inserted_id.as_object_id();
+
+    // Nothing left to charge (e.g. last day of the period): switch immediately
+    if amount < 1.0 {
+        let subscription = complete_upgrade(db, &change, None).await?;
+
+        return Ok(serde_json::json!({
+            "plan_change_id": change.id.map(|id| id.to_hex()),
+            "change_type": "upgrade",
+            "subscription_id": upgraded_id.to_hex(),
+            "plan_name": subscription.plan_name,
+            "prorated_amount": 0.0,
+            "order": null,
+            "expires_at": subscription.expires_at
+        }));
+    }
+
+    let order = payment_gateway()
+        .create_order(amount)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to create payment order: {}", e)))?;
+    let order_id = order
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ApiError::internal_error("Payment order response missing id"))?;
+
+    // Only a payment for this order may complete the upgrade
+    changes
+        .update_one(
+            doc! { "_id": change.id },
+            doc! { "$set": { "order_id": order_id, "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to record plan change: {}", e)))?;
+
+    Ok(serde_json::json!({
+        "plan_change_id": change.id.map(|id| id.to_hex()),
+        "change_type": "upgrade",
+        "subscription_id": upgraded_id.to_hex(),
+        "plan_name": target.name,
+        "prorated_amount": amount,
+        "order": order,
+        "expires_at": current.expires_at
+    }))
+}
+
+async fn verify_plan_change(
+    db: &DbConn,
+    user_id: ObjectId,
+    subscription_type: SubscriptionType,
+    dto: &VerifyPlanChangeDto,
+) -> Result<serde_json::Value, ApiError> {
+    payment_gateway()
+        .verify_payment(
+            &dto.razorpay_order_id,
+            &dto.razorpay_payment_id,
+            &dto.razorpay_signature,
+        )
+        .map_err(ApiError::bad_request)?;
+
+    let change_id = ObjectId::parse_str(&dto.plan_change_id)
+        .map_err(|_| ApiError::bad_request("Invalid plan change ID"))?;
+
+    let change = db
+        .collection::<PlanChange>("plan_changes")
+        .find_one(
+            doc! {
+                "_id": change_id,
+                "user_id": user_id,
+                "subscription_type": subscription_type.as_str(),
+                "status": "pending"
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("Plan change not found or already processed"))?;
+
+    if change.order_id.as_deref() != Some(dto.razorpay_order_id.as_str()) {
+        return Err(ApiError::bad_request("Payment does not match this plan change"));
+    }
+
+    let subscription =
+        complete_upgrade(db, &change, Some(dto.razorpay_payment_id.clone())).await?;
+
+    Ok(serde_json::json!({
+        "message": "Plan upgraded successfully",
+        "subscription": {
+            "id": subscription.id.map(|id| id.to_hex()),
+            "plan_name": subscription.plan_name,
+            "status": "active",
+            "expires_at": subscription.expires_at
+        }
+    }))
+}
+
+async fn cancel_scheduled_downgrade(
+    db: &DbConn,
+    user_id: ObjectId,
+    subscription_type: SubscriptionType,
+) -> Result<serde_json::Value, ApiError> {
+    let change = db
+        .collection::<PlanChange>("plan_changes")
+        .find_one(
+            doc! {
+                "user_id": user_id,
+                "subscription_type": subscription_type.as_str(),
+                "status": "scheduled"
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("No scheduled plan change"))?;
+
+    let now = DateTime::now();
+
+    db.collection::<PlanChange>("plan_changes")
+        .update_one(
+            doc! { "_id": change.id },
+            doc! { "$set": { "status": "cancelled", "updated_at": now } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    db.collection::<Subscription>("subscriptions")
+        .update_one(
+            doc! { "_id": change.from_subscription_id },
+            doc! {
+                "$set": { "updated_at": now },
+                "$unset": { "scheduled_plan": "" }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(serde_json::json!({
+        "message": format!("Scheduled change to {} cancelled", change.to_plan)
+    }))
+}
+
+async fn plan_history(
+    db: &DbConn,
+    user_id: ObjectId,
+    subscription_type: SubscriptionType,
+) -> Result<serde_json::Value, ApiError> {
+    let filter = doc! {
+        "user_id": user_id,
+        "subscription_type": subscription_type.as_str()
+    };
+    let newest_first = FindOptions::builder().sort(doc! { "created_at": -1 }).build();
+
+    let subscriptions: Vec<Subscription> = db
+        .collection::<Subscription>("subscriptions")
+        .find(filter.clone(), newest_first.clone())
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    let changes: Vec<PlanChange> = db
+        .collection::<PlanChange>("plan_changes")
+        .find(filter, newest_first)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_collect()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?;
+
+    Ok(serde_json::json!({
+        "subscriptions": subscriptions,
+        "plan_changes": changes
+    }))
+}
+
+// ============================================================================
+// SHARED AUTO-RENEW LOGIC
+// ============================================================================
+
+async fn set_auto_renew(
+    db: &DbConn,
+    user_id: ObjectId,
+    subscription_type: SubscriptionType,
+    enabled: bool,
+) -> Result<serde_json::Value, ApiError> {
+    let sub = find_active_subscription(db, user_id, &subscription_type)
+        .await?
+        .ok_or_else(|| ApiError::bad_request("No active subscription"))?;
+    let now = DateTime::now();
+
+    if sub.is_trial {
+        return Err(ApiError::bad_request("Trials cannot be renewed automatically"));
+    }
+
+    if !enabled {
+        db.collection::<Subscription>("subscriptions")
+            .update_one(
+                doc! { "_id": sub.id },
+                doc! {
+                    "$set": { "auto_renew": false, "updated_at": now },
+                    "$unset": { "next_renewal_at": "" }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        return Ok(serde_json::json!({
+            "auto_renew": false,
+            "message": format!("Your plan will expire on {}", crate::utils::time::format_date(sub.expires_at))
+        }));
+    }
+
+    let has_active_mandate = sub
+        .mandate
+        .as_ref()
+        .map(|m| m.status == MandateStatus::Active)
+        .unwrap_or(false);
+
+    if has_active_mandate {
+        db.collection::<Subscription>("subscriptions")
+            .update_one(
+                doc! { "_id": sub.id },
+                doc! {
+                    "$set": { "auto_renew": true, "renewal_attempts": 0, "updated_at": now },
+                    "$unset": { "next_renewal_at": "" }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+        return Ok(serde_json::json!({
+            "auto_renew": true,
+            "mandate_required": false
+        }));
+    }
+
+    // No saved payment method yet: start a mandate the customer must authorise
+    let user = db
+        .collection::<User>("users")
+        .find_one(doc! { "_id": user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+
+    // Cap at the most expensive plan of this type so renewals after an upgrade still go through
+    let max_amount = PLANS
+        .iter()
+        .filter(|p| p.subscription_type == subscription_type)
+        .map(|p| p.price)
+        .fold(0.0, f64::max);
+
+    let setup = payment_gateway()
+        .create_mandate(&user, max_amount)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to set up auto-renewal: {}", e)))?;
+
+    let mandate_bson = mongodb::bson::to_bson(&setup.mandate)
+        .map_err(|e| ApiError::internal_error(format!("Failed to serialize mandate: {}", e)))?;
+
+    db.collection::<Subscription>("subscriptions")
+        .update_one(
+            doc! { "_id": sub.id },
+            doc! { "$set": { "mandate": mandate_bson, "updated_at": now } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(serde_json::json!({
+        "auto_renew": false,
+        "mandate_required": true,
+        "checkout": setup.checkout
+    }))
+}
+
+async fn confirm_auto_renew(
+    db: &DbConn,
+    user_id: ObjectId,
+    subscription_type: SubscriptionType,
+    dto: &ConfirmMandateDto,
+) -> Result<serde_json::Value, ApiError> {
+    let sub = find_active_subscription(db, user_id, &subscription_type)
+        .await?
+        .ok_or_else(|| ApiError::bad_request("No active subscription"))?;
+
+    let mandate = sub
+        .mandate
+        .clone()
+        .filter(|m| m.status == MandateStatus::Pending)
+        .ok_or_else(|| ApiError::bad_request("No pending auto-renewal authorisation"))?;
+
+    if mandate.order_id.as_deref() != Some(dto.razorpay_order_id.as_str()) {
+        return Err(ApiError::bad_request(
+            "Order does not match the pending authorisation",
+        ));
+    }
+
+    let gateway = payment_gateway();
+    gateway
+        .verify_payment(
+            &dto.razorpay_order_id,
+            &dto.razorpay_payment_id,
+            &dto.razorpay_signature,
+        )
+        .map_err(ApiError::bad_request)?;
+
+    let token_id = gateway
+        .confirm_mandate(&mandate, &dto.razorpay_payment_id)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to confirm mandate: {}", e)))?;
+
+    db.collection::<Subscription>("subscriptions")
+        .update_one(
+            doc! { "_id": sub.id },
+            doc! {
+                "$set": {
+                    "mandate.token_id": token_id,
+                    "mandate.status": "active",
+                    "auto_renew": true,
+                    "renewal_attempts": 0,
+                    "updated_at": DateTime::now()
+                },
+                "$unset": { "next_renewal_at": "" }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    Ok(serde_json::json!({
+        "auto_renew": true,
+        "message": "Auto-renewal enabled"
+    }))
+}
+
+// ============================================================================
+// SHARED TRIAL & ENTITLEMENT LOGIC
+// ============================================================================
+
+async fn start_trial(
+    db: &DbConn,
+    user_id: ObjectId,
+    subscription_type: SubscriptionType,
+    plan_name: &str,
+) -> Result<serde_json::Value, ApiError> {
+    let plan = find_plan(&subscription_type, plan_name)
+        .ok_or_else(|| ApiError::bad_request("Invalid plan"))?;
+
+    let trial_days = plan.trial_days();
+    if trial_days <= 0 {
+        return Err(ApiError::bad_request("This plan does not offer a free trial"));
+    }
+
+    if find_active_subscription(db, user_id, &subscription_type).await?.is_some() {
+        return Err(ApiError::bad_request("You already have an active subscription"));
+    }
+
+    // One trial per user and subscription type, whichever plan it was for
+    let previous_trial = db
+        .collection::<Subscription>("subscriptions")
+        .find_one(
+            doc! {
+                "user_id": user_id,
+                "subscription_type": subscription_type.as_str(),
+                "is_trial": true
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+
+    if previous_trial.is_some() {
+        return Err(ApiError::bad_request("You have already used your free trial"));
+    }
+
+    let now = DateTime::now();
+    let mut trial = Subscription {
+        id: None,
+        user_id,
+        subscription_type,
+        plan_name: plan.name.to_string(),
+        price: 0.0,
+        status: SubscriptionStatus::Active,
+        starts_at: now,
+        expires_at: crate::utils::time::add_millis(now, trial_days * crate::utils::time::DAY_MILLIS),
+        auto_renew: false,
+        payment_id: None,
+        invoice_number: None,
+        previous_subscription_id: None,
+        scheduled_plan: None,
+        mandate: None,
+        renewal_attempts: 0,
+        next_renewal_at: None,
+        renewal_payment_id: None,
+        grace_until: None,
+        is_trial: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let res = db
+        .collection::<Subscription>("subscriptions")
+        .insert_one(&trial, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to start trial: {}", e)))?;
+    trial.id = res.inserted_id.as_object_id();
+
+    BillingService::apply_subscription(db, &trial)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to sync subscription: {}", e)))?;
+
+    Ok(serde_json::json!({
+        "subscription_id": trial.id.map(|id| id.to_hex()),
+        "plan_name": trial.plan_name,
+        "is_trial": true,
+        "trial_days": trial_days,
+        "expires_at": trial.expires_at,
+        "features": plan.features.iter().filter_map(|f| Feature::parse(f)).collect::<Vec<_>>()
+    }))
+}
+
+/// Current entitlements with usage for metered features
+async fn entitlements(db: &DbConn, user_id: ObjectId) -> Result<serde_json::Value, ApiError> {
+    let features = EntitlementService::features_for(db, user_id)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    let mut data = Vec::with_capacity(features.len());
+    for feature in features {
+        let (used, remaining) = match feature.limit {
+            FeatureLimit::Metered { limit, .. } => {
+                let used = EntitlementService::usage(db, user_id, &feature)
+                    .await
+                    .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+                (Some(used), Some((limit - used).max(0)))
+            }
+            _ => (None, None),
+        };
+
+        data.push(serde_json::json!({
+            "key": feature.key,
+            "limit": feature.limit,
+            "used": used,
+            "remaining": remaining
+        }));
+    }
+
+    Ok(serde_json::json!({ "features": data }))
+}
+
+// ============================================================================
+// PLAN CATALOG & ENTITLEMENTS
+// ============================================================================
+
+#[openapi(tag = "Subscription")]
+#[get("/subscription/plans")]
+pub async fn get_plans() -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let plans: Vec<serde_json::Value> = PLANS
+        .iter()
+        .map(|p| {
+            serde_json::json!({
+                "name": p.name,
+                "subscription_type": p.subscription_type,
+                "price": p.price,
+                "duration_days": p.duration_days,
+                "trial_days": p.trial_days(),
+                "features": p.features.iter().filter_map(|f| Feature::parse(f)).collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(serde_json::json!({ "plans": plans }))))
+}
+
+#[openapi(tag = "Subscription")]
+#[get("/subscription/entitlements")]
+pub async fn get_entitlements(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = entitlements(db, auth.user_id).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "Subscription")]
+#[post("/subscription/trial/<plan_name>")]
+pub async fn start_worker_trial(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    plan_name: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = start_trial(db, auth.user_id, SubscriptionType::Worker, &plan_name).await?;
+    Ok(Json(ApiResponse::success_with_message("Free trial started".to_string(), data)))
+}
+
+#[openapi(tag = "JobSeekerSubscription")]
+#[post("/job-seeker/subscription/trial/<plan_name>")]
+pub async fn start_job_seeker_trial(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    plan_name: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = start_trial(db, auth.user_id, SubscriptionType::JobSeeker, &plan_name).await?;
+    Ok(Json(ApiResponse::success_with_message("Free trial started".to_string(), data)))
+}
+
+// ============================================================================
+// WORKER PLAN CHANGES
+// ============================================================================
+
+#[openapi(tag = "Subscription")]
+#[post("/subscription/change/<plan_name>")]
+pub async fn change_worker_plan(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    plan_name: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = change_plan(db, auth.user_id, SubscriptionType::Worker, &plan_name).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "Subscription")]
+#[post("/subscription/change/verify", data = "<dto>")]
+pub async fn verify_worker_plan_change(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<VerifyPlanChangeDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = verify_plan_change(db, auth.user_id, SubscriptionType::Worker, &dto).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "Subscription")]
+#[delete("/subscription/change/scheduled")]
+pub async fn cancel_worker_scheduled_change(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = cancel_scheduled_downgrade(db, auth.user_id, SubscriptionType::Worker).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "Subscription")]
+#[get("/subscription/history")]
+pub async fn get_worker_plan_history(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = plan_history(db, auth.user_id, SubscriptionType::Worker).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "Subscription")]
+#[put("/subscription/auto-renew", data = "<dto>")]
+pub async fn update_worker_auto_renew(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<UpdateAutoRenewDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = set_auto_renew(db, auth.user_id, SubscriptionType::Worker, dto.enabled).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "Subscription")]
+#[post("/subscription/auto-renew/confirm", data = "<dto>")]
+pub async fn confirm_worker_auto_renew(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<ConfirmMandateDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = confirm_auto_renew(db, auth.user_id, SubscriptionType::Worker, &dto).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+// ============================================================================
+// JOB SEEKER PLAN CHANGES
+// ============================================================================
+
+#[openapi(tag = "JobSeekerSubscription")]
+#[post("/job-seeker/subscription/change/<plan_name>")]
+pub async fn change_job_seeker_plan(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    plan_name: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = change_plan(db, auth.user_id, SubscriptionType::JobSeeker, &plan_name).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "JobSeekerSubscription")]
+#[post("/job-seeker/subscription/change/verify", data = "<dto>")]
+pub async fn verify_job_seeker_plan_change(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<VerifyPlanChangeDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = verify_plan_change(db, auth.user_id, SubscriptionType::JobSeeker, &dto).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "JobSeekerSubscription")]
+#[delete("/job-seeker/subscription/change/scheduled")]
+pub async fn cancel_job_seeker_scheduled_change(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = cancel_scheduled_downgrade(db, auth.user_id, SubscriptionType::JobSeeker).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "JobSeekerSubscription")]
+#[get("/job-seeker/subscription/history")]
+pub async fn get_job_seeker_plan_history(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = plan_history(db, auth.user_id, SubscriptionType::JobSeeker).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "JobSeekerSubscription")]
+#[put("/job-seeker/subscription/auto-renew", data = "<dto>")]
+pub async fn update_job_seeker_auto_renew(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<UpdateAutoRenewDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = set_auto_renew(db, auth.user_id, SubscriptionType::JobSeeker, dto.enabled).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
+
+#[openapi(tag = "JobSeekerSubscription")]
+#[post("/job-seeker/subscription/auto-renew/confirm", data = "<dto>")]
+pub async fn confirm_job_seeker_auto_renew(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<ConfirmMandateDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let data = confirm_auto_renew(db, auth.user_id, SubscriptionType::JobSeeker, &dto).await?;
+    Ok(Json(ApiResponse::success(data)))
+}
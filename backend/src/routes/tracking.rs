@@ -0,0 +1,230 @@
+use crate::db::DbConn;
+use crate::guards::AuthGuard;
+use crate::models::{Booking, BookingLocation, BookingStatus, GeoLocation, LocationPingDto};
+use crate::services::EventHub;
+use crate::utils::{ApiError, ApiResponse};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{DateTime, doc};
+use mongodb::options::FindOneOptions;
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::Json;
+use rocket::tokio::select;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::tokio::time::{Duration, interval};
+use rocket::{Shutdown, State};
+use rocket_okapi::openapi;
+
+/// Positions closer together than this are dropped
+const MIN_PING_INTERVAL_MS: i64 = 2_000;
+/// How often an open stream re-checks that the booking is still active
+const STATUS_CHECK_SECS: u64 = 15;
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+// ============================================================================
+// SHARED TRACKING LOGIC
+// ============================================================================
+
+/// Load a booking the user takes part in
+async fn find_party_booking(db: &DbConn, booking_id: &str, user_id: ObjectId) -> Result<Booking, ApiError> {
+    let booking_oid = ObjectId::parse_str(booking_id)
+        .map_err(|_| ApiError::bad_request("Invalid booking ID"))?;
+
+    db.collection::<Booking>("bookings")
+        .find_one(
+            doc! {
+                "_id": booking_oid,
+                "$or": [{ "customer_id": user_id }, { "worker_user_id": user_id }]
+            },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Booking not found"))
+}
+
+/// Tracking runs from acceptance until the booking closes
+fn is_trackable(status: BookingStatus) -> bool {
+    matches!(status, BookingStatus::Accepted | BookingStatus::InProgress)
+}
+
+async fn latest_location(db: &DbConn, booking_id: Option<ObjectId>) -> Result<Option<BookingLocation>, ApiError> {
+    db.collection::<BookingLocation>("booking_locations")
+        .find_one(
+            doc! { "booking_id": booking_id },
+            FindOneOptions::builder().sort(doc! { "recorded_at": -1 }).build(),
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))
+}
+
+fn distance_m(from: [f64; 2], to: [f64; 2]) -> f64 {
+    let (lon1, lat1) = (from[0].to_radians(), from[1].to_radians());
+    let (lon2, lat2) = (to[0].to_radians(), to[1].to_radians());
+
+    let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+        + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+/// Position as sent to the customer, with the remaining distance to the job
+fn location_json(booking: &Booking, location: &BookingLocation) -> serde_json::Value {
+    let [longitude, latitude] = location.location.coordinates;
+
+    serde_json::json!({
+        "booking_id": location.booking_id.to_hex(),
+        "latitude": latitude,
+        "longitude": longitude,
+        "accuracy_m": location.accuracy_m,
+        "heading": location.heading,
+        "speed_mps": location.speed_mps,
+        "distance_to_job_m": distance_m(location.location.coordinates, booking.location.coordinates).round(),
+        "recorded_at": location.recorded_at
+    })
+}
+
+// ============================================================================
+// TRACKING ENDPOINTS
+// ============================================================================
+
+/// Worker app posts its position while heading to and working on a booking
+#[openapi(tag = "Tracking")]
+#[post("/booking/<booking_id>/location", data = "<dto>")]
+pub async fn post_booking_location(
+    db: &State<DbConn>,
+    hub: &State<EventHub>,
+    auth: AuthGuard,
+    booking_id: String,
+    dto: Json<LocationPingDto>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_party_booking(db, &booking_id, auth.user_id).await?;
+    if booking.worker_user_id != auth.user_id {
+        return Err(ApiError::forbidden("Only the worker can share their location"));
+    }
+    if !is_trackable(booking.status) {
+        return Err(ApiError::bad_request("Location sharing has ended for this booking"));
+    }
+    if !(-90.0..=90.0).contains(&dto.latitude) {
+        return Err(ApiError::bad_request("Invalid latitude"));
+    }
+    if !(-180.0..=180.0).contains(&dto.longitude) {
+        return Err(ApiError::bad_request("Invalid longitude"));
+    }
+
+    let now = DateTime::now();
+    if let Some(last) = latest_location(db, booking.id).await?
+        && now.timestamp_millis() - last.recorded_at.timestamp_millis() < MIN_PING_INTERVAL_MS
+    {
+        return Err(ApiError::too_many_requests("Location updates are too frequent"));
+    }
+
+    let location = BookingLocation {
+        id: None,
+        booking_id: booking.id.ok_or_else(|| ApiError::internal_error("Booking missing ID"))?,
+        worker_user_id: auth.user_id,
+        location: GeoLocation {
+            geo_type: String::from("Point"),
+            coordinates: [dto.longitude, dto.latitude],
+        },
+        accuracy_m: dto.accuracy_m,
+        heading: dto.heading,
+        speed_mps: dto.speed_mps,
+        recorded_at: now,
+    };
+
+    db.collection::<BookingLocation>("booking_locations")
+        .insert_one(&location, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to save location: {}", e)))?;
+
+    let data = location_json(&booking, &location);
+    hub.publish(vec![booking.customer_id, booking.worker_user_id], "location", data.clone());
+
+    Ok(Json(ApiResponse::success(data)))
+}
+
+/// Latest shared position, for clients polling instead of streaming
+#[openapi(tag = "Tracking")]
+#[get("/booking/<booking_id>/location")]
+pub async fn get_booking_location(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    booking_id: String,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let booking = find_party_booking(db, &booking_id, auth.user_id).await?;
+    let tracking = is_trackable(booking.status);
+
+    let location = if tracking {
+        latest_location(db, booking.id).await?
+    } else {
+        None
+    };
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "tracking": tracking,
+        "location": location.map(|l| location_json(&booking, &l))
+    }))))
+}
+
+/// Server-sent events for a booking's parties: the last known position, then a
+/// "location" event per update. Sends "tracking_stopped" and closes once the
+/// booking is no longer active.
+#[get("/booking/<booking_id>/location/stream")]
+pub async fn booking_location_stream(
+    db: &State<DbConn>,
+    hub: &State<EventHub>,
+    auth: AuthGuard,
+    booking_id: String,
+    mut shutdown: Shutdown,
+) -> Result<EventStream![], ApiError> {
+    let booking = find_party_booking(db, &booking_id, auth.user_id).await?;
+    if !is_trackable(booking.status) {
+        return Err(ApiError::bad_request("Location sharing has ended for this booking"));
+    }
+
+    let db = db.inner().clone();
+    let mut receiver = hub.subscribe();
+    let last = latest_location(&db, booking.id).await?;
+    let user_id = auth.user_id;
+
+    Ok(EventStream! {
+        if let Some(last) = last {
+            yield Event::json(&location_json(&booking, &last)).event("location");
+        }
+
+        let mut status_check = interval(Duration::from_secs(STATUS_CHECK_SECS));
+        loop {
+            // None when it's time to re-check the booking
+            let event = select! {
+                event = receiver.recv() => match event {
+                    Ok(event) => Some(event),
+                    Err(RecvError::Closed) => break,
+                    Err(RecvError::Lagged(_)) => continue,
+                },
+                _ = status_check.tick() => None,
+                _ = &mut shutdown => break,
+            };
+
+            match event {
+                Some(event) => {
+                    let for_booking = event.data.get("booking_id").and_then(|v| v.as_str()) == Some(booking_id.as_str());
+                    if event.event == "location" && for_booking && event.recipients.contains(&user_id) {
+                        yield Event::json(&event.data).event(event.event);
+                    }
+                }
+                None => {
+                    let active = db
+                        .collection::<Booking>("bookings")
+                        .find_one(doc! { "_id": booking.id }, None)
+                        .await
+                        .ok()
+                        .flatten()
+                        .is_some_and(|b| is_trackable(b.status));
+                    if !active {
+                        yield Event::json(&serde_json::json!({ "booking_id": booking_id })).event("tracking_stopped");
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
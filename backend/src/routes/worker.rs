@@ -1,16 +1,22 @@
 use crate::db::DbConn;
 use crate::guards::{AuthGuard, KycGuard};
 use crate::models::{
-    CreateWorkerProfileDto, GeoLocation, NearbyWorkerQuery, Subscription, SubscriptionStatus,
-    SubscriptionType, UpdateLocationDto, UpdateWorkerProfileDto, WorkerProfile,
-    WorkerSubscriptionPlan,
+    CreateWorkerProfileDto, GeoLocation, NearbyWorkerQuery, SlotQuery, Subscription,
+    SubscriptionStatus, SubscriptionType, UpdateLocationDto, UpdateWorkerProfileDto,
+    WorkerAvailability, WorkerProfile, WorkerSubscriptionPlan, find_plan,
 };
-use crate::services::RazorpayService;
+use crate::services::availability::MAX_SLOT_RANGE_DAYS;
+use crate::services::{
+    AvailabilityService, BillingService, MatchingService, RazorpayService, ReliabilityService,
+};
+use crate::utils::search;
+use crate::utils::time::{parse_rfc3339, to_local};
 use crate::utils::{ApiError, ApiResponse};
+use chrono::{NaiveDate, Timelike};
 use hmac::{Hmac, Mac};
 use mongodb::bson::oid::ObjectId;
-use mongodb::bson::{DateTime, doc};
-use mongodb::options::FindOptions;
+use mongodb::bson::{DateTime, Document, doc};
+use rocket::futures::TryStreamExt;
 use rocket::http::Status;
 use rocket::serde::json::Json;
 use rocket::{Request, State};
@@ -35,19 +41,15 @@ pub async fn create_subscription(
     plan_name: String,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
     // Validate plan and get price
-    let (price, plan_type) = match plan_name.to_lowercase().as_str() {
-        "silver" => (499.0, WorkerSubscriptionPlan::Silver),
-        "gold" => (799.0, WorkerSubscriptionPlan::Gold),
-        _ => {
-            return Err(ApiError::bad_request(
-                "Invalid plan. Choose 'silver' or 'gold'",
-            ));
-        }
-    };
+    let plan = find_plan(&SubscriptionType::Worker, &plan_name).ok_or_else(|| {
+        ApiError::bad_request("Invalid plan. Choose 'silver' or 'gold'")
+    })?;
+    let price = plan.price;
 
     let now = DateTime::now();
-    let expires_at =
-        DateTime::from_millis(chrono::Utc::now().timestamp_millis() + 365 * 24 * 60 * 60 * 1000);
+    let expires_at = DateTime::from_millis(
+        chrono::Utc::now().timestamp_millis() + plan.duration_days * 24 * 60 * 60 * 1000,
+    );
 
     // Check if user already has an active subscription (worker)
     let existing = db
@@ -56,7 +58,8 @@ pub async fn create_subscription(
             doc! {
                 "user_id": auth.user_id,
                 "subscription_type": "worker",
-                "status": "active"
+                "status": "active",
+                "is_trial": { "$ne": true } // A trial can be converted to a paid plan
             },
             None,
         )
@@ -65,7 +68,7 @@ pub async fn create_subscription(
 
     if existing.is_some() {
         return Err(ApiError::bad_request(
-            "You already have an active subscription",
+            "You already have an active subscription. Use /subscription/change/<plan_name> to switch plans",
         ));
     }
 
@@ -103,6 +106,14 @@ pub async fn create_subscription(
         auto_renew: false,
         payment_id: None,
         invoice_number: Some(next_invoice_number),
+        previous_subscription_id: None,
+        scheduled_plan: None,
+        mandate: None,
+        renewal_attempts: 0,
+        next_renewal_at: None,
+        renewal_payment_id: None,
+        grace_until: None,
+        is_trial: false,
         created_at: now,
         updated_at: now,
     };
@@ -196,6 +207,10 @@ pub async fn verify_subscription_payment(
         ));
     }
 
+    BillingService::supersede_trials(db, auth.user_id, &SubscriptionType::Worker, subscription_id)
+        .await
+        .map_err(ApiError::internal_error)?;
+
     /* ------------------------------------------------------------------ */
     /* 3. FETCH UPDATED SUBSCRIPTION                                       */
     /* ------------------------------------------------------------------ */
@@ -276,7 +291,8 @@ pub async fn get_subscription_status(
                 "plan_name": sub.plan_name,
                 "status": format!("{:?}", sub.status),
                 "expires_at": sub.expires_at,
-                "auto_renew": sub.auto_renew
+                "auto_renew": sub.auto_renew,
+                "is_trial": sub.is_trial
             }
         }))))
     } else {
@@ -364,6 +380,9 @@ pub async fn create_worker_profile(
         rating: 0.0,
         total_reviews: 0,
         total_jobs_completed: 0,
+        availability: None,
+        suspended_until: None,
+        reliability: None,
         created_at: DateTime::now(),
         location,
         updated_at: DateTime::now(),
@@ -459,6 +478,23 @@ pub async fn update_worker_profile(
         update_doc.insert("service_areas", areas);
     }
     if let Some(available) = dto.is_available {
+        if available {
+            let suspended = db
+                .collection::<WorkerProfile>("worker_profiles")
+                .find_one(
+                    doc! { "user_id": auth.user_id, "suspended_until": { "$gt": DateTime::now() } },
+                    None,
+                )
+                .await
+                .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+            if let Some(until) = suspended.and_then(|w| w.suspended_until) {
+                return Err(ApiError::forbidden(format!(
+                    "Your profile is suspended until {}",
+                    crate::utils::time::format_date(until)
+                )));
+            }
+        }
         update_doc.insert("is_available", available);
     }
 
@@ -504,46 +540,138 @@ pub async fn search_workers(
         filter.insert("subcategories", subcategory);
     }
 
+    if let Some(city) = query.city.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        filter.insert(
+            "service_areas",
+            doc! { "$regex": format!("^{}$", regex::escape(city)), "$options": "i" },
+        );
+    }
+
     if let Some(min_rating) = query.min_rating {
         filter.insert("rating", doc! { "$gte": min_rating });
     }
 
-    let find_options = FindOptions::builder()
-        .skip(skip as u64)
-        .limit(limit)
-        .sort(doc! {
-            "subscription_plan": -1,
-            "rating": -1,
-            "total_reviews": -1
-        })
-        .build();
+    if let Some(available) =
+        available_at_filter(db, query.available_at.as_deref(), query.duration_minutes).await?
+    {
+        filter.extend(available);
+    }
+
+    // Text search ranks by relevance; with no exact hits, fall back to typo-tolerant prefixes
+    let terms = search::terms(query.q.as_deref().unwrap_or_default());
+    let mut by_relevance = false;
+    let mut fuzzy = false;
+    if !terms.is_empty() {
+        // Combined with $and, as the availability filter may bring its own
+        let text_filter = doc! { "$and": [filter.clone(), search::text_filter(&terms)] };
+        let matched = db
+            .collection::<WorkerProfile>("worker_profiles")
+            .count_documents(text_filter.clone(), None)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
+        if matched > 0 {
+            filter = text_filter;
+            by_relevance = true;
+        } else {
+            filter = doc! { "$and": [filter, search::fuzzy_filter(&terms, &WORKER_SEARCH_FIELDS)] };
+            fuzzy = true;
+        }
+    }
+
+    // Priority listings come first; rating and reliability decide the order within each group
+    let mut pipeline = vec![doc! { "$match": filter.clone() }];
+    let mut sort = doc! {};
+    if by_relevance {
+        pipeline.push(doc! { "$addFields": { "relevance": { "$meta": "textScore" } } });
+        sort.insert("relevance", -1);
+    }
+    sort.extend(doc! {
+        "priority_listing": -1,
+        "rank_score": -1,
+        "total_reviews": -1
+    });
+    pipeline.extend([
+        ReliabilityService::rank_stage(),
+        doc! { "$sort": sort },
+        doc! { "$skip": skip },
+        doc! { "$limit": limit },
+    ]);
 
     let mut cursor = db
         .collection::<WorkerProfile>("worker_profiles")
-        .find(filter.clone(), find_options)
+        .aggregate(pipeline, None)
         .await
         .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
 
-    let mut workers = Vec::new();
+    let mut workers: Vec<serde_json::Value> = Vec::new();
     while cursor
         .advance()
         .await
         .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?
     {
-        let worker = cursor
-            .deserialize_current()
+        let worker_doc = cursor.deserialize_current().map_err(|e| {
+            ApiError::internal_error(format!("Deserialization error: {}", e))
+        })?;
+        let relevance = worker_doc.get_f64("relevance").ok();
+        let worker: WorkerProfile = mongodb::bson::from_document(worker_doc)
             .map_err(|e| ApiError::internal_error(format!("Deserialization error: {}", e)))?;
-        workers.push(worker);
+
+        let highlights = (!terms.is_empty()).then(|| {
+            let categories = worker.categories.join(", ");
+            let subcategories = worker.subcategories.join(", ");
+            let service_areas = worker.service_areas.join(", ");
+            search::highlights(
+                &[
+                    ("categories", categories.as_str()),
+                    ("subcategories", subcategories.as_str()),
+                    ("service_areas", service_areas.as_str()),
+                    ("description", worker.description.as_deref().unwrap_or_default()),
+                ],
+                &terms,
+            )
+        });
+        let mut data = serde_json::json!(worker);
+        if let Some(highlights) = highlights {
+            data["highlights"] = highlights;
+            data["relevance"] = serde_json::json!(relevance);
+        }
+        workers.push(data);
     }
 
     let total = db
         .collection::<WorkerProfile>("worker_profiles")
-        .count_documents(filter, None)
+        .count_documents(filter.clone(), None)
         .await
         .map_err(|e| ApiError::internal_error(format!("Count error: {}", e)))?;
 
+    let facets = db
+        .collection::<WorkerProfile>("worker_profiles")
+        .aggregate(
+            vec![
+                doc! { "$match": filter },
+                doc! {
+                    "$facet": {
+                        "category": search::facet("categories", true),
+                        "service_area": search::facet("service_areas", true)
+                    }
+                },
+            ],
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .try_next()
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Cursor error: {}", e)))?
+        .unwrap_or_default();
+
     Ok(Json(ApiResponse::success(serde_json::json!({
         "workers": workers,
+        "facets": {
+            "category": search::facet_counts(facets.get("category")),
+            "service_area": search::facet_counts(facets.get("service_area")),
+        },
+        "fuzzy": fuzzy,
         "pagination": {
             "page": page,
             "limit": limit,
@@ -555,14 +683,25 @@ pub async fn search_workers(
 
 #[derive(FromForm, serde::Deserialize, rocket_okapi::okapi::schemars::JsonSchema)]
 pub struct SearchWorkersQuery {
+    pub q: Option<String>, // Matched against categories, description and service areas
     pub category: Option<String>,
     pub subcategory: Option<String>,
-    pub city: Option<String>,
+    pub city: Option<String>, // One of the worker's service areas
     pub min_rating: Option<f64>,
+    pub available_at: Option<String>, // RFC 3339; only workers free from then on
+    pub duration_minutes: Option<i32>, // Length of the free window needed (default 60)
     pub page: Option<i64>,
     pub limit: Option<i64>,
 }
 
+/// Fields matched by the typo-tolerant fallback; the text index covers the same ones
+const WORKER_SEARCH_FIELDS: [&str; 4] = ["categories", "subcategories", "service_areas", "description"];
+
+/// Search radius of the nearby workers listing
+const NEARBY_RADIUS_M: f64 = 50_000.0;
+/// Workers within the same band of distance are ranked by plan and score rather than metres
+const DISTANCE_BAND_M: f64 = 2_000.0;
+
 #[openapi(tag = "Worker")]
 #[get("/worker/nearby?<query..>")]
 pub async fn find_nearby_workers(
@@ -586,20 +725,15 @@ pub async fn find_nearby_workers(
         match_filter.insert("subcategories", subcategory);
     }
 
+    if let Some(available) =
+        available_at_filter(db, query.available_at.as_deref(), query.duration_minutes).await?
+    {
+        match_filter.extend(available);
+    }
+
     let pipeline = vec![
         // 1️⃣ GEO SEARCH (uses 2dsphere index)
-        doc! {
-            "$geoNear": {
-                "near": {
-                    "type": "Point",
-                    "coordinates": [query.longitude, query.latitude]
-                },
-                "distanceField": "distance",
-                "maxDistance": 50_000,
-                "spherical": true,
-                "key": "location"
-            }
-        },
+        MatchingService::geo_near_stage(query.longitude, query.latitude, NEARBY_RADIUS_M),
         // 2️⃣ FILTER EARLY
         doc! { "$match": match_filter.clone() },
         // 3️⃣ LOOKUP USER (PIPELINE + PROJECTION = FAST)
@@ -616,8 +750,7 @@ pub async fn find_nearby_workers(
                     {
                         "$project": {
                             "_id": 0,
-                            "name": 1,
-                            "mobile": 1
+                            "name": 1
                         }
                     }
                 ],
@@ -626,19 +759,25 @@ pub async fn find_nearby_workers(
         },
         // 4️⃣ FLATTEN USER
         doc! { "$unwind": "$user" },
-        // 5️⃣ EXPOSE FIELDS FOR FRONTEND
+        // 5️⃣ EXPOSE FIELDS FOR FRONTEND (phone numbers only via a conversation's contact reveal)
         doc! {
             "$addFields": {
-                "phone": "$user.mobile",
                 "name": "$user.name"
             }
         },
-        // 6️⃣ SORT (after filtering, before pagination)
+        // 6️⃣ RANK: nearest 2 km band first, then priority listing, rating and reliability
+        ReliabilityService::rank_stage(),
+        doc! {
+            "$addFields": {
+                "distance_band": { "$floor": { "$divide": ["$distance", DISTANCE_BAND_M] } }
+            }
+        },
         doc! {
             "$sort": {
-                "distance": 1,
-                "subscription_plan": -1,
-                "rating": -1
+                "distance_band": 1,
+                "priority_listing": -1,
+                "rank_score": -1,
+                "distance": 1
             }
         },
         // 7️⃣ PAGINATION
@@ -647,7 +786,8 @@ pub async fn find_nearby_workers(
         // 8️⃣ CLEAN RESPONSE
         doc! {
             "$project": {
-                "user": 0
+                "user": 0,
+                "distance_band": 0
             }
         },
     ];
@@ -673,18 +813,7 @@ pub async fn find_nearby_workers(
 
     // ---------- COUNT (FAST + SAME FILTER) ----------
     let count_pipeline = vec![
-        doc! {
-            "$geoNear": {
-                "near": {
-                    "type": "Point",
-                    "coordinates": [query.longitude, query.latitude]
-                },
-                "distanceField": "distance",
-                "maxDistance": 50_000,
-                "spherical": true,
-                "key": "location"
-            }
-        },
+        MatchingService::geo_near_stage(query.longitude, query.latitude, NEARBY_RADIUS_M),
         doc! { "$match": match_filter },
         doc! { "$count": "total" },
     ];
@@ -756,4 +885,150 @@ pub async fn update_worker_location(
     Ok(Json(ApiResponse::success(serde_json::json!({
         "message": "Location updated successfully"
     }))))
-}
\ No newline at end of file
+}
+
+// ============================================================================
+// WORKER AVAILABILITY ENDPOINTS
+// ============================================================================
+
+/// Profile filter for the `available_at` search parameter, if given
+async fn available_at_filter(
+    db: &State<DbConn>,
+    available_at: Option<&str>,
+    duration_minutes: Option<i32>,
+) -> Result<Option<Document>, ApiError> {
+    let Some(available_at) = available_at else {
+        return Ok(None);
+    };
+
+    let at = parse_rfc3339(available_at)
+        .ok_or_else(|| ApiError::bad_request("Invalid available_at, expected an RFC 3339 timestamp"))?;
+    let duration = duration_minutes.unwrap_or(60);
+    if !(15..=12 * 60).contains(&duration) {
+        return Err(ApiError::bad_request("Duration must be between 15 minutes and 12 hours"));
+    }
+
+    let local = to_local(at);
+    if (local.hour() * 60 + local.minute()) as i32 + duration > 24 * 60 {
+        return Err(ApiError::bad_request("The requested time window must end on the same day"));
+    }
+
+    let filter = AvailabilityService::available_at_filter(db, at, duration)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    Ok(Some(filter))
+}
+
+#[openapi(tag = "Worker")]
+#[put("/worker/availability", data = "<dto>")]
+pub async fn update_worker_availability(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+    dto: Json<WorkerAvailability>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let mut availability = dto.into_inner();
+    availability.validate().map_err(ApiError::bad_request)?;
+    availability.holidays.sort();
+    availability.holidays.dedup();
+
+    let availability_bson = mongodb::bson::to_bson(&availability)
+        .map_err(|e| ApiError::internal_error(format!("Serialization error: {}", e)))?;
+
+    let result = db
+        .collection::<WorkerProfile>("worker_profiles")
+        .update_one(
+            doc! { "user_id": auth.user_id },
+            doc! { "$set": { "availability": availability_bson, "updated_at": DateTime::now() } },
+            None,
+        )
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?;
+
+    if result.matched_count == 0 {
+        return Err(ApiError::not_found("Worker profile not found"));
+    }
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "message": "Availability updated successfully",
+        "availability": availability
+    }))))
+}
+
+#[openapi(tag = "Worker")]
+#[get("/worker/availability")]
+pub async fn get_worker_availability(
+    db: &State<DbConn>,
+    auth: AuthGuard,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker = db
+        .collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "user_id": auth.user_id }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker profile not found"))?;
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "availability": AvailabilityService::calendar(&worker),
+        "is_default": worker.availability.is_none()
+    }))))
+}
+
+#[openapi(tag = "Worker")]
+#[get("/worker/slots/<worker_id>?<query..>")]
+pub async fn get_worker_slots(
+    db: &State<DbConn>,
+    worker_id: String,
+    query: SlotQuery,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let worker_oid =
+        ObjectId::parse_str(&worker_id).map_err(|_| ApiError::bad_request("Invalid worker ID"))?;
+
+    let parse_date = |value: &str| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| ApiError::bad_request(format!("Invalid date '{}', expected YYYY-MM-DD", value)))
+    };
+    let from = parse_date(&query.from)?;
+    let to = match &query.to {
+        Some(to) => parse_date(to)?,
+        None => from,
+    };
+
+    if to < from {
+        return Err(ApiError::bad_request("'to' must not be before 'from'"));
+    }
+    if (to - from).num_days() >= MAX_SLOT_RANGE_DAYS {
+        return Err(ApiError::bad_request(format!(
+            "Slots can be listed for at most {} days at a time",
+            MAX_SLOT_RANGE_DAYS
+        )));
+    }
+
+    let duration = query.duration_minutes.unwrap_or(60);
+    if !(15..=12 * 60).contains(&duration) {
+        return Err(ApiError::bad_request("Duration must be between 15 minutes and 12 hours"));
+    }
+
+    let worker = db
+        .collection::<WorkerProfile>("worker_profiles")
+        .find_one(doc! { "_id": worker_oid }, None)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+        .ok_or_else(|| ApiError::not_found("Worker not found"))?;
+
+    // Workers who paused bookings have no free slots
+    let days = if worker.is_verified && worker.is_available {
+        AvailabilityService::free_slots(db, &worker, from, to, duration)
+            .await
+            .map_err(|e| ApiError::internal_error(format!("Database error: {}", e)))?
+    } else {
+        Vec::new()
+    };
+
+    Ok(Json(ApiResponse::success(serde_json::json!({
+        "worker_id": worker_id,
+        "duration_minutes": duration,
+        "accepting_bookings": worker.is_verified && worker.is_available,
+        "days": days
+    }))))
+}
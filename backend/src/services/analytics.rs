@@ -0,0 +1,488 @@
+use chrono::{Datelike, Duration, NaiveDate, TimeZone, Utc};
+use mongodb::bson::{doc, Bson, DateTime, Document};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+/// Statuses a paid subscription can be in. Abandoned checkouts are also stored as
+/// cancelled, so this is always paired with a `payment_id` check (see `paid_filter`)
+const PAID_STATUSES: [&str; 4] = ["active", "expired", "superseded", "cancelled"];
+
+/// Matches subscriptions that were actually paid for
+fn paid_filter() -> Document {
+    doc! {
+        "status": { "$in": PAID_STATUSES.to_vec() },
+        "payment_id": { "$ne": null }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interval {
+    Day,
+    Week,
+    Month,
+}
+
+impl Interval {
+    pub fn parse(value: &str) -> Option<Interval> {
+        match value.to_lowercase().as_str() {
+            "day" | "daily" => Some(Interval::Day),
+            "week" | "weekly" => Some(Interval::Week),
+            "month" | "monthly" => Some(Interval::Month),
+            _ => None,
+        }
+    }
+
+    /// Unit name understood by `$dateTrunc` and `$densify`
+    fn unit(&self) -> &'static str {
+        match self {
+            Interval::Day => "day",
+            Interval::Week => "week",
+            Interval::Month => "month",
+        }
+    }
+
+    fn label_format(&self) -> &'static str {
+        match self {
+            Interval::Day | Interval::Week => "%Y-%m-%d",
+            Interval::Month => "%Y-%m",
+        }
+    }
+
+    /// Start of the bucket containing `date` (weeks start on Monday, all in UTC)
+    fn truncate(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Interval::Day => date,
+            Interval::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Interval::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+/// Reporting window: `from` inclusive, `to` exclusive, bucketed by `interval`
+#[derive(Debug, Clone)]
+pub struct AnalyticsRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub interval: Interval,
+    pub subscription_type: Option<String>,
+}
+
+impl AnalyticsRange {
+    fn start_dt(&self) -> DateTime {
+        day_start(self.from)
+    }
+
+    fn end_dt(&self) -> DateTime {
+        day_start(self.to)
+    }
+
+    /// First bucket boundary, so densified buckets line up with `$dateTrunc`
+    fn bucket_start(&self) -> DateTime {
+        day_start(self.interval.truncate(self.from))
+    }
+
+    fn type_filter(&self, filter: &mut Document) {
+        if let Some(ref t) = self.subscription_type {
+            filter.insert("subscription_type", t);
+        }
+    }
+
+    fn date_trunc(&self, field: &str) -> Document {
+        doc! {
+            "$dateTrunc": {
+                "date": field,
+                "unit": self.interval.unit(),
+                "startOfWeek": "monday"
+            }
+        }
+    }
+
+    /// Fill buckets with no documents so the series has no gaps
+    fn densify(&self) -> Document {
+        doc! {
+            "$densify": {
+                "field": "_id",
+                "range": {
+                    "step": 1,
+                    "unit": self.interval.unit(),
+                    "bounds": [self.bucket_start(), self.end_dt()]
+                }
+            }
+        }
+    }
+
+    fn period_label(&self) -> Document {
+        doc! { "$dateToString": { "format": self.interval.label_format(), "date": "$_id" } }
+    }
+}
+
+fn day_start(date: NaiveDate) -> DateTime {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    DateTime::from_millis(Utc.from_utc_datetime(&midnight).timestamp_millis())
+}
+
+fn sum_if(condition: Document, value: impl Into<Bson>) -> Document {
+    doc! { "$sum": { "$cond": [condition, value.into(), 0] } }
+}
+
+pub struct AnalyticsService;
+
+impl AnalyticsService {
+    async fn run(db: &Database, pipeline: Vec<Document>) -> Result<Vec<Document>, String> {
+        db.collection::<Document>("subscriptions")
+            .aggregate(pipeline, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Paid subscriptions booked inside the range, tagged as new / renewal / upgrade
+    fn payments_stages(range: &AnalyticsRange) -> Vec<Document> {
+        let mut filter = paid_filter();
+        filter.extend(doc! {
+            "is_trial": { "$ne": true },
+            "price": { "$gt": 0 },
+            "created_at": { "$gte": range.start_dt(), "$lt": range.end_dt() }
+        });
+        range.type_filter(&mut filter);
+
+        vec![
+            doc! { "$match": filter },
+            doc! {
+                "$lookup": {
+                    "from": "plan_changes",
+                    "localField": "_id",
+                    "foreignField": "to_subscription_id",
+                    "as": "upgrade"
+                }
+            },
+            doc! {
+                "$addFields": {
+                    "kind": {
+                        "$switch": {
+                            "branches": [
+                                { "case": { "$gt": [{ "$size": "$upgrade" }, 0] }, "then": "upgrade" },
+                                { "case": { "$gt": ["$previous_subscription_id", null] }, "then": "renewal" }
+                            ],
+                            "default": "new"
+                        }
+                    }
+                }
+            },
+        ]
+    }
+
+    /// One document per paid subscription with the interval it actually covered:
+    /// `starts_at`, `ended_at`, `is_new` (no predecessor) and `churned`
+    /// (ended without being renewed or replaced)
+    fn lifecycle_stages(range: &AnalyticsRange) -> Vec<Document> {
+        let mut filter = paid_filter();
+        filter.extend(doc! {
+            "is_trial": { "$ne": true },
+            "price": { "$gt": 0 },
+            "starts_at": { "$lt": range.end_dt() }
+        });
+        range.type_filter(&mut filter);
+
+        // Only a paid successor counts as a renewal
+        let mut successor = paid_filter();
+        successor.insert("$expr", doc! { "$eq": ["$previous_subscription_id", "$$sid"] });
+
+        vec![
+            doc! { "$match": filter },
+            doc! {
+                "$lookup": {
+                    "from": "subscriptions",
+                    "let": { "sid": "$_id" },
+                    "pipeline": [
+                        { "$match": successor },
+                        { "$project": { "starts_at": 1 } }
+                    ],
+                    "as": "successor"
+                }
+            },
+            doc! {
+                "$addFields": {
+                    "successor_starts_at": { "$min": "$successor.starts_at" },
+                    "is_new": { "$not": [{ "$gt": ["$previous_subscription_id", null] }] }
+                }
+            },
+            doc! {
+                "$addFields": {
+                    // Upgrades end the old period early, cancellations end it when cancelled
+                    "ended_at": {
+                        "$switch": {
+                            "branches": [
+                                {
+                                    "case": { "$eq": ["$status", "superseded"] },
+                                    "then": { "$min": ["$expires_at", "$successor_starts_at"] }
+                                },
+                                {
+                                    "case": { "$eq": ["$status", "cancelled"] },
+                                    "then": { "$min": ["$expires_at", "$updated_at"] }
+                                }
+                            ],
+                            "default": "$expires_at"
+                        }
+                    },
+                    "churned": {
+                        "$and": [
+                            { "$in": ["$status", ["expired", "cancelled"]] },
+                            { "$eq": [{ "$size": "$successor" }, 0] }
+                        ]
+                    }
+                }
+            },
+            doc! { "$project": { "successor": 0, "upgrade": 0 } },
+        ]
+    }
+
+    /// Revenue per bucket, split into new, renewal and upgrade payments
+    pub async fn revenue_series(db: &Database, range: &AnalyticsRange) -> Result<Vec<Document>, String> {
+        let mut pipeline = Self::payments_stages(range);
+        pipeline.extend([
+            doc! {
+                "$group": {
+                    "_id": range.date_trunc("$created_at"),
+                    "revenue": { "$sum": "$price" },
+                    "payments": { "$sum": 1 },
+                    "new_revenue": sum_if(doc! { "$eq": ["$kind", "new"] }, "$price"),
+                    "renewal_revenue": sum_if(doc! { "$eq": ["$kind", "renewal"] }, "$price"),
+                    "upgrade_revenue": sum_if(doc! { "$eq": ["$kind", "upgrade"] }, "$price"),
+                    "new_subscriptions": sum_if(doc! { "$eq": ["$kind", "new"] }, 1),
+                    "renewals": sum_if(doc! { "$eq": ["$kind", "renewal"] }, 1),
+                    "upgrades": sum_if(doc! { "$eq": ["$kind", "upgrade"] }, 1)
+                }
+            },
+            range.densify(),
+            doc! { "$sort": { "_id": 1 } },
+            doc! {
+                "$project": {
+                    "_id": 0,
+                    "period": range.period_label(),
+                    "revenue": { "$round": [{ "$ifNull": ["$revenue", 0] }, 2] },
+                    "payments": { "$ifNull": ["$payments", 0] },
+                    "new_revenue": { "$round": [{ "$ifNull": ["$new_revenue", 0] }, 2] },
+                    "renewal_revenue": { "$round": [{ "$ifNull": ["$renewal_revenue", 0] }, 2] },
+                    "upgrade_revenue": { "$round": [{ "$ifNull": ["$upgrade_revenue", 0] }, 2] },
+                    "new_subscriptions": { "$ifNull": ["$new_subscriptions", 0] },
+                    "renewals": { "$ifNull": ["$renewals", 0] },
+                    "upgrades": { "$ifNull": ["$upgrades", 0] }
+                }
+            },
+        ]);
+
+        Self::run(db, pipeline).await
+    }
+
+    /// Revenue in the range grouped by plan and by subscription type
+    pub async fn revenue_breakdown(db: &Database, range: &AnalyticsRange) -> Result<Document, String> {
+        let mut pipeline = Self::payments_stages(range);
+        pipeline.push(doc! {
+            "$facet": {
+                "by_plan": [
+                    {
+                        "$group": {
+                            "_id": { "plan_name": "$plan_name", "subscription_type": "$subscription_type" },
+                            "revenue": { "$sum": "$price" },
+                            "payments": { "$sum": 1 },
+                            "users": { "$addToSet": "$user_id" }
+                        }
+                    },
+                    { "$sort": { "revenue": -1 } },
+                    {
+                        "$project": {
+                            "_id": 0,
+                            "plan_name": "$_id.plan_name",
+                            "subscription_type": "$_id.subscription_type",
+                            "revenue": { "$round": ["$revenue", 2] },
+                            "payments": 1,
+                            "paying_users": { "$size": "$users" }
+                        }
+                    }
+                ],
+                "by_type": [
+                    {
+                        "$group": {
+                            "_id": "$subscription_type",
+                            "revenue": { "$sum": "$price" },
+                            "payments": { "$sum": 1 },
+                            "users": { "$addToSet": "$user_id" }
+                        }
+                    },
+                    { "$sort": { "revenue": -1 } },
+                    {
+                        "$project": {
+                            "_id": 0,
+                            "subscription_type": "$_id",
+                            "revenue": { "$round": ["$revenue", 2] },
+                            "payments": 1,
+                            "paying_users": { "$size": "$users" }
+                        }
+                    }
+                ]
+            }
+        });
+
+        Ok(Self::run(db, pipeline).await?.into_iter().next().unwrap_or_default())
+    }
+
+    /// Active paying subscribers at the end of each bucket, with new and churned
+    /// subscribers and the churn rate against the bucket's opening count
+    pub async fn subscriber_series(db: &Database, range: &AnalyticsRange) -> Result<Vec<Document>, String> {
+        let mut pipeline = Self::lifecycle_stages(range);
+        pipeline.extend([
+            // Each subscription becomes a +1 event when it starts and a -1 event when it ends
+            doc! {
+                "$project": {
+                    "events": [
+                        {
+                            "at": "$starts_at",
+                            "delta": 1,
+                            "new": { "$cond": ["$is_new", 1, 0] },
+                            "churned": 0
+                        },
+                        {
+                            "at": "$ended_at",
+                            "delta": -1,
+                            "new": 0,
+                            "churned": { "$cond": ["$churned", 1, 0] }
+                        }
+                    ]
+                }
+            },
+            doc! { "$unwind": "$events" },
+            doc! { "$replaceRoot": { "newRoot": "$events" } },
+            doc! { "$match": { "at": { "$lt": range.end_dt() } } },
+            doc! {
+                "$group": {
+                    "_id": range.date_trunc("$at"),
+                    "delta": { "$sum": "$delta" },
+                    "new": { "$sum": "$new" },
+                    "churned": { "$sum": "$churned" }
+                }
+            },
+            range.densify(),
+            // Running total over all history, so buckets before `from` still count
+            doc! {
+                "$setWindowFields": {
+                    "sortBy": { "_id": 1 },
+                    "output": {
+                        "active": {
+                            "$sum": { "$ifNull": ["$delta", 0] },
+                            "window": { "documents": ["unbounded", "current"] }
+                        }
+                    }
+                }
+            },
+            doc! { "$match": { "_id": { "$gte": range.bucket_start() } } },
+            doc! {
+                "$addFields": {
+                    "opening": { "$subtract": ["$active", { "$ifNull": ["$delta", 0] }] },
+                    "churned": { "$ifNull": ["$churned", 0] }
+                }
+            },
+            doc! { "$sort": { "_id": 1 } },
+            doc! {
+                "$project": {
+                    "_id": 0,
+                    "period": range.period_label(),
+                    "active_subscribers": "$active",
+                    "opening_subscribers": "$opening",
+                    "new_subscribers": { "$ifNull": ["$new", 0] },
+                    "churned": "$churned",
+                    "churn_rate": {
+                        "$cond": [
+                            { "$gt": ["$opening", 0] },
+                            { "$round": [{ "$divide": ["$churned", "$opening"] }, 4] },
+                            0
+                        ]
+                    }
+                }
+            },
+        ]);
+
+        Self::run(db, pipeline).await
+    }
+
+    /// Headline numbers for the range: revenue, ARPU, churn and subscribers active at its end
+    pub async fn summary(db: &Database, range: &AnalyticsRange) -> Result<Document, String> {
+        let from = range.start_dt();
+        let to = range.end_dt();
+
+        let mut pipeline = Self::lifecycle_stages(range);
+        pipeline.extend([
+            doc! {
+                "$facet": {
+                    "revenue": [
+                        { "$match": { "created_at": { "$gte": from, "$lt": to } } },
+                        {
+                            "$group": {
+                                "_id": null,
+                                "revenue": { "$sum": "$price" },
+                                "payments": { "$sum": 1 },
+                                "users": { "$addToSet": "$user_id" }
+                            }
+                        }
+                    ],
+                    "opening": [
+                        { "$match": { "starts_at": { "$lt": from }, "ended_at": { "$gt": from } } },
+                        { "$count": "count" }
+                    ],
+                    "new": [
+                        { "$match": { "is_new": true, "starts_at": { "$gte": from, "$lt": to } } },
+                        { "$count": "count" }
+                    ],
+                    "churned": [
+                        { "$match": { "churned": true, "ended_at": { "$gte": from, "$lt": to } } },
+                        { "$count": "count" }
+                    ],
+                    "closing": [
+                        { "$match": { "ended_at": { "$gte": to } } },
+                        { "$count": "count" }
+                    ]
+                }
+            },
+            doc! {
+                "$project": {
+                    "revenue": { "$ifNull": [{ "$first": "$revenue.revenue" }, 0] },
+                    "payments": { "$ifNull": [{ "$first": "$revenue.payments" }, 0] },
+                    "paying_users": { "$size": { "$ifNull": [{ "$first": "$revenue.users" }, []] } },
+                    "opening_subscribers": { "$ifNull": [{ "$first": "$opening.count" }, 0] },
+                    "new_subscribers": { "$ifNull": [{ "$first": "$new.count" }, 0] },
+                    "churned": { "$ifNull": [{ "$first": "$churned.count" }, 0] },
+                    "active_subscribers": { "$ifNull": [{ "$first": "$closing.count" }, 0] }
+                }
+            },
+            doc! {
+                "$project": {
+                    "revenue": { "$round": ["$revenue", 2] },
+                    "payments": 1,
+                    "paying_users": 1,
+                    "arpu": {
+                        "$cond": [
+                            { "$gt": ["$paying_users", 0] },
+                            { "$round": [{ "$divide": ["$revenue", "$paying_users"] }, 2] },
+                            0
+                        ]
+                    },
+                    "opening_subscribers": 1,
+                    "new_subscribers": 1,
+                    "churned": 1,
+                    "churn_rate": {
+                        "$cond": [
+                            { "$gt": ["$opening_subscribers", 0] },
+                            { "$round": [{ "$divide": ["$churned", "$opening_subscribers"] }, 4] },
+                            0
+                        ]
+                    },
+                    "active_subscribers": 1
+                }
+            },
+        ]);
+
+        Ok(Self::run(db, pipeline).await?.into_iter().next().unwrap_or_default())
+    }
+}
@@ -0,0 +1,54 @@
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::Database;
+
+use crate::models::{ApplicationStatus, JobApplication};
+
+pub struct ApplicationService;
+
+impl ApplicationService {
+    /// Bring the job's application list and the seeker's counter in line with an
+    /// application that was just created or changed. Safe to repeat.
+    pub async fn sync(db: &Database, application: &JobApplication) -> Result<(), String> {
+        let application_id = application.id.ok_or("Application missing ID")?;
+        let active = application.status != ApplicationStatus::Withdrawn;
+
+        let update = if active {
+            doc! { "$addToSet": { "applications": application_id } }
+        } else {
+            doc! { "$pull": { "applications": application_id } }
+        };
+        db.collection::<Document>("jobs")
+            .update_one(doc! { "_id": application.job_id }, update, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Self::refresh_seeker_count(db, application.seeker_profile_id).await
+    }
+
+    /// Recount the seeker's active applications rather than incrementing, so a
+    /// failed request can't leave the counter drifting
+    pub async fn refresh_seeker_count(db: &Database, seeker_profile_id: ObjectId) -> Result<(), String> {
+        let count = db
+            .collection::<JobApplication>("applications")
+            .count_documents(
+                doc! {
+                    "seeker_profile_id": seeker_profile_id,
+                    "status": { "$ne": ApplicationStatus::Withdrawn.as_str() }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        db.collection::<Document>("job_seeker_profiles")
+            .update_one(
+                doc! { "_id": seeker_profile_id },
+                doc! { "$set": { "applications_count": count as i32, "updated_at": DateTime::now() } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
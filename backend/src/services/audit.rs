@@ -0,0 +1,38 @@
+use mongodb::bson::{oid::ObjectId, DateTime};
+use mongodb::Database;
+
+use crate::models::AuditLog;
+
+pub struct AuditService;
+
+impl AuditService {
+    /// Append an entry to the audit log; returns its ID
+    pub async fn record(
+        db: &Database,
+        actor_type: &str,
+        actor_id: Option<ObjectId>,
+        action: &str,
+        entity_type: &str,
+        entity_id: Option<ObjectId>,
+        details: serde_json::Value,
+    ) -> Result<Option<ObjectId>, String> {
+        let entry = AuditLog {
+            id: None,
+            actor_type: actor_type.to_string(),
+            actor_id,
+            action: action.to_string(),
+            entity_type: entity_type.to_string(),
+            entity_id,
+            details,
+            created_at: DateTime::now(),
+        };
+
+        let res = db
+            .collection::<AuditLog>("audit_logs")
+            .insert_one(&entry, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(res.inserted_id.as_object_id())
+    }
+}
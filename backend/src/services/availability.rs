@@ -0,0 +1,334 @@
+use chrono::{Datelike, NaiveDate, Timelike};
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+use crate::models::{
+    Booking, DaySlots, FreeSlot, WEEKDAYS, WorkerAvailability, WorkerProfile, format_hhmm,
+};
+use crate::utils::time::{HOUR_MILLIS, MINUTE_MILLIS, add_millis, local_day_start, to_local};
+
+/// Bookings in these states hold the worker's time
+const ACTIVE_BOOKING_STATUSES: [&str; 3] = ["requested", "accepted", "in_progress"];
+/// Longest allowed booking, used to widen overlap lookups
+const MAX_BOOKING_MINUTES: i64 = 12 * 60;
+pub const MAX_SLOT_RANGE_DAYS: i64 = 31;
+
+pub struct AvailabilityService;
+
+impl AvailabilityService {
+    /// The worker's calendar, or the default one if they never set it
+    pub fn calendar(worker: &WorkerProfile) -> WorkerAvailability {
+        worker
+            .availability
+            .clone()
+            .unwrap_or_else(WorkerAvailability::default_schedule)
+    }
+
+    /// Bookable windows of a local day as minutes since midnight. Each working range
+    /// is split around breaks; holidays and days off have none.
+    fn working_windows(calendar: &WorkerAvailability, day: NaiveDate) -> Vec<(i32, i32)> {
+        if calendar.holidays.contains(&day.format("%Y-%m-%d").to_string()) {
+            return Vec::new();
+        }
+        let Some(schedule) = calendar.day(weekday_key(day)) else {
+            return Vec::new();
+        };
+
+        let breaks: Vec<(i32, i32)> = schedule.breaks.iter().filter_map(|b| b.minutes()).collect();
+        let mut windows = Vec::new();
+
+        for (start, end) in schedule.hours.iter().filter_map(|h| h.minutes()) {
+            let mut cursor = start;
+            let mut overlapping: Vec<&(i32, i32)> =
+                breaks.iter().filter(|(bs, be)| *bs < end && *be > start).collect();
+            overlapping.sort();
+
+            for (break_start, break_end) in overlapping {
+                if *break_start > cursor {
+                    windows.push((cursor, *break_start));
+                }
+                cursor = cursor.max(*break_end);
+            }
+            if cursor < end {
+                windows.push((cursor, end));
+            }
+        }
+
+        windows
+    }
+
+    /// Active bookings of a worker that overlap [from, to)
+    async fn bookings_between(
+        db: &Database,
+        worker_id: ObjectId,
+        from: DateTime,
+        to: DateTime,
+    ) -> Result<Vec<Booking>, String> {
+        let bookings: Vec<Booking> = db
+            .collection::<Booking>("bookings")
+            .find(
+                doc! {
+                    "worker_id": worker_id,
+                    "status": { "$in": ACTIVE_BOOKING_STATUSES.to_vec() },
+                    "scheduled_at": {
+                        "$gte": add_millis(from, -MAX_BOOKING_MINUTES * MINUTE_MILLIS),
+                        "$lt": to
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(bookings
+            .into_iter()
+            .filter(|b| booking_end(b) > from.timestamp_millis())
+            .collect())
+    }
+
+    /// Free slots of `duration` minutes for each local day in [from, to]
+    pub async fn free_slots(
+        db: &Database,
+        worker: &WorkerProfile,
+        from: NaiveDate,
+        to: NaiveDate,
+        duration: i32,
+    ) -> Result<Vec<DaySlots>, String> {
+        let worker_id = worker.id.ok_or("Worker missing ID")?;
+        let calendar = Self::calendar(worker);
+        let step = calendar.slot_minutes as i64 * MINUTE_MILLIS;
+        let length = duration as i64 * MINUTE_MILLIS;
+
+        let range_start = local_day_start(from);
+        let range_end = local_day_start(to.succ_opt().ok_or("Invalid date range")?);
+        let bookings = Self::bookings_between(db, worker_id, range_start, range_end).await?;
+        let now = DateTime::now().timestamp_millis();
+
+        let mut days = Vec::new();
+        for day in from.iter_days().take_while(|d| *d <= to) {
+            let day_start = local_day_start(day).timestamp_millis();
+            let day_end = day_start + 24 * HOUR_MILLIS;
+
+            let booked = bookings
+                .iter()
+                .filter(|b| (day_start..day_end).contains(&b.scheduled_at.timestamp_millis()))
+                .count() as i32;
+            let fully_booked = calendar.daily_capacity.is_some_and(|c| booked >= c);
+
+            let mut slots = Vec::new();
+            if !fully_booked {
+                for (window_start, window_end) in Self::working_windows(&calendar, day) {
+                    let window_end = day_start + window_end as i64 * MINUTE_MILLIS;
+                    let mut start = day_start + window_start as i64 * MINUTE_MILLIS;
+
+                    while start + length <= window_end {
+                        let end = start + length;
+                        let clashes = bookings
+                            .iter()
+                            .any(|b| b.scheduled_at.timestamp_millis() < end && booking_end(b) > start);
+
+                        if start > now && !clashes {
+                            slots.push(FreeSlot {
+                                start: to_local(DateTime::from_millis(start)).to_rfc3339(),
+                                end: to_local(DateTime::from_millis(end)).to_rfc3339(),
+                            });
+                        }
+                        start += step;
+                    }
+                }
+            }
+
+            days.push(DaySlots {
+                date: day.format("%Y-%m-%d").to_string(),
+                is_holiday: calendar.holidays.contains(&day.format("%Y-%m-%d").to_string()),
+                bookings: booked,
+                fully_booked,
+                slots,
+            });
+        }
+
+        Ok(days)
+    }
+
+    /// Why the worker can't take a booking of `duration` minutes at `start`, if they can't
+    pub async fn unavailable_reason(
+        db: &Database,
+        worker: &WorkerProfile,
+        start: DateTime,
+        duration: i32,
+    ) -> Result<Option<String>, String> {
+        let worker_id = worker.id.ok_or("Worker missing ID")?;
+        let calendar = Self::calendar(worker);
+
+        let local = to_local(start);
+        let day = local.date_naive();
+        let start_minute = (local.hour() * 60 + local.minute()) as i32;
+        let end_minute = start_minute + duration;
+
+        if calendar.holidays.contains(&day.format("%Y-%m-%d").to_string()) {
+            return Ok(Some("Worker is on holiday on that date".to_string()));
+        }
+
+        let fits = Self::working_windows(&calendar, day)
+            .iter()
+            .any(|(ws, we)| *ws <= start_minute && end_minute <= *we);
+        if !fits {
+            return Ok(Some("Requested time is outside the worker's working hours".to_string()));
+        }
+
+        let day_start = local_day_start(day);
+        let day_end = add_millis(day_start, 24 * HOUR_MILLIS);
+        let bookings = Self::bookings_between(db, worker_id, day_start, day_end).await?;
+
+        let end = start.timestamp_millis() + duration as i64 * MINUTE_MILLIS;
+        if bookings
+            .iter()
+            .any(|b| b.scheduled_at.timestamp_millis() < end && booking_end(b) > start.timestamp_millis())
+        {
+            return Ok(Some("Worker already has a booking at that time".to_string()));
+        }
+
+        let booked = bookings.iter().filter(|b| b.scheduled_at >= day_start).count() as i32;
+        if calendar.daily_capacity.is_some_and(|c| booked >= c) {
+            return Ok(Some("Worker is fully booked on that date".to_string()));
+        }
+
+        Ok(None)
+    }
+
+    /// Filter on `worker_profiles` matching workers free for `duration` minutes from `at`
+    /// by their calendar, existing bookings and daily capacity. Windows must end the
+    /// same local day.
+    pub async fn available_at_filter(
+        db: &Database,
+        at: DateTime,
+        duration: i32,
+    ) -> Result<Document, String> {
+        let local = to_local(at);
+        let day = local.date_naive();
+        let date = day.format("%Y-%m-%d").to_string();
+        let start_minute = (local.hour() * 60 + local.minute()) as i32;
+        let start_hm = format_hhmm(start_minute);
+        let end_hm = format_hhmm(start_minute + duration);
+
+        let mut calendar_match = vec![doc! {
+            "availability.holidays": { "$ne": date.as_str() },
+            "availability.weekly": {
+                "$elemMatch": {
+                    "day": weekday_key(day),
+                    "hours": { "$elemMatch": { "start": { "$lte": start_hm.as_str() }, "end": { "$gte": end_hm.as_str() } } },
+                    "breaks": {
+                        "$not": { "$elemMatch": { "start": { "$lt": end_hm.as_str() }, "end": { "$gt": start_hm.as_str() } } }
+                    }
+                }
+            }
+        }];
+
+        let default_fits = Self::working_windows(&WorkerAvailability::default_schedule(), day)
+            .iter()
+            .any(|(ws, we)| *ws <= start_minute && start_minute + duration <= *we);
+        if default_fits {
+            calendar_match.push(doc! { "availability": null });
+        }
+
+        // Workers with a clashing booking, plus how many bookings each has that day
+        let end = add_millis(at, duration as i64 * MINUTE_MILLIS);
+        let day_start = local_day_start(day);
+        let day_end = add_millis(day_start, 24 * HOUR_MILLIS);
+
+        let busy: Vec<Document> = db
+            .collection::<Booking>("bookings")
+            .aggregate(
+                vec![
+                    doc! {
+                        "$match": {
+                            "status": { "$in": ACTIVE_BOOKING_STATUSES.to_vec() },
+                            "scheduled_at": {
+                                "$gte": add_millis(day_start, -MAX_BOOKING_MINUTES * MINUTE_MILLIS),
+                                "$lt": day_end.max(end)
+                            }
+                        }
+                    },
+                    doc! {
+                        "$group": {
+                            "_id": "$worker_id",
+                            "clashes": {
+                                "$max": {
+                                    "$and": [
+                                        { "$lt": ["$scheduled_at", end] },
+                                        { "$gt": [
+                                            { "$add": ["$scheduled_at", { "$multiply": ["$duration_minutes", MINUTE_MILLIS] }] },
+                                            at
+                                        ] }
+                                    ]
+                                }
+                            },
+                            "booked": {
+                                "$sum": {
+                                    "$cond": [
+                                        { "$and": [
+                                            { "$gte": ["$scheduled_at", day_start] },
+                                            { "$lt": ["$scheduled_at", day_end] }
+                                        ] },
+                                        1,
+                                        0
+                                    ]
+                                }
+                            }
+                        }
+                    },
+                    doc! {
+                        "$lookup": {
+                            "from": "worker_profiles",
+                            "localField": "_id",
+                            "foreignField": "_id",
+                            "as": "profile"
+                        }
+                    },
+                    doc! { "$unwind": "$profile" },
+                    doc! {
+                        "$match": {
+                            "$or": [
+                                { "clashes": true },
+                                { "$expr": {
+                                    "$and": [
+                                        { "$gt": ["$profile.availability.daily_capacity", null] },
+                                        { "$gte": ["$booked", "$profile.availability.daily_capacity"] }
+                                    ]
+                                } }
+                            ]
+                        }
+                    },
+                    doc! { "$project": { "_id": 1 } },
+                ],
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let busy_ids: Vec<ObjectId> = busy.iter().filter_map(|d| d.get_object_id("_id").ok()).collect();
+
+        Ok(doc! {
+            "$and": [
+                { "$or": calendar_match },
+                { "_id": { "$nin": busy_ids } }
+            ]
+        })
+    }
+}
+
+/// Calendar key ("mon" .. "sun") of a date
+fn weekday_key(day: NaiveDate) -> &'static str {
+    WEEKDAYS[day.weekday().num_days_from_monday() as usize]
+}
+
+fn booking_end(booking: &Booking) -> i64 {
+    booking.scheduled_at.timestamp_millis() + booking.duration_minutes as i64 * MINUTE_MILLIS
+}
@@ -0,0 +1,176 @@
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::FindOneOptions;
+use mongodb::Database;
+
+use crate::models::{Subscription, SubscriptionType};
+use crate::services::EntitlementService;
+use crate::utils::time::DAY_MILLIS;
+
+pub struct BillingService;
+
+impl BillingService {
+    /// Next value of the invoice sequence shared by all subscriptions
+    pub async fn next_invoice_number(db: &Database) -> Result<i64, String> {
+        let max_invoice = db
+            .collection::<Subscription>("subscriptions")
+            .find_one(
+                doc! { "invoice_number": { "$exists": true } },
+                FindOneOptions::builder()
+                    .sort(doc! { "invoice_number": -1 })
+                    .build(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(max_invoice.and_then(|s| s.invoice_number).unwrap_or(0) + 1)
+    }
+
+    /// Price difference between two plans for the time left until `expires_at`,
+    /// rounded to the paisa. Prices are for a full `duration_days` period.
+    pub fn prorated_amount(
+        current_price: f64,
+        new_price: f64,
+        duration_days: i64,
+        expires_at: DateTime,
+        now: DateTime,
+    ) -> f64 {
+        let period = (duration_days * DAY_MILLIS).max(1);
+        let remaining = (expires_at.timestamp_millis() - now.timestamp_millis()).clamp(0, period);
+        let amount = (new_price - current_price) * remaining as f64 / period as f64;
+
+        (amount.max(0.0) * 100.0).round() / 100.0
+    }
+
+    /// The paid plan `paid_id` replaces any free trial the user still has running
+    pub async fn supersede_trials(
+        db: &Database,
+        user_id: ObjectId,
+        subscription_type: &SubscriptionType,
+        paid_id: ObjectId,
+    ) -> Result<(), String> {
+        db.collection::<Subscription>("subscriptions")
+            .update_many(
+                doc! {
+                    "_id": { "$ne": paid_id },
+                    "user_id": user_id,
+                    "subscription_type": subscription_type.as_str(),
+                    "status": "active",
+                    "is_trial": true
+                },
+                doc! { "$set": { "status": "superseded", "updated_at": DateTime::now() } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Copy an active subscription onto the user's flat fields and the matching profile.
+    /// `priority_listing` on the profile is what searches sort by.
+    pub async fn apply_subscription(db: &Database, sub: &Subscription) -> Result<(), String> {
+        let now = DateTime::now();
+        let priority_listing = EntitlementService::has_feature(db, sub.user_id, "priority_listing").await?;
+
+        match sub.subscription_type {
+            SubscriptionType::Worker => {
+                db.collection::<mongodb::bson::Document>("users")
+                    .update_one(
+                        doc! { "_id": sub.user_id },
+                        doc! {
+                            "$set": {
+                                "subscription_id": sub.id,
+                                "subscription_plan": &sub.plan_name,
+                                "subscription_expires_at": sub.expires_at,
+                                "updated_at": now
+                            }
+                        },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                db.collection::<mongodb::bson::Document>("worker_profiles")
+                    .update_one(
+                        doc! { "user_id": sub.user_id },
+                        doc! {
+                            "$set": {
+                                "subscription_plan": &sub.plan_name,
+                                "subscription_expires_at": sub.expires_at,
+                                "priority_listing": priority_listing,
+                                "updated_at": now
+                            }
+                        },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+            SubscriptionType::JobSeeker => {
+                db.collection::<mongodb::bson::Document>("job_seeker_profiles")
+                    .update_one(
+                        doc! { "user_id": sub.user_id },
+                        doc! {
+                            "$set": {
+                                "subscription_plan": &sub.plan_name,
+                                "subscription_expires_at": sub.expires_at,
+                                "priority_listing": priority_listing,
+                                "updated_at": now
+                            }
+                        },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove a lapsed subscription from the user's flat fields and profile
+    pub async fn clear_subscription(db: &Database, sub: &Subscription) -> Result<(), String> {
+        let now = DateTime::now();
+        // Another active plan may still grant it
+        let priority_listing = EntitlementService::has_feature(db, sub.user_id, "priority_listing").await?;
+        let profiles = match sub.subscription_type {
+            SubscriptionType::Worker => {
+                db.collection::<mongodb::bson::Document>("users")
+                    .update_one(
+                        doc! { "_id": sub.user_id, "subscription_id": sub.id },
+                        doc! {
+                            "$set": { "updated_at": now },
+                            "$unset": {
+                                "subscription_id": "",
+                                "subscription_plan": "",
+                                "subscription_expires_at": ""
+                            }
+                        },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                "worker_profiles"
+            }
+            SubscriptionType::JobSeeker => "job_seeker_profiles",
+        };
+
+        db.collection::<mongodb::bson::Document>(profiles)
+            .update_one(
+                doc! { "user_id": sub.user_id },
+                doc! {
+                    "$set": {
+                        "subscription_plan": "none",
+                        "priority_listing": priority_listing,
+                        "updated_at": now
+                    },
+                    "$unset": { "subscription_expires_at": "" }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,353 @@
+use log::{error, info};
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+use crate::models::{
+    Booking, BookingStatus, BookingStatusChange, CancellationPolicy, CompletionConfirmation,
+};
+use crate::routes::notification::create_user_notification;
+use crate::services::ReliabilityService;
+use crate::utils::time::{HOUR_MILLIS, format_datetime};
+
+pub struct BookingService;
+
+impl BookingService {
+    /// Insert a new booking request and notify the worker
+    pub async fn create(db: &Database, mut booking: Booking) -> Result<Booking, String> {
+        let res = db
+            .collection::<Booking>("bookings")
+            .insert_one(&booking, None)
+            .await
+            .map_err(|e| e.to_string())?;
+        booking.id = res.inserted_id.as_object_id();
+
+        Self::notify(db, &booking, booking.customer_id).await;
+        info!("Booking {:?} requested for worker {}", booking.id, booking.worker_id);
+
+        Ok(booking)
+    }
+
+    /// Move a booking from one of `from` to `to`, recording who did it. Returns `None`
+    /// when the booking was no longer in an allowed state (e.g. a concurrent update).
+    pub async fn transition(
+        db: &Database,
+        booking_id: ObjectId,
+        actor: ObjectId,
+        from: &[BookingStatus],
+        to: BookingStatus,
+        note: Option<String>,
+        mut extra: Document,
+    ) -> Result<Option<Booking>, String> {
+        let now = DateTime::now();
+
+        let change = BookingStatusChange {
+            status: to,
+            changed_by: actor,
+            note,
+            at: now,
+        };
+        let change = mongodb::bson::to_bson(&change).map_err(|e| e.to_string())?;
+
+        extra.insert("status", to.as_str());
+        extra.insert("updated_at", now);
+        match to {
+            BookingStatus::Accepted => {
+                extra.insert("accepted_at", now);
+            }
+            BookingStatus::InProgress => {
+                extra.insert("started_at", now);
+            }
+            BookingStatus::Completed => {
+                extra.insert("completed_at", now);
+            }
+            BookingStatus::Cancelled | BookingStatus::Declined => {
+                extra.insert("cancelled_at", now);
+                extra.insert("cancelled_by", actor);
+            }
+            BookingStatus::Requested | BookingStatus::NoShow => {}
+        }
+
+        let from: Vec<&str> = from.iter().map(|s| s.as_str()).collect();
+
+        let updated = db
+            .collection::<Booking>("bookings")
+            .find_one_and_update(
+                doc! { "_id": booking_id, "status": { "$in": from } },
+                doc! { "$set": extra, "$push": { "status_history": change } },
+                FindOneAndUpdateOptions::builder()
+                    .return_document(ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let Some(booking) = updated else {
+            return Ok(None);
+        };
+
+        if matches!(to, BookingStatus::Completed | BookingStatus::Cancelled | BookingStatus::NoShow) {
+            if let Err(e) = ReliabilityService::refresh_for_booking(db, &booking).await {
+                error!("Failed to refresh reliability for booking {:?}: {}", booking.id, e);
+            }
+
+            // Live tracking ends with the booking. The transition is already saved, so a
+            // failed cleanup is left to the retention TTL.
+            if let Err(e) = db
+                .collection::<Document>("booking_locations")
+                .delete_many(doc! { "booking_id": booking.id }, None)
+                .await
+            {
+                error!("Failed to clear locations for booking {:?}: {}", booking.id, e);
+            }
+        }
+
+        Self::notify(db, &booking, actor).await;
+
+        Ok(Some(booking))
+    }
+
+    /// Sign off a completed job, moving its confirmation from one of `from` to `to`.
+    /// Only confirmed jobs count towards the worker's completed total. Returns false
+    /// if the completion was not in an allowed state.
+    pub async fn confirm_completion(
+        db: &Database,
+        booking_id: ObjectId,
+        from: &[CompletionConfirmation],
+        to: CompletionConfirmation,
+    ) -> Result<bool, String> {
+        let now = DateTime::now();
+        let from: Vec<mongodb::bson::Bson> = from
+            .iter()
+            .map(mongodb::bson::to_bson)
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?;
+        let to_bson = mongodb::bson::to_bson(&to).map_err(|e| e.to_string())?;
+
+        let confirmed = db
+            .collection::<Booking>("bookings")
+            .find_one_and_update(
+                doc! {
+                    "_id": booking_id,
+                    "status": BookingStatus::Completed.as_str(),
+                    "completion.confirmation": { "$in": from }
+                },
+                doc! {
+                    "$set": {
+                        "completion.confirmation": to_bson,
+                        "completion.confirmed_at": now,
+                        "updated_at": now
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let Some(booking) = confirmed else {
+            return Ok(false);
+        };
+
+        db.collection::<Document>("worker_profiles")
+            .update_one(
+                doc! { "_id": booking.worker_id },
+                doc! { "$inc": { "total_jobs_completed": 1 }, "$set": { "updated_at": now } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if let Err(e) = ReliabilityService::refresh_worker(db, booking.worker_id, booking.worker_user_id).await {
+            error!("Failed to refresh reliability for booking {:?}: {}", booking.id, e);
+        }
+
+        let body = match to {
+            CompletionConfirmation::AutoConfirmed => format!(
+                "Your {} job was confirmed automatically and now counts towards your completed jobs.",
+                booking.subcategory
+            ),
+            _ => format!("The customer confirmed your {} job. Well done!", booking.subcategory),
+        };
+        let _ = create_user_notification(
+            db,
+            booking.worker_user_id,
+            "booking_completion_confirmed".to_string(),
+            "Job Confirmed".to_string(),
+            body,
+            booking.id,
+        )
+        .await;
+
+        Ok(true)
+    }
+
+    /// Scheduler job: confirm completed jobs the customer didn't respond to in time
+    pub async fn auto_confirm_completions(db: &Database) -> Result<(), String> {
+        let due: Vec<Booking> = db
+            .collection::<Booking>("bookings")
+            .find(
+                doc! {
+                    "status": BookingStatus::Completed.as_str(),
+                    "completion.confirmation": "pending",
+                    "completion.confirm_by": { "$lte": DateTime::now() }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for booking in due {
+            let Some(booking_id) = booking.id else { continue };
+            if Self::confirm_completion(
+                db,
+                booking_id,
+                &[CompletionConfirmation::Pending],
+                CompletionConfirmation::AutoConfirmed,
+            )
+            .await?
+            {
+                info!("Booking {} completion auto-confirmed", booking_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The booking category's cancellation policy, or the configured defaults
+    pub async fn cancellation_policy(db: &Database, category: Option<&str>) -> Result<CancellationPolicy, String> {
+        if let Some(category) = category {
+            let policy = db
+                .collection::<CancellationPolicy>("cancellation_policies")
+                .find_one(doc! { "category": category }, None)
+                .await
+                .map_err(|e| e.to_string())?;
+            if let Some(policy) = policy {
+                return Ok(policy);
+            }
+        }
+
+        Ok(CancellationPolicy {
+            category: category.unwrap_or("default").to_string(),
+            customer_window_hours: crate::config::Config::cancellation_window_hours(),
+            worker_window_hours: crate::config::Config::worker_cancellation_window_hours(),
+            no_show_grace_minutes: crate::config::Config::no_show_grace_minutes(),
+        })
+    }
+
+    /// Whether cancelling now, as the customer or the worker, falls inside the
+    /// policy window. Only accepted bookings can be cancelled late.
+    pub fn is_late_cancellation(booking: &Booking, policy: &CancellationPolicy, by_customer: bool) -> bool {
+        if booking.status != BookingStatus::Accepted {
+            return false;
+        }
+
+        let window_hours = if by_customer {
+            policy.customer_window_hours
+        } else {
+            policy.worker_window_hours
+        };
+        booking.scheduled_at.timestamp_millis() - DateTime::now().timestamp_millis() < window_hours * HOUR_MILLIS
+    }
+
+    /// Tell the other party about the booking's current status
+    async fn notify(db: &Database, booking: &Booking, actor: ObjectId) {
+        let when = format_datetime(booking.scheduled_at);
+        let reason = booking
+            .cancellation_reason
+            .as_deref()
+            .map(|r| format!(" Reason: {}", r))
+            .unwrap_or_default();
+
+        let (recipient, notification_type, title, body) = match booking.status {
+            BookingStatus::Requested => (
+                booking.worker_user_id,
+                "booking_requested",
+                "New Booking Request",
+                format!("You have a new {} booking request for {}.", booking.subcategory, when),
+            ),
+            BookingStatus::Accepted => (
+                booking.customer_id,
+                "booking_accepted",
+                "Booking Accepted",
+                format!("Your {} booking for {} has been accepted.", booking.subcategory, when),
+            ),
+            BookingStatus::Declined => (
+                booking.customer_id,
+                "booking_declined",
+                "Booking Declined",
+                format!(
+                    "Your {} booking for {} was declined.{}",
+                    booking.subcategory, when, reason
+                ),
+            ),
+            BookingStatus::InProgress => (
+                booking.customer_id,
+                "booking_in_progress",
+                "Work Started",
+                format!("Work on your {} booking has started.", booking.subcategory),
+            ),
+            BookingStatus::Completed => (
+                booking.customer_id,
+                "booking_completed",
+                "Booking Completed",
+                match booking.completion {
+                    Some(ref completion) => format!(
+                        "Your {} booking has been marked complete. Check the photos and confirm, or report a problem, by {}.",
+                        booking.subcategory,
+                        format_datetime(completion.confirm_by)
+                    ),
+                    None => format!(
+                        "Your {} booking has been completed. Rate your experience!",
+                        booking.subcategory
+                    ),
+                },
+            ),
+            BookingStatus::Cancelled => {
+                let recipient = if actor == booking.customer_id {
+                    booking.worker_user_id
+                } else {
+                    booking.customer_id
+                };
+                (
+                    recipient,
+                    "booking_cancelled",
+                    "Booking Cancelled",
+                    format!(
+                        "The {} booking for {} has been cancelled.{}",
+                        booking.subcategory, when, reason
+                    ),
+                )
+            }
+            BookingStatus::NoShow => {
+                let recipient = if actor == booking.customer_id {
+                    booking.worker_user_id
+                } else {
+                    booking.customer_id
+                };
+                (
+                    recipient,
+                    "booking_no_show",
+                    "No-Show Reported",
+                    format!(
+                        "You were reported as a no-show for the {} booking on {}. If this is wrong, raise a dispute.",
+                        booking.subcategory, when
+                    ),
+                )
+            }
+        };
+
+        let _ = create_user_notification(
+            db,
+            recipient,
+            notification_type.to_string(),
+            title.to_string(),
+            body,
+            booking.id,
+        )
+        .await;
+    }
+}
@@ -0,0 +1,325 @@
+use log::{info, warn};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+use crate::config::Config;
+use crate::models::{
+    Booking, Dispute, ResolutionAction, ResolutionActionDto, ResolutionActionKind, Subscription,
+    WorkerProfile,
+};
+use crate::routes::notification::create_user_notification;
+use crate::services::{AuditService, payment_gateway};
+use crate::utils::time::{DAY_MILLIS, HOUR_MILLIS, add_millis, format_date};
+
+/// Longest suspension a single resolution can impose
+const MAX_SUSPENSION_DAYS: i64 = 365;
+
+pub struct DisputeService;
+
+impl DisputeService {
+    /// First-response and resolution deadlines for a dispute filed at `filed_at`
+    pub fn sla_deadlines(filed_at: DateTime) -> (DateTime, DateTime) {
+        (
+            add_millis(filed_at, Config::dispute_response_hours() * HOUR_MILLIS),
+            add_millis(filed_at, Config::dispute_resolution_hours() * HOUR_MILLIS),
+        )
+    }
+
+    /// Save a new dispute and tell the other party
+    pub async fn open(db: &Database, mut dispute: Dispute) -> Result<Dispute, String> {
+        let res = db
+            .collection::<Dispute>("disputes")
+            .insert_one(&dispute, None)
+            .await
+            .map_err(|e| e.to_string())?;
+        dispute.id = res.inserted_id.as_object_id();
+
+        if let Some(other) = dispute.against_user_id {
+            let _ = create_user_notification(
+                db,
+                other,
+                "dispute_opened".to_string(),
+                "Dispute Raised".to_string(),
+                format!(
+                    "A dispute was raised on one of your bookings: \"{}\". Support will be in touch.",
+                    dispute.subject
+                ),
+                dispute.id,
+            )
+            .await;
+        }
+
+        info!("Dispute {:?} opened by {}", dispute.id, dispute.raised_by);
+
+        Ok(dispute)
+    }
+
+    /// Scheduler job: flag open disputes that missed their first-response or
+    /// resolution deadline
+    pub async fn check_sla(db: &Database) -> Result<(), String> {
+        let now = DateTime::now();
+
+        let overdue: Vec<Dispute> = db
+            .collection::<Dispute>("disputes")
+            .find(
+                doc! {
+                    "status": { "$in": ["open", "in_review"] },
+                    "sla_breached": false,
+                    "$or": [
+                        { "first_responded_at": null, "first_response_due_at": { "$lte": now } },
+                        { "resolution_due_at": { "$lte": now } }
+                    ]
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for dispute in overdue {
+            db.collection::<Dispute>("disputes")
+                .update_one(
+                    doc! { "_id": dispute.id },
+                    doc! { "$set": { "sla_breached": true, "updated_at": now } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+
+            let missed = if dispute.first_responded_at.is_none() && dispute.first_response_due_at <= now {
+                "first_response"
+            } else {
+                "resolution"
+            };
+            warn!("Dispute {:?} breached its {} SLA", dispute.id, missed);
+
+            AuditService::record(
+                db,
+                "system",
+                None,
+                "dispute.sla_breached",
+                "dispute",
+                dispute.id,
+                serde_json::json!({
+                    "missed": missed,
+                    "assigned_admin_id": dispute.assigned_admin_id.map(|id| id.to_hex())
+                }),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Carry out one resolution action and record it in the audit log
+    pub async fn apply_action(
+        db: &Database,
+        dispute: &Dispute,
+        action: &ResolutionActionDto,
+        admin_id: ObjectId,
+    ) -> Result<ResolutionAction, String> {
+        let mut applied = ResolutionAction {
+            kind: action.kind,
+            amount: None,
+            refund_id: None,
+            suspension_days: None,
+            audit_log_id: None,
+        };
+
+        let (audit_action, details) = match action.kind {
+            ResolutionActionKind::Refund => {
+                let amount = action
+                    .amount
+                    .filter(|a| a.is_finite() && *a > 0.0)
+                    .ok_or("A refund needs a positive amount")?;
+                applied.amount = Some(amount);
+                applied.refund_id = Self::refund(db, dispute, amount).await?;
+
+                let _ = create_user_notification(
+                    db,
+                    dispute.raised_by,
+                    "dispute_refund".to_string(),
+                    "Refund Issued".to_string(),
+                    format!("A refund of ₹{:.2} has been issued for your dispute.", amount),
+                    dispute.id,
+                )
+                .await;
+
+                (
+                    "dispute.refund",
+                    serde_json::json!({ "amount": amount, "refund_id": applied.refund_id }),
+                )
+            }
+            ResolutionActionKind::Warning => {
+                let user_id = dispute
+                    .against_user_id
+                    .ok_or("This dispute has no other party to warn")?;
+
+                let _ = create_user_notification(
+                    db,
+                    user_id,
+                    "account_warning".to_string(),
+                    "Account Warning".to_string(),
+                    "Following a dispute review, your account has received a warning. Repeated issues may lead to suspension.".to_string(),
+                    dispute.id,
+                )
+                .await;
+
+                ("dispute.warning", serde_json::json!({ "user_id": user_id.to_hex() }))
+            }
+            ResolutionActionKind::WorkerSuspension => {
+                let days = action
+                    .suspension_days
+                    .filter(|d| (1..=MAX_SUSPENSION_DAYS).contains(d))
+                    .ok_or(format!("Suspension must be between 1 and {} days", MAX_SUSPENSION_DAYS))?;
+                let booking = Self::booking(db, dispute)
+                    .await?
+                    .ok_or("Only booking disputes can suspend a worker")?;
+                applied.suspension_days = Some(days);
+
+                let until = add_millis(DateTime::now(), days * DAY_MILLIS);
+                db.collection::<WorkerProfile>("worker_profiles")
+                    .update_one(
+                        doc! { "_id": booking.worker_id },
+                        doc! {
+                            "$set": {
+                                "is_available": false,
+                                "suspended_until": until,
+                                "updated_at": DateTime::now()
+                            }
+                        },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                let _ = create_user_notification(
+                    db,
+                    booking.worker_user_id,
+                    "worker_suspended".to_string(),
+                    "Profile Suspended".to_string(),
+                    format!(
+                        "Following a dispute review, your worker profile is suspended until {}.",
+                        format_date(until)
+                    ),
+                    dispute.id,
+                )
+                .await;
+
+                (
+                    "dispute.worker_suspension",
+                    serde_json::json!({
+                        "worker_id": booking.worker_id.to_hex(),
+                        "days": days,
+                        "suspended_until": until.try_to_rfc3339_string().unwrap_or_default()
+                    }),
+                )
+            }
+        };
+
+        applied.audit_log_id = AuditService::record(
+            db,
+            "admin",
+            Some(admin_id),
+            audit_action,
+            "dispute",
+            dispute.id,
+            details,
+        )
+        .await?;
+
+        info!("Dispute {:?}: applied {}", dispute.id, audit_action);
+
+        Ok(applied)
+    }
+
+    /// Subscription payments are refunded through the gateway. Booking payments
+    /// are settled outside the app, so those refunds are only recorded.
+    async fn refund(db: &Database, dispute: &Dispute, amount: f64) -> Result<Option<String>, String> {
+        let Some(subscription_id) = dispute.subscription_id else {
+            return Ok(None);
+        };
+
+        let subscription = db
+            .collection::<Subscription>("subscriptions")
+            .find_one(doc! { "_id": subscription_id }, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Subscription not found")?;
+
+        let refundable = subscription.price - Self::refunded(db, subscription_id, dispute.id).await?;
+        if amount > refundable {
+            return Err(format!(
+                "Refund cannot exceed the ₹{:.2} left of the ₹{:.2} paid",
+                refundable.max(0.0),
+                subscription.price
+            ));
+        }
+        let payment_id = subscription
+            .payment_id
+            .as_deref()
+            .ok_or("The subscription has no payment to refund")?;
+
+        payment_gateway().refund_payment(payment_id, amount).await.map(Some)
+    }
+
+    /// Total already refunded for a subscription by other disputes
+    async fn refunded(
+        db: &Database,
+        subscription_id: ObjectId,
+        exclude_dispute_id: Option<ObjectId>,
+    ) -> Result<f64, String> {
+        let totals: Vec<mongodb::bson::Document> = db
+            .collection::<Dispute>("disputes")
+            .aggregate(
+                vec![
+                    doc! { "$match": { "subscription_id": subscription_id, "_id": { "$ne": exclude_dispute_id } } },
+                    doc! { "$unwind": "$resolution.actions" },
+                    doc! { "$match": { "resolution.actions.kind": "refund" } },
+                    doc! { "$group": { "_id": null, "total": { "$sum": "$resolution.actions.amount" } } },
+                ],
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(totals
+            .first()
+            .and_then(|t| t.get_f64("total").ok())
+            .unwrap_or(0.0))
+    }
+
+    async fn booking(db: &Database, dispute: &Dispute) -> Result<Option<Booking>, String> {
+        let Some(booking_id) = dispute.booking_id else {
+            return Ok(None);
+        };
+
+        db.collection::<Booking>("bookings")
+            .find_one(doc! { "_id": booking_id }, None)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Tell both parties how the dispute was closed
+    pub async fn notify_closed(db: &Database, dispute: &Dispute, title: &str, summary: &str) {
+        let parties = std::iter::once(dispute.raised_by).chain(dispute.against_user_id);
+
+        for user_id in parties {
+            let _ = create_user_notification(
+                db,
+                user_id,
+                "dispute_closed".to_string(),
+                title.to_string(),
+                format!("\"{}\": {}", dispute.subject, summary),
+                dispute.id,
+            )
+            .await;
+        }
+    }
+}
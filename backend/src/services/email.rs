@@ -1,10 +1,12 @@
 use lettre::{
     Message, SmtpTransport, Transport,
-    message::{header::ContentType, Mailbox},
+    message::{header::ContentType, Attachment, Mailbox, MultiPart, SinglePart},
     transport::smtp::authentication::Credentials,
 };
 use log::{info, error, warn};
 
+use crate::models::JobPost;
+
 pub struct EmailService;
 
 impl EmailService {
@@ -164,4 +166,168 @@ impl EmailService {
         mailer.send(&email_message)?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    pub async fn send_payment_link_email(email: &str, name: &str, plan: &str, amount: f64, link: &str) -> bool {
+        let display_name = if name.is_empty() { "there" } else { name };
+
+        let email_body = format!(
+            r#"
+            <!DOCTYPE html>
+            <html>
+            <body>
+                <h2>Complete your Mento Services subscription</h2>
+                <p>Hi {},</p>
+                <p>Your <strong>{}</strong> plan is ready. Pay <strong>₹{:.2}</strong> securely using the link below to activate it.</p>
+                <p><a href="{}">{}</a></p>
+                <p>If you did not request this, please ignore this email.</p>
+                <p>Best regards,<br><strong>Mento Services Team</strong></p>
+            </body>
+            </html>
+            "#,
+            display_name, plan, amount, link, link
+        );
+
+        match Self::send_html(email, "Your Mento Services payment link", email_body).await {
+            Ok(_) => {
+                info!("Payment link email sent to {}", email);
+                true
+            }
+            Err(e) => {
+                error!("Failed to send payment link email to {}: {}", email, e);
+                false
+            }
+        }
+    }
+
+    /// Jobs matching a saved search. `total` counts matches beyond those listed.
+    pub async fn send_job_alert_email(
+        email: &str,
+        search_name: &str,
+        jobs: &[JobPost],
+        total: usize,
+        unsubscribe_url: &str,
+    ) -> bool {
+        let base_url = crate::config::Config::app_base_url();
+        let rows: String = jobs
+            .iter()
+            .map(|job| {
+                format!(
+                    r#"<li><a href="{}/jobs/{}"><strong>{}</strong></a> at {}{}</li>"#,
+                    base_url,
+                    job.id.map(|id| id.to_hex()).unwrap_or_default(),
+                    job.title,
+                    job.company_name,
+                    job.location.as_deref().map(|l| format!(" &middot; {}", l)).unwrap_or_default()
+                )
+            })
+            .collect();
+        let more = if total > jobs.len() {
+            format!("<p>And {} more in the app.</p>", total - jobs.len())
+        } else {
+            String::new()
+        };
+
+        let email_body = format!(
+            r#"
+            <!DOCTYPE html>
+            <html>
+            <body>
+                <h2>New jobs for "{}"</h2>
+                <ul>{}</ul>
+                {}
+                <p>Best regards,<br><strong>Mento Services Team</strong></p>
+                <p style="font-size: 12px; color: #888;">You are receiving this because of a saved search. <a href="{}">Unsubscribe from these emails</a>.</p>
+            </body>
+            </html>
+            "#,
+            search_name, rows, more, unsubscribe_url
+        );
+        let subject = format!("{} new job{} for \"{}\"", total, if total == 1 { "" } else { "s" }, search_name);
+
+        match Self::send_html(email, &subject, email_body).await {
+            Ok(_) => {
+                info!("Job alert email sent to {}", email);
+                true
+            }
+            Err(e) => {
+                error!("Failed to send job alert email to {}: {}", email, e);
+                false
+            }
+        }
+    }
+
+    /// Email with a calendar invite attached, e.g. for an interview. `method` must
+    /// match the METHOD inside the `.ics` ("REQUEST" or "CANCEL").
+    pub async fn send_calendar_invite(email: &str, subject: &str, body: String, ics: String, method: &str) -> bool {
+        match Self::try_send_calendar_invite(email, subject, body, ics, method).await {
+            Ok(_) => {
+                info!("Calendar invite sent to {}", email);
+                true
+            }
+            Err(e) => {
+                error!("Failed to send calendar invite to {}: {}", email, e);
+                false
+            }
+        }
+    }
+
+    async fn try_send_calendar_invite(
+        email: &str,
+        subject: &str,
+        body: String,
+        ics: String,
+        method: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (from_mailbox, mailer) = Self::smtp()?;
+        let to_mailbox: Mailbox = email.parse()?;
+
+        let calendar_type = ContentType::parse(&format!("text/calendar; charset=UTF-8; method={}", method))?;
+        let email_message = Message::builder()
+            .from(from_mailbox)
+            .to(to_mailbox)
+            .subject(subject)
+            .multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::html(body))
+                    .singlepart(Attachment::new("invite.ics".to_string()).body(ics, calendar_type)),
+            )?;
+
+        mailer.send(&email_message)?;
+        Ok(())
+    }
+
+    /// Send an HTML email with the configured SMTP account
+    async fn send_html(email: &str, subject: &str, body: String) -> Result<(), Box<dyn std::error::Error>> {
+        let (from_mailbox, mailer) = Self::smtp()?;
+        let to_mailbox: Mailbox = email.parse()?;
+
+        let email_message = Message::builder()
+            .from(from_mailbox)
+            .to(to_mailbox)
+            .subject(subject)
+            .header(ContentType::TEXT_HTML)
+            .body(body)?;
+
+        mailer.send(&email_message)?;
+        Ok(())
+    }
+
+    /// Sender address and SMTP transport, if email is configured
+    fn smtp() -> Result<(Mailbox, SmtpTransport), Box<dyn std::error::Error>> {
+        let mail_user = crate::config::Config::mail_user();
+        let mail_password = crate::config::Config::mail_password();
+
+        if mail_user.is_empty() || mail_password.is_empty() {
+            warn!("Email credentials not configured. Skipping email send.");
+            return Err("Email not configured".into());
+        }
+
+        let from_mailbox: Mailbox = crate::config::Config::mail_from().parse()?;
+        let creds = Credentials::new(mail_user, mail_password);
+        let mailer = SmtpTransport::relay(&crate::config::Config::mail_host())?
+            .credentials(creds)
+            .build();
+
+        Ok((from_mailbox, mailer))
+    }
+}
@@ -0,0 +1,307 @@
+use log::info;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument, UpdateOptions};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+use crate::db::is_duplicate_key;
+use crate::models::{ContactReveal, EntitlementUsage, Feature, FeatureLimit, Subscription, find_plan};
+use crate::routes::notification::create_user_notification;
+use crate::services::BillingService;
+use crate::utils::ApiError;
+
+pub enum EntitlementError {
+    NotEntitled,
+    LimitReached(i64),
+    Database(String),
+}
+
+impl From<EntitlementError> for ApiError {
+    fn from(e: EntitlementError) -> Self {
+        match e {
+            EntitlementError::NotEntitled => {
+                ApiError::forbidden("Your current plan does not include this feature")
+            }
+            EntitlementError::LimitReached(limit) => ApiError::forbidden(format!(
+                "You have used all {} included in your plan for this period",
+                limit
+            )),
+            EntitlementError::Database(e) => ApiError::internal_error(format!("Database error: {}", e)),
+        }
+    }
+}
+
+pub struct EntitlementService;
+
+impl EntitlementService {
+    /// Features granted by all of the user's active subscriptions (trials included)
+    pub async fn features_for(db: &Database, user_id: ObjectId) -> Result<Vec<Feature>, String> {
+        let subscriptions: Vec<Subscription> = db
+            .collection::<Subscription>("subscriptions")
+            .find(doc! { "user_id": user_id, "status": "active" }, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut features: Vec<Feature> = Vec::new();
+        for sub in subscriptions {
+            let Some(plan) = find_plan(&sub.subscription_type, &sub.plan_name) else {
+                continue;
+            };
+
+            for feature in plan.features.iter().filter_map(|spec| Feature::parse(spec)) {
+                match features.iter().position(|f| f.key == feature.key) {
+                    Some(pos) => {
+                        let existing = features.remove(pos);
+                        features.push(existing.merge(feature));
+                    }
+                    None => features.push(feature),
+                }
+            }
+        }
+
+        Ok(features)
+    }
+
+    pub async fn feature(db: &Database, user_id: ObjectId, key: &str) -> Result<Option<Feature>, String> {
+        Ok(Self::features_for(db, user_id)
+            .await?
+            .into_iter()
+            .find(|f| f.key == key))
+    }
+
+    pub async fn has_feature(db: &Database, user_id: ObjectId, key: &str) -> Result<bool, String> {
+        Ok(Self::feature(db, user_id, key).await?.is_some())
+    }
+
+    /// Uses of a metered feature in the current period
+    pub async fn usage(db: &Database, user_id: ObjectId, feature: &Feature) -> Result<i64, String> {
+        let FeatureLimit::Metered { period, .. } = feature.limit else {
+            return Ok(0);
+        };
+
+        let usage = db
+            .collection::<EntitlementUsage>("entitlement_usage")
+            .find_one(
+                doc! {
+                    "user_id": user_id,
+                    "feature": &feature.key,
+                    "period": period.current_key()
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(usage.map(|u| u.count).unwrap_or(0))
+    }
+
+    /// Fail unless the user's plans include `key`
+    pub async fn require(db: &Database, user_id: ObjectId, key: &str) -> Result<Feature, EntitlementError> {
+        Self::feature(db, user_id, key)
+            .await
+            .map_err(EntitlementError::Database)?
+            .ok_or(EntitlementError::NotEntitled)
+    }
+
+    /// Record one use of `key`. Returns the remaining quota, or `None` when unlimited.
+    pub async fn consume(db: &Database, user_id: ObjectId, key: &str) -> Result<Option<i64>, EntitlementError> {
+        let feature = Self::require(db, user_id, key).await?;
+
+        let FeatureLimit::Metered { limit, period } = feature.limit else {
+            return Ok(None);
+        };
+
+        let usage = db.collection::<EntitlementUsage>("entitlement_usage");
+        let period_key = period.current_key();
+        let now = DateTime::now();
+
+        usage
+            .update_one(
+                doc! { "user_id": user_id, "feature": key, "period": &period_key },
+                doc! { "$setOnInsert": { "count": 0_i64, "updated_at": now } },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map_err(|e| EntitlementError::Database(e.to_string()))?;
+
+        // Only increments while under the limit, so concurrent requests cannot overshoot
+        let updated = usage
+            .find_one_and_update(
+                doc! {
+                    "user_id": user_id,
+                    "feature": key,
+                    "period": &period_key,
+                    "count": { "$lt": limit }
+                },
+                doc! { "$inc": { "count": 1_i64 }, "$set": { "updated_at": now } },
+                FindOneAndUpdateOptions::builder()
+                    .return_document(ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|e| EntitlementError::Database(e.to_string()))?;
+
+        match updated {
+            Some(u) => Ok(Some(limit - u.count)),
+            None => Err(EntitlementError::LimitReached(limit)),
+        }
+    }
+
+    /// Give back one use of `key`, e.g. when the metered action failed afterwards
+    pub async fn release(db: &Database, user_id: ObjectId, key: &str) -> Result<(), String> {
+        let Some(feature) = Self::feature(db, user_id, key).await? else {
+            return Ok(());
+        };
+        let FeatureLimit::Metered { period, .. } = feature.limit else {
+            return Ok(());
+        };
+
+        db.collection::<EntitlementUsage>("entitlement_usage")
+            .update_one(
+                doc! {
+                    "user_id": user_id,
+                    "feature": key,
+                    "period": period.current_key(),
+                    "count": { "$gt": 0 }
+                },
+                doc! { "$inc": { "count": -1_i64 }, "$set": { "updated_at": DateTime::now() } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Whether either user has revealed the other's contact details
+    pub async fn has_revealed(db: &Database, a: ObjectId, b: ObjectId) -> Result<bool, String> {
+        let reveal = db
+            .collection::<ContactReveal>("contact_reveals")
+            .find_one(
+                doc! {
+                    "$or": [
+                        { "user_id": a, "target_user_id": b },
+                        { "user_id": b, "target_user_id": a }
+                    ]
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(reveal.is_some())
+    }
+
+    /// ID of `user_id`'s reveal of `target_user_id`'s contact details, if any
+    pub async fn find_reveal(
+        db: &Database,
+        user_id: ObjectId,
+        target_user_id: ObjectId,
+    ) -> Result<Option<ObjectId>, String> {
+        let reveal = db
+            .collection::<ContactReveal>("contact_reveals")
+            .find_one(doc! { "user_id": user_id, "target_user_id": target_user_id }, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(reveal.and_then(|r| r.id))
+    }
+
+    /// Let `user_id` see `target_user_id`'s contact details. The first reveal of a
+    /// contact uses one `contact_reveals`; seeing it again is free. Returns the
+    /// reveal's ID, which the user can later review the worker against.
+    pub async fn reveal_contact(
+        db: &Database,
+        user_id: ObjectId,
+        target_user_id: ObjectId,
+    ) -> Result<ObjectId, EntitlementError> {
+        if let Some(id) = Self::find_reveal(db, user_id, target_user_id)
+            .await
+            .map_err(EntitlementError::Database)?
+        {
+            return Ok(id);
+        }
+
+        Self::consume(db, user_id, "contact_reveals").await?;
+
+        let reveal = ContactReveal {
+            id: None,
+            user_id,
+            target_user_id,
+            created_at: DateTime::now(),
+        };
+        match db.collection::<ContactReveal>("contact_reveals").insert_one(&reveal, None).await {
+            Ok(res) => res
+                .inserted_id
+                .as_object_id()
+                .ok_or_else(|| EntitlementError::Database("Reveal missing ID".to_string())),
+            Err(e) => {
+                // Not recorded by this request, so don't charge for it. A duplicate key
+                // means a concurrent request revealed the same contact.
+                let _ = Self::release(db, user_id, "contact_reveals").await;
+                if is_duplicate_key(&e) {
+                    Self::find_reveal(db, user_id, target_user_id)
+                        .await
+                        .map_err(EntitlementError::Database)?
+                        .ok_or_else(|| EntitlementError::Database("Reveal not found".to_string()))
+                } else {
+                    Err(EntitlementError::Database(e.to_string()))
+                }
+            }
+        }
+    }
+
+    /// Scheduler job: end free trials whose period is over
+    pub async fn expire_trials(db: &Database) -> Result<(), String> {
+        let now = DateTime::now();
+
+        let ended: Vec<Subscription> = db
+            .collection::<Subscription>("subscriptions")
+            .find(
+                doc! {
+                    "status": "active",
+                    "is_trial": true,
+                    "expires_at": { "$lte": now }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for sub in ended {
+            db.collection::<Subscription>("subscriptions")
+                .update_one(
+                    doc! { "_id": sub.id, "status": "active" },
+                    doc! { "$set": { "status": "expired", "updated_at": now } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+
+            BillingService::clear_subscription(db, &sub).await?;
+
+            let _ = create_user_notification(
+                db,
+                sub.user_id,
+                "trial_ended".to_string(),
+                "Free Trial Ended".to_string(),
+                format!(
+                    "Your free trial of the {} plan has ended. Subscribe to keep your benefits.",
+                    sub.plan_name
+                ),
+                sub.id,
+            )
+            .await;
+
+            info!("Expired trial subscription {:?}", sub.id);
+        }
+
+        Ok(())
+    }
+}
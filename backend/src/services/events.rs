@@ -0,0 +1,45 @@
+use mongodb::bson::oid::ObjectId;
+use rocket::tokio::sync::broadcast;
+
+/// Events buffered per subscriber before slow ones start missing some
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// An event for the real-time stream, delivered to the listed users only
+#[derive(Debug, Clone)]
+pub struct HubEvent {
+    pub recipients: Vec<ObjectId>,
+    pub event: String, // SSE event name, e.g. "message"
+    pub data: serde_json::Value,
+}
+
+/// In-process fan-out of real-time events to connected SSE clients (managed state).
+/// Events are not persisted; clients catch up by polling after reconnecting.
+pub struct EventHub {
+    sender: broadcast::Sender<HubEvent>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        EventHub { sender }
+    }
+
+    pub fn publish(&self, recipients: Vec<ObjectId>, event: &str, data: serde_json::Value) {
+        // No receivers just means nobody is connected right now
+        let _ = self.sender.send(HubEvent {
+            recipients,
+            event: event.to_string(),
+            data,
+        });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<HubEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,203 @@
+use log::{error, info, warn};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+use crate::config::Config;
+use crate::models::{Interview, InterviewMode, InterviewSlot, JobPost, User};
+use crate::routes::notification::create_user_notification;
+use crate::services::EmailService;
+use crate::utils::ics::{CalendarEvent, IcsMethod};
+use crate::utils::time::{HOUR_MILLIS, MINUTE_MILLIS, add_millis, format_datetime};
+
+pub struct InterviewService;
+
+impl InterviewService {
+    async fn user_email(db: &Database, user_id: ObjectId) -> Result<Option<String>, String> {
+        Ok(db
+            .collection::<User>("users")
+            .find_one(doc! { "_id": user_id }, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .and_then(|user| user.email)
+            .filter(|email| !email.trim().is_empty()))
+    }
+
+    fn mode_label(mode: InterviewMode) -> &'static str {
+        match mode {
+            InterviewMode::InPerson => "In person",
+            InterviewMode::Phone => "Phone",
+            InterviewMode::Video => "Video call",
+        }
+    }
+
+    /// Email both parties an `.ics` for the interview's scheduled slot: an invite
+    /// when it is booked, or a cancellation of that slot. Parties without an
+    /// email address are skipped.
+    pub async fn send_invites(db: &Database, interview: &Interview, slot: &InterviewSlot, method: IcsMethod) {
+        if let Err(e) = Self::try_send_invites(db, interview, slot, method).await {
+            error!("Failed to send interview emails for {:?}: {}", interview.id, e);
+        }
+    }
+
+    async fn try_send_invites(
+        db: &Database,
+        interview: &Interview,
+        slot: &InterviewSlot,
+        method: IcsMethod,
+    ) -> Result<(), String> {
+        let job = db
+            .collection::<JobPost>("jobs")
+            .find_one(doc! { "_id": interview.job_id }, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Job not found")?;
+        let interview_id = interview.id.ok_or("Interview missing ID")?;
+
+        // The job's HR contact takes the invite if the poster has no email
+        let poster_email = match Self::user_email(db, interview.poster_user_id).await? {
+            Some(email) => Some(email),
+            None => job.hr_email.clone().filter(|email| !email.trim().is_empty()),
+        };
+        let seeker_email = Self::user_email(db, interview.seeker_user_id).await?;
+        let organizer = poster_email.clone().unwrap_or_else(Config::mail_user);
+
+        let uid = format!("interview-{}-{}@mentoservices.com", interview_id.to_hex(), interview.round);
+        let summary = format!("Interview: {} at {}", job.title, job.company_name);
+        let description = format!(
+            "{} interview for {} at {}.{}",
+            Self::mode_label(interview.mode),
+            job.title,
+            job.company_name,
+            interview.notes.as_deref().map(|n| format!("\n\n{}", n)).unwrap_or_default()
+        );
+        let attendees: Vec<&str> = [&poster_email, &seeker_email]
+            .into_iter()
+            .filter_map(|email| email.as_deref())
+            .collect();
+
+        let event = CalendarEvent {
+            uid: &uid,
+            // A cancellation must outrank the invite it replaces
+            sequence: if method == IcsMethod::Cancel { 1 } else { 0 },
+            start: slot.start,
+            end: add_millis(slot.start, slot.duration_minutes as i64 * MINUTE_MILLIS),
+            summary: &summary,
+            description: &description,
+            location: interview.location.as_deref(),
+            organizer_email: &organizer,
+            attendee_emails: attendees.clone(),
+        };
+        let (subject, heading) = match method {
+            IcsMethod::Request => (
+                format!("Interview scheduled: {}", job.title),
+                "Your interview is scheduled",
+            ),
+            IcsMethod::Cancel => (
+                format!("Interview cancelled: {}", job.title),
+                "This interview has been cancelled",
+            ),
+        };
+        let body = format!(
+            r#"
+            <!DOCTYPE html>
+            <html>
+            <body>
+                <h2>{}</h2>
+                <p><strong>{}</strong> at {}</p>
+                <p>{} &middot; {} ({} minutes)</p>
+                <p>{}</p>
+                <p>The attached invite adds it to your calendar.</p>
+                <p>Best regards,<br><strong>Mento Services Team</strong></p>
+            </body>
+            </html>
+            "#,
+            heading,
+            job.title,
+            job.company_name,
+            Self::mode_label(interview.mode),
+            format_datetime(slot.start),
+            slot.duration_minutes,
+            interview.location.as_deref().unwrap_or_default()
+        );
+
+        let ics = event.to_ics(method);
+        for email in attendees {
+            EmailService::send_calendar_invite(email, &subject, body.clone(), ics.clone(), method.as_str()).await;
+        }
+
+        Ok(())
+    }
+
+    /// Scheduler job: remind both parties of interviews starting soon
+    pub async fn send_reminders(db: &Database) -> Result<(), String> {
+        let now = DateTime::now();
+        let horizon = add_millis(now, Config::interview_reminder_hours() * HOUR_MILLIS);
+
+        let due: Vec<Interview> = db
+            .collection::<Interview>("interviews")
+            .find(
+                doc! {
+                    "status": "scheduled",
+                    "reminder_sent": false,
+                    "scheduled_slot.start": { "$gt": now, "$lte": horizon }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for interview in due {
+            // Claim the reminder so overlapping runs don't send it twice
+            let claimed = db
+                .collection::<Interview>("interviews")
+                .update_one(
+                    doc! { "_id": interview.id, "reminder_sent": false },
+                    doc! { "$set": { "reminder_sent": true } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            if claimed.modified_count == 0 {
+                continue;
+            }
+            let Some(ref slot) = interview.scheduled_slot else {
+                warn!("Scheduled interview {:?} has no slot", interview.id);
+                continue;
+            };
+
+            let job_title = db
+                .collection::<JobPost>("jobs")
+                .find_one(doc! { "_id": interview.job_id }, None)
+                .await
+                .map_err(|e| e.to_string())?
+                .map(|job| job.title)
+                .unwrap_or_else(|| "your job".to_string());
+
+            let body = format!(
+                "Reminder: the interview for \"{}\" is on {} ({}).",
+                job_title,
+                format_datetime(slot.start),
+                Self::mode_label(interview.mode)
+            );
+            for user_id in [interview.poster_user_id, interview.seeker_user_id] {
+                let _ = create_user_notification(
+                    db,
+                    user_id,
+                    "interview_reminder".to_string(),
+                    "Upcoming Interview".to_string(),
+                    body.clone(),
+                    interview.id,
+                )
+                .await;
+            }
+
+            info!("Interview {:?} reminder sent", interview.id);
+        }
+
+        Ok(())
+    }
+}
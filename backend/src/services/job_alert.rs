@@ -0,0 +1,225 @@
+use log::{error, info};
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+use crate::config::Config;
+use crate::models::{AlertFrequency, JobPost, JobStatus, SavedSearch, User};
+use crate::routes::notification::create_user_notification;
+use crate::services::EmailService;
+use crate::utils::time::{DAY_MILLIS, add_millis};
+
+// Jobs listed in one alert email; the rest are summarised as a count
+const MAX_JOBS_PER_ALERT: usize = 20;
+
+pub struct JobAlertService;
+
+impl JobAlertService {
+    /// Approved jobs matching a saved search. Job posts are stored camelCase.
+    pub fn job_filter(search: &SavedSearch) -> Document {
+        let contains = |text: &str| doc! { "$regex": regex::escape(text.trim()), "$options": "i" };
+        let mut conditions = vec![doc! { "status": JobStatus::Approved.as_str() }];
+
+        if let Some(q) = search.q.as_deref().filter(|q| !q.trim().is_empty()) {
+            conditions.push(doc! {
+                "$or": [
+                    { "title": contains(q) },
+                    { "companyName": contains(q) },
+                    { "jobRole": contains(q) }
+                ]
+            });
+        }
+        if let Some(location) = search.location.as_deref().filter(|l| !l.trim().is_empty()) {
+            conditions.push(doc! { "location": contains(location) });
+        }
+        if let Some(role) = search.job_role.as_deref().filter(|r| !r.trim().is_empty()) {
+            conditions.push(doc! { "jobRole": contains(role) });
+        }
+        if let Some(salary_min) = search.salary_min {
+            // Jobs without an upper bound are judged on their lower one
+            conditions.push(doc! {
+                "$or": [
+                    { "salaryMax": { "$gte": salary_min } },
+                    { "salaryMax": null, "salaryMin": { "$gte": salary_min } }
+                ]
+            });
+        }
+
+        doc! { "$and": conditions }
+    }
+
+    pub fn unsubscribe_url(search: &SavedSearch) -> String {
+        format!(
+            "{}/api/v1/saved-searches/unsubscribe?token={}",
+            Config::app_base_url(),
+            search.unsubscribe_token
+        )
+    }
+
+    /// Scheduler job: find jobs approved since each search last ran, alerting
+    /// instant searches now and queueing the rest for their digest
+    pub async fn send_alerts(db: &Database) -> Result<(), String> {
+        let now = DateTime::now();
+        let searches: Vec<SavedSearch> = db
+            .collection::<SavedSearch>("saved_searches")
+            .find(doc! { "alert_frequency": { "$ne": AlertFrequency::Off.as_str() } }, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for search in searches {
+            if let Err(e) = Self::process(db, &search, now).await {
+                error!("Job alerts for saved search {:?} failed: {}", search.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn process(db: &Database, search: &SavedSearch, now: DateTime) -> Result<(), String> {
+        let search_id = search.id.ok_or("Saved search missing ID")?;
+
+        let mut filter = Self::job_filter(search);
+        filter.insert("approvedAt", doc! { "$gt": search.last_checked_at, "$lte": now });
+        let new_jobs: Vec<JobPost> = db
+            .collection::<JobPost>("jobs")
+            .find(filter, FindOptions::builder().sort(doc! { "approvedAt": -1 }).build())
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+        let new_ids: Vec<ObjectId> = new_jobs.iter().filter_map(|job| job.id).collect();
+
+        // Claim the window so overlapping runs don't alert twice; digests queue the matches
+        let mut update = doc! { "$set": { "last_checked_at": now } };
+        if search.alert_frequency != AlertFrequency::Instant && !new_ids.is_empty() {
+            update.insert("$addToSet", doc! { "pending_job_ids": { "$each": new_ids.clone() } });
+        }
+        let claimed = db
+            .collection::<SavedSearch>("saved_searches")
+            .update_one(
+                doc! { "_id": search_id, "last_checked_at": search.last_checked_at },
+                update,
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        if claimed.modified_count == 0 {
+            return Ok(());
+        }
+
+        let period_days = match search.alert_frequency {
+            AlertFrequency::Instant => {
+                if !new_jobs.is_empty() {
+                    Self::notify(db, search, &new_jobs).await?;
+                }
+                return Ok(());
+            }
+            AlertFrequency::Daily => 1,
+            AlertFrequency::Weekly => 7,
+            AlertFrequency::Off => return Ok(()),
+        };
+        if add_millis(search.last_digest_at, period_days * DAY_MILLIS) > now {
+            return Ok(());
+        }
+
+        // Take the queued matches and start the next digest period
+        let Some(queued) = db
+            .collection::<SavedSearch>("saved_searches")
+            .find_one_and_update(
+                doc! { "_id": search_id, "last_digest_at": search.last_digest_at },
+                doc! { "$set": { "last_digest_at": now, "pending_job_ids": [] } },
+                FindOneAndUpdateOptions::builder()
+                    .return_document(ReturnDocument::Before)
+                    .build(),
+            )
+            .await
+            .map_err(|e| e.to_string())?
+        else {
+            return Ok(());
+        };
+        if queued.pending_job_ids.is_empty() {
+            return Ok(());
+        }
+
+        // Skip jobs that closed or expired while queued
+        let jobs: Vec<JobPost> = db
+            .collection::<JobPost>("jobs")
+            .find(
+                doc! {
+                    "_id": { "$in": queued.pending_job_ids.clone() },
+                    "status": JobStatus::Approved.as_str()
+                },
+                FindOptions::builder().sort(doc! { "approvedAt": -1 }).build(),
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !jobs.is_empty() {
+            Self::notify(db, &queued, &jobs).await?;
+        }
+
+        Ok(())
+    }
+
+    /// In-app notification, plus email unless the seeker turned it off
+    async fn notify(db: &Database, search: &SavedSearch, jobs: &[JobPost]) -> Result<(), String> {
+        let first = &jobs[0];
+        let (related_id, body) = if jobs.len() == 1 {
+            (
+                first.id,
+                format!("{} at {} matches your saved search \"{}\".", first.title, first.company_name, search.name),
+            )
+        } else {
+            (
+                search.id,
+                format!(
+                    "{} new jobs match your saved search \"{}\", including {} at {}.",
+                    jobs.len(),
+                    search.name,
+                    first.title,
+                    first.company_name
+                ),
+            )
+        };
+        let _ = create_user_notification(
+            db,
+            search.user_id,
+            "job_alert".to_string(),
+            "New Job Matches".to_string(),
+            body,
+            related_id,
+        )
+        .await;
+
+        if search.email_alerts {
+            let email = db
+                .collection::<User>("users")
+                .find_one(doc! { "_id": search.user_id }, None)
+                .await
+                .map_err(|e| e.to_string())?
+                .and_then(|user| user.email)
+                .filter(|email| !email.trim().is_empty());
+            if let Some(email) = email {
+                let listed = &jobs[..jobs.len().min(MAX_JOBS_PER_ALERT)];
+                EmailService::send_job_alert_email(
+                    &email,
+                    &search.name,
+                    listed,
+                    jobs.len(),
+                    &Self::unsubscribe_url(search),
+                )
+                .await;
+            }
+        }
+
+        info!("Job alert for saved search {:?}: {} jobs", search.id, jobs.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,366 @@
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+use serde::Serialize;
+use std::cmp::Reverse;
+use rocket_okapi::okapi::schemars::JsonSchema;
+
+use crate::models::{
+    ApplicationStatus, JobApplication, JobPost, JobSeekerProfile, JobSeekerSubscriptionPlan,
+    JobStatus, WorkMode,
+};
+
+// Weights out of 100 for each part of a match
+const SKILLS_WEIGHT: f64 = 30.0;
+const ROLE_WEIGHT: f64 = 20.0;
+const LOCATION_WEIGHT: f64 = 15.0;
+const RELOCATION_WEIGHT: f64 = 6.0;
+const SALARY_WEIGHT: f64 = 15.0;
+const JOB_TYPE_WEIGHT: f64 = 10.0;
+const EXPERIENCE_WEIGHT: f64 = 10.0;
+// Skills needed for the full skills score; most posts only list a handful
+const FULL_SKILLS_MATCH: usize = 5;
+// Added to premium job seekers when ranking candidates
+const PREMIUM_BOOST: u32 = 10;
+// Most documents scored per request, after the prefilter
+const CANDIDATE_CAP: i64 = 2000;
+// Shorter terms match nearly everything, so they don't narrow the prefilter
+const MIN_TERM_LEN: usize = 3;
+
+/// How well a job seeker fits a job, with the reasons behind the score
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MatchScore {
+    pub score: u32,
+    pub reasons: Vec<String>,
+}
+
+pub struct JobMatchingService;
+
+impl JobMatchingService {
+    /// Lowercase words of `text`, keeping `+` and `#` so "C++" and "C#" survive
+    fn words(text: &str) -> Vec<String> {
+        text.to_lowercase()
+            .split(|c: char| !(c.is_alphanumeric() || c == '+' || c == '#'))
+            .filter(|w| !w.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Whether the whole phrase appears in `haystack` (space-padded words)
+    fn contains_phrase(haystack: &str, phrase: &str) -> bool {
+        let words = Self::words(phrase);
+        !words.is_empty() && haystack.contains(&format!(" {} ", words.join(" ")))
+    }
+
+    fn padded(parts: &[&str]) -> String {
+        format!(" {} ", Self::words(&parts.join(" ")).join(" "))
+    }
+
+    /// Case-insensitive regex matching any of `terms`. Only narrows the candidates
+    /// `score` looks at, so it errs on the side of matching too much.
+    fn any_of<'a>(terms: impl IntoIterator<Item = &'a str>) -> Option<Document> {
+        let mut terms: Vec<String> = terms
+            .into_iter()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| t.chars().count() >= MIN_TERM_LEN)
+            .map(|t| regex::escape(&t))
+            .collect();
+        terms.sort();
+        terms.dedup();
+
+        (!terms.is_empty()).then(|| doc! { "$regex": terms.join("|"), "$options": "i" })
+    }
+
+    /// Jobs that could score for `profile`: mentioning one of their skills or roles,
+    /// in a place or of a type they want, or remote. None when the profile has
+    /// nothing to narrow on.
+    fn jobs_prefilter(profile: &JobSeekerProfile) -> Option<Document> {
+        let mut any = vec![doc! { "workMode": WorkMode::Remote.as_str() }];
+
+        let roles = profile
+            .preferred_categories
+            .iter()
+            .chain(profile.headline.iter())
+            .chain(profile.work_experience.iter().map(|w| &w.title));
+        let terms = profile.skills.iter().chain(roles).map(String::as_str);
+        if let Some(pattern) = Self::any_of(terms) {
+            for field in ["title", "jobRole", "requirements", "eligibility", "companyBrief"] {
+                any.push(doc! { field: pattern.clone() });
+            }
+        }
+        if profile.willing_to_relocate {
+            any.push(doc! { "location": { "$ne": null } });
+        } else if let Some(pattern) =
+            Self::any_of(profile.preferred_locations.iter().map(String::as_str))
+        {
+            any.push(doc! { "location": pattern });
+        }
+        let job_types: Vec<String> = profile.preferred_job_types.iter().map(|t| t.to_lowercase()).collect();
+        if !job_types.is_empty() {
+            any.push(doc! { "jobType": { "$in": job_types } });
+        }
+
+        (any.len() > 1).then(|| doc! { "$or": any })
+    }
+
+    /// Seekers that could score for `job`: with a skill or role from the job, wanting
+    /// its location or type, or open to relocating. None for remote jobs, which
+    /// score on location for everyone.
+    fn candidates_prefilter(job: &JobPost) -> Option<Document> {
+        if job.work_mode == Some(WorkMode::Remote) {
+            return None;
+        }
+
+        let requirements = job.requirements.clone().unwrap_or_default().join(" ");
+        let eligibility = job.eligibility.clone().unwrap_or_default().join(" ");
+        let job_words =
+            Self::words(&[job.title.as_str(), &job.job_role, &requirements, &eligibility].join(" "));
+        let role_words = Self::words(&[job.title.as_str(), &job.job_role].join(" "));
+
+        let mut any = vec![doc! { "willing_to_relocate": true }];
+        if let Some(pattern) = Self::any_of(job_words.iter().map(String::as_str)) {
+            any.push(doc! { "skills": pattern });
+        }
+        if let Some(pattern) = Self::any_of(role_words.iter().map(String::as_str)) {
+            for field in ["preferred_categories", "headline", "work_experience.title"] {
+                any.push(doc! { field: pattern.clone() });
+            }
+        }
+        if let Some(location) = job.location.as_deref()
+            && let Some(pattern) = Self::any_of(Self::words(location).iter().map(String::as_str))
+        {
+            any.push(doc! { "preferred_locations": pattern });
+        }
+        if let Some(job_type) = job.job_type {
+            let pattern = format!("^{}$", job_type.as_str());
+            any.push(doc! { "preferred_job_types": { "$regex": pattern, "$options": "i" } });
+        }
+
+        Some(doc! { "$or": any })
+    }
+
+    fn is_premium(profile: &JobSeekerProfile) -> bool {
+        matches!(profile.subscription_plan, JobSeekerSubscriptionPlan::Premium)
+            && profile.subscription_expires_at.is_some_and(|at| at > DateTime::now())
+    }
+
+    /// Score a seeker against a job. Symmetric, so it ranks jobs for a seeker and
+    /// seekers for a job alike.
+    pub fn score(profile: &JobSeekerProfile, job: &JobPost) -> MatchScore {
+        let mut score = 0.0;
+        let mut reasons = Vec::new();
+
+        let requirements = job.requirements.clone().unwrap_or_default().join(" ");
+        let eligibility = job.eligibility.clone().unwrap_or_default().join(" ");
+        let job_text = Self::padded(&[
+            &job.title,
+            &job.job_role,
+            &requirements,
+            &eligibility,
+            job.company_brief.as_deref().unwrap_or_default(),
+        ]);
+        let role_text = Self::padded(&[&job.title, &job.job_role]);
+
+        let matched_skills: Vec<&String> = profile
+            .skills
+            .iter()
+            .filter(|skill| Self::contains_phrase(&job_text, skill))
+            .collect();
+        if !matched_skills.is_empty() {
+            let wanted = profile.skills.len().min(FULL_SKILLS_MATCH);
+            score += SKILLS_WEIGHT * (matched_skills.len().min(wanted) as f64 / wanted as f64);
+            reasons.push(format!(
+                "Matching skills: {}",
+                matched_skills.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+            ));
+        }
+
+        // Preferred categories first, then headline and past job titles
+        let preferred_role = profile
+            .preferred_categories
+            .iter()
+            .find(|category| Self::contains_phrase(&role_text, category));
+        let past_role = profile
+            .headline
+            .iter()
+            .chain(profile.work_experience.iter().map(|w| &w.title))
+            .find(|title| {
+                Self::contains_phrase(&role_text, title)
+                    || Self::contains_phrase(&Self::padded(&[title.as_str()]), &job.job_role)
+            });
+        if let Some(category) = preferred_role {
+            score += ROLE_WEIGHT;
+            reasons.push(format!("Preferred role: {}", category));
+        } else if let Some(title) = past_role {
+            score += ROLE_WEIGHT * 0.8;
+            reasons.push(format!("Relevant experience: {}", title));
+        }
+
+        if job.work_mode == Some(WorkMode::Remote) {
+            score += LOCATION_WEIGHT;
+            reasons.push("Remote job".to_string());
+        } else if let Some(location) = job.location.as_deref() {
+            let location_text = Self::padded(&[location]);
+            let preferred = profile
+                .preferred_locations
+                .iter()
+                .find(|preferred| Self::contains_phrase(&location_text, preferred));
+            if let Some(preferred) = preferred {
+                score += LOCATION_WEIGHT;
+                reasons.push(format!("Preferred location: {}", preferred));
+            } else if profile.willing_to_relocate {
+                score += RELOCATION_WEIGHT;
+                reasons.push(format!("Open to relocating to {}", location));
+            }
+        }
+
+        // Ranges overlap; a missing bound is open-ended
+        let offered = (job.salary_min, job.salary_max);
+        let expected = (profile.expected_salary_min, profile.expected_salary_max);
+        if (offered.0.is_some() || offered.1.is_some()) && (expected.0.is_some() || expected.1.is_some()) {
+            let fits = offered.1.unwrap_or(f64::MAX) >= expected.0.unwrap_or(0.0)
+                && expected.1.unwrap_or(f64::MAX) >= offered.0.unwrap_or(0.0);
+            if fits {
+                score += SALARY_WEIGHT;
+                reasons.push("Salary expectations fit the offered range".to_string());
+            }
+        }
+
+        if let Some(job_type) = job.job_type
+            && profile.preferred_job_types.iter().any(|t| t.eq_ignore_ascii_case(job_type.as_str()))
+        {
+            score += JOB_TYPE_WEIGHT;
+            reasons.push(format!("Preferred job type: {}", job_type.as_str()));
+        }
+
+        // Only scored when the job states a range and the seeker their years
+        if let Some(years) = profile.experience_years {
+            let enough = job.experience_min.is_none_or(|min| years >= min);
+            let not_over = job.experience_max.is_none_or(|max| years <= max);
+            if (job.experience_min.is_some() || job.experience_max.is_some()) && enough && not_over {
+                score += EXPERIENCE_WEIGHT;
+                reasons.push(format!("{} years of experience fits the role", years));
+            }
+        }
+
+        MatchScore {
+            score: score.round() as u32,
+            reasons,
+        }
+    }
+
+    /// Approved, unexpired jobs the seeker hasn't applied to, best match first
+    pub async fn recommend_jobs(
+        db: &Database,
+        profile: &JobSeekerProfile,
+        limit: usize,
+    ) -> Result<Vec<(JobPost, MatchScore)>, String> {
+        let profile_id = profile.id.ok_or("Profile missing ID")?;
+        let applied: Vec<ObjectId> = db
+            .collection::<JobApplication>("applications")
+            .find(
+                doc! {
+                    "seeker_profile_id": profile_id,
+                    "status": { "$ne": ApplicationStatus::Withdrawn.as_str() }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect::<Vec<JobApplication>>()
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|application| application.job_id)
+            .collect();
+
+        let mut conditions = vec![doc! {
+            "$or": [
+                { "expiresAt": null },
+                { "expiresAt": { "$gt": DateTime::now() } }
+            ]
+        }];
+        conditions.extend(Self::jobs_prefilter(profile));
+        let filter = doc! {
+            "_id": { "$nin": applied },
+            "status": JobStatus::Approved.as_str(),
+            "postedBy": { "$ne": profile.user_id },
+            "$and": conditions
+        };
+
+        let mut jobs = db
+            .collection::<JobPost>("jobs")
+            .find(
+                filter,
+                FindOptions::builder()
+                    .sort(doc! { "createdAt": -1 })
+                    .limit(CANDIDATE_CAP)
+                    .build(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // Scored newest first, so ties go to newer posts
+        let mut ranked = Vec::new();
+        while let Some(job) = jobs.try_next().await.map_err(|e| e.to_string())? {
+            let score = Self::score(profile, &job);
+            if score.score > 0 {
+                ranked.push((job, score));
+                Self::keep_best(&mut ranked, limit, false);
+            }
+        }
+        Self::keep_best(&mut ranked, limit, true);
+        Ok(ranked)
+    }
+
+    /// Available job seekers for a job, best match first, premium seekers boosted
+    pub async fn rank_candidates(
+        db: &Database,
+        job: &JobPost,
+        limit: usize,
+    ) -> Result<Vec<(JobSeekerProfile, MatchScore)>, String> {
+        let mut filter = doc! { "is_available": true, "user_id": { "$ne": job.posted_by } };
+        if let Some(prefilter) = Self::candidates_prefilter(job) {
+            filter.extend(prefilter);
+        }
+
+        let mut profiles = db
+            .collection::<JobSeekerProfile>("job_seeker_profiles")
+            .find(
+                filter,
+                FindOptions::builder()
+                    .sort(doc! { "updated_at": -1 })
+                    .limit(CANDIDATE_CAP)
+                    .build(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut ranked = Vec::new();
+        while let Some(profile) = profiles.try_next().await.map_err(|e| e.to_string())? {
+            let mut score = Self::score(&profile, job);
+            if score.score == 0 {
+                continue;
+            }
+            if Self::is_premium(&profile) {
+                score.score += PREMIUM_BOOST;
+                score.reasons.push("Premium job seeker".to_string());
+            }
+            ranked.push((profile, score));
+            Self::keep_best(&mut ranked, limit, false);
+        }
+        Self::keep_best(&mut ranked, limit, true);
+        Ok(ranked)
+    }
+
+    /// Trim `ranked` to the best `limit`, highest score first. Earlier entries win
+    /// ties (the sort is stable). Unless `now`, waits until there is enough to trim
+    /// so matches are sorted in batches rather than on every push.
+    fn keep_best<T>(ranked: &mut Vec<(T, MatchScore)>, limit: usize, now: bool) {
+        if now || ranked.len() >= limit.max(1) * 2 {
+            ranked.sort_by_key(|(_, m)| Reverse(m.score));
+            ranked.truncate(limit);
+        }
+    }
+}
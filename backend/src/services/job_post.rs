@@ -0,0 +1,136 @@
+use log::info;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+use crate::config::Config;
+use crate::models::{JobPost, JobStatus};
+use crate::routes::notification::create_user_notification;
+use crate::utils::time::{DAY_MILLIS, add_millis, format_date};
+
+// Job posts are stored with camelCase field names (see `JobPost`)
+
+pub struct JobPostService;
+
+impl JobPostService {
+    /// Expiry for a post approved or renewed now
+    pub fn fresh_expiry() -> DateTime {
+        add_millis(DateTime::now(), Config::job_post_validity_days() * DAY_MILLIS)
+    }
+
+    /// Move a post from the status it was loaded with to `to`, setting `extra`
+    /// fields too. Returns None if someone else changed it first.
+    pub async fn transition(
+        db: &Database,
+        job: &JobPost,
+        to: JobStatus,
+        mut extra: Document,
+    ) -> Result<Option<JobPost>, String> {
+        extra.insert("status", to.as_str());
+        extra.insert("updatedAt", DateTime::now());
+
+        db.collection::<JobPost>("jobs")
+            .find_one_and_update(
+                doc! { "_id": job.id, "status": job.status.as_str() },
+                doc! { "$set": extra },
+                FindOneAndUpdateOptions::builder()
+                    .return_document(ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Scheduler job: take down approved posts past their expiry
+    pub async fn expire_posts(db: &Database) -> Result<(), String> {
+        let due: Vec<JobPost> = db
+            .collection::<JobPost>("jobs")
+            .find(
+                doc! { "status": JobStatus::Approved.as_str(), "expiresAt": { "$lte": DateTime::now() } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for job in due {
+            if Self::transition(db, &job, JobStatus::Expired, doc! {}).await?.is_none() {
+                continue;
+            }
+
+            let _ = create_user_notification(
+                db,
+                job.posted_by,
+                "job_expired".to_string(),
+                "Job Post Expired".to_string(),
+                format!(
+                    "Your job \"{}\" has expired and is no longer visible. Reopen it to keep hiring.",
+                    job.title
+                ),
+                job.id,
+            )
+            .await;
+
+            info!("Job post {:?} expired", job.id);
+        }
+
+        Ok(())
+    }
+
+    /// Scheduler job: remind owners to renew posts that expire soon
+    pub async fn send_expiry_reminders(db: &Database) -> Result<(), String> {
+        let now = DateTime::now();
+        let horizon = add_millis(now, Config::job_expiry_reminder_days() * DAY_MILLIS);
+
+        let due: Vec<JobPost> = db
+            .collection::<JobPost>("jobs")
+            .find(
+                doc! {
+                    "status": JobStatus::Approved.as_str(),
+                    "expiryReminderSent": { "$ne": true },
+                    "expiresAt": { "$gt": now, "$lte": horizon }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for job in due {
+            let claimed = db
+                .collection::<JobPost>("jobs")
+                .update_one(
+                    doc! { "_id": job.id, "expiryReminderSent": { "$ne": true } },
+                    doc! { "$set": { "expiryReminderSent": true } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            if claimed.modified_count == 0 {
+                continue;
+            }
+            let Some(expires_at) = job.expires_at else { continue };
+
+            let _ = create_user_notification(
+                db,
+                job.posted_by,
+                "job_expiring".to_string(),
+                "Job Post Expiring Soon".to_string(),
+                format!(
+                    "Your job \"{}\" expires on {}. Renew it to keep receiving applications.",
+                    job.title,
+                    format_date(expires_at)
+                ),
+                job.id,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use mongodb::bson::{doc, Document};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+pub struct MatchingService;
+
+impl MatchingService {
+    /// `$geoNear` stage over `worker_profiles.location` (2dsphere index), adding a
+    /// `distance` field in metres
+    pub fn geo_near_stage(longitude: f64, latitude: f64, max_distance_m: f64) -> Document {
+        Self::geo_near_stage_on("location", longitude, latitude, max_distance_m)
+    }
+
+    /// `$geoNear` stage over any 2dsphere-indexed `key`, adding a `distance` field in metres
+    pub fn geo_near_stage_on(key: &str, longitude: f64, latitude: f64, max_distance_m: f64) -> Document {
+        doc! {
+            "$geoNear": {
+                "near": {
+                    "type": "Point",
+                    "coordinates": [longitude, latitude]
+                },
+                "distanceField": "distance",
+                "maxDistance": max_distance_m,
+                "spherical": true,
+                "key": key
+            }
+        }
+    }
+
+    /// Nearest worker profiles matching `filter` within `max_distance_m`, closest first
+    pub async fn nearby_workers(
+        db: &Database,
+        longitude: f64,
+        latitude: f64,
+        max_distance_m: f64,
+        filter: Document,
+        limit: i64,
+    ) -> Result<Vec<Document>, String> {
+        let pipeline = vec![
+            Self::geo_near_stage(longitude, latitude, max_distance_m),
+            doc! { "$match": filter },
+            doc! { "$limit": limit },
+            doc! {
+                "$project": {
+                    "_id": 1,
+                    "user_id": 1,
+                    "subscription_plan": 1,
+                    "rating": 1,
+                    "distance": 1
+                }
+            },
+        ];
+
+        db.collection::<Document>("worker_profiles")
+            .aggregate(pipeline, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,78 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+use mongodb::bson::doc;
+use mongodb::Database;
+
+use crate::models::{Conversation, Message};
+use crate::services::EntitlementService;
+
+/// Indian mobile numbers, with or without +91/0 prefixes and separators
+static PHONE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:(?:\+91|\b91|\b0)[\s.-]?[6-9]|\b[6-9])(?:[\s.-]?\d){9}\b").expect("valid phone pattern")
+});
+
+pub struct MessagingService;
+
+impl MessagingService {
+    /// Whether the pair has a confirmed booking or a paid contact reveal, either of
+    /// which unlocks phone numbers in chat
+    pub async fn contact_unlocked(db: &Database, conversation: &Conversation) -> Result<bool, String> {
+        let booking = db
+            .collection::<mongodb::bson::Document>("bookings")
+            .find_one(
+                doc! {
+                    "customer_id": conversation.customer_id,
+                    "worker_user_id": conversation.worker_user_id,
+                    "status": { "$in": ["accepted", "in_progress", "completed"] }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        if booking.is_some() {
+            return Ok(true);
+        }
+
+        EntitlementService::has_revealed(db, conversation.customer_id, conversation.worker_user_id).await
+    }
+
+    pub fn mask_phone_numbers(text: &str) -> String {
+        PHONE_PATTERN.replace_all(text, "[phone hidden]").into_owned()
+    }
+
+    /// A message as shown to the participants
+    pub fn message_json(message: &Message, unlocked: bool) -> serde_json::Value {
+        let body = message.body.as_deref().map(|body| {
+            if unlocked {
+                body.to_string()
+            } else {
+                Self::mask_phone_numbers(body)
+            }
+        });
+
+        serde_json::json!({
+            "_id": message.id.map(|id| id.to_hex()),
+            "conversation_id": message.conversation_id.to_hex(),
+            "sender_id": message.sender_id.to_hex(),
+            "body": body,
+            "attachments": message.attachments,
+            "read_at": message.read_at,
+            "created_at": message.created_at,
+        })
+    }
+
+    /// Short text for conversation lists
+    pub fn preview(message: &Message) -> String {
+        match message.body.as_deref().map(str::trim).filter(|b| !b.is_empty()) {
+            Some(body) => {
+                let masked = Self::mask_phone_numbers(body);
+                match masked.char_indices().nth(80) {
+                    Some((i, _)) => format!("{}…", &masked[..i]),
+                    None => masked,
+                }
+            }
+            None => "📷 Photo".to_string(),
+        }
+    }
+}
@@ -1,9 +1,55 @@
+pub mod analytics;
+pub mod application;
+pub mod audit;
+pub mod availability;
+pub mod billing;
+pub mod booking;
+pub mod dispute;
 pub mod email;
+pub mod entitlement;
+pub mod events;
+pub mod interview;
+pub mod job_alert;
+pub mod job_matching;
+pub mod job_post;
 pub mod jwt;
+pub mod matching;
+pub mod messaging;
 pub mod msg91;
+pub mod payment_gateway;
+pub mod payment_link;
+pub mod pdf;
+pub mod quote;
 pub mod razorpay;
+pub mod reliability;
+pub mod renewal;
+pub mod resume;
+pub mod scheduler;
+pub mod service_request;
 
+pub use analytics::AnalyticsService;
+pub use application::ApplicationService;
+pub use audit::AuditService;
+pub use availability::AvailabilityService;
+pub use billing::BillingService;
+pub use booking::BookingService;
+pub use dispute::DisputeService;
 pub use razorpay::RazorpayService;
 pub use email::EmailService;
+pub use entitlement::EntitlementService;
+pub use events::EventHub;
+pub use interview::InterviewService;
+pub use job_alert::JobAlertService;
+pub use job_matching::JobMatchingService;
+pub use job_post::JobPostService;
 pub use jwt::JwtService;
-pub use msg91::Msg91Service;
\ No newline at end of file
+pub use matching::MatchingService;
+pub use messaging::MessagingService;
+pub use msg91::Msg91Service;
+pub use payment_gateway::payment_gateway;
+pub use payment_link::PaymentLinkService;
+pub use quote::QuoteService;
+pub use reliability::ReliabilityService;
+pub use renewal::RenewalService;
+pub use resume::ResumeService;
+pub use service_request::ServiceRequestService;
@@ -3,6 +3,7 @@ use serde::{Deserialize};
 use serde_json::json;
 
 const MSG91_BASE: &str = "https://control.msg91.com/api/v5/otp";
+const MSG91_FLOW: &str = "https://control.msg91.com/api/v5/flow/";
 
 pub struct Msg91Service;
 
@@ -77,4 +78,49 @@ impl Msg91Service {
 
         Ok(())
     }
+
+    /// Send a transactional SMS through a MSG91 flow template; `variables` fill the template
+    pub async fn send_sms(mobile: &str, template_id: &str, variables: serde_json::Value) -> Result<(), String> {
+        let mut recipient = json!({ "mobiles": format!("91{}", mobile) });
+        if let (Some(recipient), Some(vars)) = (recipient.as_object_mut(), variables.as_object()) {
+            recipient.extend(vars.clone());
+        }
+
+        let res = Self::client()
+            .post(MSG91_FLOW)
+            .header("authkey", Self::auth_key())
+            .json(&json!({
+                "template_id": template_id,
+                "short_url": "0",
+                "recipients": [recipient]
+            }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !res.status().is_success() {
+            return Err(res.text().await.unwrap_or_else(|_| "MSG91 send SMS failed".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Send a payment link using the `MSG91_PAYMENT_LINK_TEMPLATE_ID` template
+    /// (variables: `name`, `plan`, `amount`, `link`)
+    pub async fn send_payment_link(mobile: &str, name: &str, plan: &str, amount: f64, link: &str) -> Result<(), String> {
+        let template_id = std::env::var("MSG91_PAYMENT_LINK_TEMPLATE_ID")
+            .map_err(|_| "MSG91_PAYMENT_LINK_TEMPLATE_ID not set".to_string())?;
+
+        Self::send_sms(
+            mobile,
+            &template_id,
+            json!({
+                "name": name,
+                "plan": plan,
+                "amount": format!("{:.2}", amount),
+                "link": link
+            }),
+        )
+        .await
+    }
 }
@@ -0,0 +1,509 @@
+use mongodb::bson::DateTime;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::models::{MandateStatus, PaymentMandate, User};
+use crate::services::RazorpayService;
+
+/// A mandate that still has to be authorised by the customer
+pub struct MandateSetup {
+    pub mandate: PaymentMandate,
+    pub checkout: serde_json::Value, // Order details the client opens checkout with
+}
+
+/// Hosted payment page for a single amount
+pub struct HostedLink {
+    pub id: String,
+    pub url: String,
+}
+
+/// Gateway-side state of a hosted payment link
+pub struct HostedLinkState {
+    pub status: String, // "created" | "paid" | "expired" | "cancelled"
+    pub payment_id: Option<String>,
+}
+
+/// Operations the billing code needs from a payment provider
+#[rocket::async_trait]
+pub trait PaymentGateway: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Create a one-off order for `amount` rupees
+    async fn create_order(&self, amount: f64) -> Result<serde_json::Value, String>;
+
+    /// Check the signature returned by checkout
+    fn verify_payment(&self, order_id: &str, payment_id: &str, signature: &str) -> Result<(), String>;
+
+    /// Start a recurring-payment mandate capped at `max_amount` rupees per charge
+    async fn create_mandate(&self, user: &User, max_amount: f64) -> Result<MandateSetup, String>;
+
+    /// Exchange the authorisation payment for a reusable token
+    async fn confirm_mandate(&self, mandate: &PaymentMandate, payment_id: &str) -> Result<String, String>;
+
+    /// Charge an active mandate; returns the gateway payment ID
+    async fn charge_mandate(
+        &self,
+        mandate: &PaymentMandate,
+        user: &User,
+        amount: f64,
+        receipt: &str,
+    ) -> Result<String, String>;
+
+    /// Create a payment link for `amount` rupees; we deliver it ourselves
+    async fn create_payment_link(
+        &self,
+        user: &User,
+        amount: f64,
+        reference: &str,
+        description: &str,
+        expires_at: DateTime,
+    ) -> Result<HostedLink, String>;
+
+    async fn fetch_payment_link(&self, link_id: &str) -> Result<HostedLinkState, String>;
+
+    async fn cancel_payment_link(&self, link_id: &str) -> Result<(), String>;
+
+    /// Refund `amount` rupees of a captured payment; returns the gateway refund ID
+    async fn refund_payment(&self, payment_id: &str, amount: f64) -> Result<String, String>;
+}
+
+/// Gateway selected by the `PAYMENT_GATEWAY` setting
+pub fn payment_gateway() -> Box<dyn PaymentGateway> {
+    match crate::config::Config::payment_gateway().as_str() {
+        "local" => Box::new(LocalGateway),
+        _ => Box::new(RazorpayGateway),
+    }
+}
+
+fn to_paise(amount: f64) -> i64 {
+    (amount * 100.0).round() as i64
+}
+
+// ============================================================================
+// RAZORPAY
+// ============================================================================
+
+pub struct RazorpayGateway;
+
+#[rocket::async_trait]
+impl PaymentGateway for RazorpayGateway {
+    fn name(&self) -> &'static str {
+        "razorpay"
+    }
+
+    async fn create_order(&self, amount: f64) -> Result<serde_json::Value, String> {
+        RazorpayService::create_order(amount).await
+    }
+
+    fn verify_payment(&self, order_id: &str, payment_id: &str, signature: &str) -> Result<(), String> {
+        RazorpayService::verify_signature(order_id, payment_id, signature)
+    }
+
+    async fn create_mandate(&self, user: &User, max_amount: f64) -> Result<MandateSetup, String> {
+        let customer = RazorpayService::post(
+            "/customers",
+            json!({
+                "name": user.name.clone().unwrap_or_default(),
+                "email": user.email.clone().unwrap_or_default(),
+                "contact": user.mobile,
+                "fail_existing": "0"
+            }),
+        )
+        .await?;
+
+        let customer_id = customer
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or("Razorpay customer response missing id")?
+            .to_string();
+
+        // ₹1 authorisation payment that registers the recurring token
+        let order = RazorpayService::post(
+            "/orders",
+            json!({
+                "amount": 100,
+                "currency": "INR",
+                "customer_id": customer_id,
+                "method": "card",
+                "payment_capture": 1,
+                "token": {
+                    "max_amount": to_paise(max_amount),
+                    "expire_at": chrono::Utc::now().timestamp() + 10 * 365 * 24 * 60 * 60,
+                    "frequency": "as_presented"
+                }
+            }),
+        )
+        .await?;
+
+        let order_id = order.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
+
+        Ok(MandateSetup {
+            mandate: PaymentMandate {
+                gateway: self.name().to_string(),
+                customer_id: Some(customer_id.clone()),
+                order_id,
+                token_id: None,
+                status: MandateStatus::Pending,
+                created_at: DateTime::now(),
+            },
+            checkout: json!({
+                "order": order,
+                "customer_id": customer_id,
+                "recurring": "1"
+            }),
+        })
+    }
+
+    async fn confirm_mandate(&self, _mandate: &PaymentMandate, payment_id: &str) -> Result<String, String> {
+        let payment = RazorpayService::get(&format!("/payments/{}", payment_id)).await?;
+
+        payment
+            .get("token_id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| "Payment did not create a recurring token".to_string())
+    }
+
+    async fn charge_mandate(
+        &self,
+        mandate: &PaymentMandate,
+        user: &User,
+        amount: f64,
+        receipt: &str,
+    ) -> Result<String, String> {
+        let token_id = mandate.token_id.as_deref().ok_or("Mandate has no token")?;
+        let customer_id = mandate.customer_id.as_deref().ok_or("Mandate has no customer")?;
+
+        let order = RazorpayService::post(
+            "/orders",
+            json!({
+                "amount": to_paise(amount),
+                "currency": "INR",
+                "receipt": receipt,
+                "payment_capture": 1
+            }),
+        )
+        .await?;
+        let order_id = order
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or("Razorpay order response missing id")?;
+
+        let payment = RazorpayService::post(
+            "/payments/create/recurring",
+            json!({
+                "email": user.email.clone().unwrap_or_default(),
+                "contact": user.mobile,
+                "amount": to_paise(amount),
+                "currency": "INR",
+                "order_id": order_id,
+                "customer_id": customer_id,
+                "token": token_id,
+                "recurring": "1",
+                "description": receipt
+            }),
+        )
+        .await?;
+
+        payment
+            .get("razorpay_payment_id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| "Recurring payment response missing payment id".to_string())
+    }
+
+    async fn create_payment_link(
+        &self,
+        user: &User,
+        amount: f64,
+        reference: &str,
+        description: &str,
+        expires_at: DateTime,
+    ) -> Result<HostedLink, String> {
+        let link = RazorpayService::post(
+            "/payment_links",
+            json!({
+                "amount": to_paise(amount),
+                "currency": "INR",
+                "reference_id": reference,
+                "description": description,
+                "expire_by": expires_at.timestamp_millis() / 1000,
+                "customer": {
+                    "name": user.name.clone().unwrap_or_default(),
+                    "contact": format!("+91{}", user.mobile),
+                    "email": user.email.clone().unwrap_or_default()
+                },
+                "notify": { "sms": false, "email": false },
+                "reminder_enable": false
+            }),
+        )
+        .await?;
+
+        let id = link.get("id").and_then(|v| v.as_str());
+        let url = link.get("short_url").and_then(|v| v.as_str());
+
+        match (id, url) {
+            (Some(id), Some(url)) => Ok(HostedLink {
+                id: id.to_string(),
+                url: url.to_string(),
+            }),
+            _ => Err("Razorpay payment link response missing id or short_url".to_string()),
+        }
+    }
+
+    async fn fetch_payment_link(&self, link_id: &str) -> Result<HostedLinkState, String> {
+        let link = RazorpayService::get(&format!("/payment_links/{}", link_id)).await?;
+
+        let payment_id = link
+            .get("payments")
+            .and_then(|v| v.as_array())
+            .and_then(|payments| {
+                payments
+                    .iter()
+                    .find(|p| p.get("status").and_then(|s| s.as_str()) == Some("captured"))
+            })
+            .and_then(|p| p.get("payment_id"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        Ok(HostedLinkState {
+            status: link
+                .get("status")
+                .and_then(|v| v.as_str())
+                .unwrap_or("created")
+                .to_string(),
+            payment_id,
+        })
+    }
+
+    async fn cancel_payment_link(&self, link_id: &str) -> Result<(), String> {
+        RazorpayService::post(&format!("/payment_links/{}/cancel", link_id), json!({})).await?;
+        Ok(())
+    }
+
+    async fn refund_payment(&self, payment_id: &str, amount: f64) -> Result<String, String> {
+        let refund = RazorpayService::post(
+            &format!("/payments/{}/refund", payment_id),
+            json!({ "amount": to_paise(amount), "speed": "normal" }),
+        )
+        .await?;
+
+        refund
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| "Razorpay refund response missing id".to_string())
+    }
+}
+
+// ============================================================================
+// LOCAL STAND-IN (development / tests)
+// ============================================================================
+
+/// Offline gateway: every call succeeds except charges against tokens
+/// containing "fail", and signatures are `local_<order_id>_<payment_id>`.
+pub struct LocalGateway;
+
+impl LocalGateway {
+    pub fn signature(order_id: &str, payment_id: &str) -> String {
+        format!("local_{}_{}", order_id, payment_id)
+    }
+}
+
+#[rocket::async_trait]
+impl PaymentGateway for LocalGateway {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn create_order(&self, amount: f64) -> Result<serde_json::Value, String> {
+        Ok(json!({
+            "id": format!("order_local_{}", Uuid::new_v4().simple()),
+            "amount": to_paise(amount),
+            "currency": "INR",
+            "status": "created"
+        }))
+    }
+
+    fn verify_payment(&self, order_id: &str, payment_id: &str, signature: &str) -> Result<(), String> {
+        if Self::signature(order_id, payment_id) != signature {
+            return Err("Invalid payment signature".to_string());
+        }
+        Ok(())
+    }
+
+    async fn create_mandate(&self, _user: &User, _max_amount: f64) -> Result<MandateSetup, String> {
+        let order = self.create_order(1.0).await?;
+        let customer_id = format!("cust_local_{}", Uuid::new_v4().simple());
+
+        Ok(MandateSetup {
+            mandate: PaymentMandate {
+                gateway: self.name().to_string(),
+                customer_id: Some(customer_id.clone()),
+                order_id: order.get("id").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                token_id: None,
+                status: MandateStatus::Pending,
+                created_at: DateTime::now(),
+            },
+            checkout: json!({
+                "order": order,
+                "customer_id": customer_id,
+                "recurring": "1"
+            }),
+        })
+    }
+
+    async fn confirm_mandate(&self, _mandate: &PaymentMandate, payment_id: &str) -> Result<String, String> {
+        Ok(format!("token_local_{}", payment_id))
+    }
+
+    async fn charge_mandate(
+        &self,
+        mandate: &PaymentMandate,
+        _user: &User,
+        _amount: f64,
+        _receipt: &str,
+    ) -> Result<String, String> {
+        let token_id = mandate.token_id.as_deref().ok_or("Mandate has no token")?;
+
+        if token_id.contains("fail") {
+            return Err("Payment declined by local gateway".to_string());
+        }
+
+        Ok(format!("pay_local_{}", Uuid::new_v4().simple()))
+    }
+
+    async fn create_payment_link(
+        &self,
+        _user: &User,
+        _amount: f64,
+        _reference: &str,
+        _description: &str,
+        _expires_at: DateTime,
+    ) -> Result<HostedLink, String> {
+        let id = format!("plink_local_{}", Uuid::new_v4().simple());
+        Ok(HostedLink {
+            url: format!("{}/pay/{}", crate::config::Config::app_base_url(), id),
+            id,
+        })
+    }
+
+    /// Local links stay unpaid until the payment link webhook reports them paid.
+    /// That webhook is still checked against RAZORPAY_WEBHOOK_SECRET, so sign test
+    /// deliveries with it.
+    async fn fetch_payment_link(&self, _link_id: &str) -> Result<HostedLinkState, String> {
+        Ok(HostedLinkState {
+            status: "created".to_string(),
+            payment_id: None,
+        })
+    }
+
+    async fn cancel_payment_link(&self, _link_id: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn refund_payment(&self, _payment_id: &str, _amount: f64) -> Result<String, String> {
+        Ok(format!("rfnd_local_{}", Uuid::new_v4().simple()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::KycStatus;
+
+    fn user() -> User {
+        User {
+            id: None,
+            mobile: "9876543210".to_string(),
+            email: Some("user@example.com".to_string()),
+            name: Some("Test User".to_string()),
+            profile_photo: None,
+            city: None,
+            pincode: None,
+            kyc_status: KycStatus::Approved,
+            is_active: true,
+            fcm_token: None,
+            subscription_id: None,
+            subscription_plan: None,
+            subscription_expires_at: None,
+            reliability: None,
+            last_login_at: DateTime::now(),
+            created_at: DateTime::now(),
+            updated_at: DateTime::now(),
+        }
+    }
+
+    fn mandate(token_id: Option<&str>) -> PaymentMandate {
+        PaymentMandate {
+            gateway: "local".to_string(),
+            customer_id: Some("cust_local_1".to_string()),
+            order_id: Some("order_local_1".to_string()),
+            token_id: token_id.map(str::to_string),
+            status: MandateStatus::Active,
+            created_at: DateTime::now(),
+        }
+    }
+
+    #[rocket::async_test]
+    async fn local_order_is_in_paise() {
+        let order = LocalGateway.create_order(499.5).await.unwrap();
+        assert!(order["id"].as_str().unwrap().starts_with("order_local_"));
+        assert_eq!(order["amount"], 49950);
+        assert_eq!(order["currency"], "INR");
+    }
+
+    #[test]
+    fn local_signature_must_match_order_and_payment() {
+        let signature = LocalGateway::signature("order_1", "pay_1");
+        assert!(LocalGateway.verify_payment("order_1", "pay_1", &signature).is_ok());
+        assert!(LocalGateway.verify_payment("order_2", "pay_1", &signature).is_err());
+        assert!(LocalGateway.verify_payment("order_1", "pay_2", &signature).is_err());
+        assert!(LocalGateway.verify_payment("order_1", "pay_1", "forged").is_err());
+    }
+
+    #[rocket::async_test]
+    async fn local_mandate_is_pending_until_confirmed() {
+        let setup = LocalGateway.create_mandate(&user(), 999.0).await.unwrap();
+        assert_eq!(setup.mandate.gateway, "local");
+        assert_eq!(setup.mandate.status, MandateStatus::Pending);
+        assert!(setup.mandate.token_id.is_none());
+        assert_eq!(setup.checkout["order"]["id"].as_str(), setup.mandate.order_id.as_deref());
+
+        let token = LocalGateway.confirm_mandate(&setup.mandate, "pay_1").await.unwrap();
+        assert_eq!(token, "token_local_pay_1");
+    }
+
+    #[rocket::async_test]
+    async fn local_charges_fail_only_for_failing_tokens() {
+        let charged = LocalGateway.charge_mandate(&mandate(Some("token_local_ok")), &user(), 499.0, "r1").await;
+        assert!(charged.unwrap().starts_with("pay_local_"));
+
+        let declined = LocalGateway.charge_mandate(&mandate(Some("token_fail")), &user(), 499.0, "r2").await;
+        assert!(declined.is_err());
+
+        let no_token = LocalGateway.charge_mandate(&mandate(None), &user(), 499.0, "r3").await;
+        assert!(no_token.is_err());
+    }
+
+    #[rocket::async_test]
+    async fn local_payment_links_stay_unpaid() {
+        let link = LocalGateway
+            .create_payment_link(&user(), 499.0, "ref", "Gold plan", DateTime::now())
+            .await
+            .unwrap();
+        assert!(link.id.starts_with("plink_local_"));
+        assert!(link.url.ends_with(&format!("/pay/{}", link.id)));
+
+        let state = LocalGateway.fetch_payment_link(&link.id).await.unwrap();
+        assert_eq!(state.status, "created");
+        assert!(state.payment_id.is_none());
+        assert!(LocalGateway.cancel_payment_link(&link.id).await.is_ok());
+    }
+
+    #[rocket::async_test]
+    async fn local_refunds_succeed() {
+        let refund = LocalGateway.refund_payment("pay_local_1", 100.0).await.unwrap();
+        assert!(refund.starts_with("rfnd_local_"));
+    }
+}
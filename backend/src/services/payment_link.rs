@@ -0,0 +1,381 @@
+use log::{error, info, warn};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+use crate::models::{
+    PaymentLink, PaymentLinkStatus, Subscription, SubscriptionStatus, SubscriptionType, User,
+    find_plan,
+};
+use crate::routes::notification::create_user_notification;
+use crate::services::payment_gateway::payment_gateway;
+use crate::services::{BillingService, EmailService, Msg91Service};
+use crate::utils::time::{DAY_MILLIS, HOUR_MILLIS, add_millis, format_date};
+
+pub struct PaymentLinkService;
+
+impl PaymentLinkService {
+    /// Create a pending subscription with the next invoice number and a gateway
+    /// payment link for it, then deliver the link over `send_via`
+    pub async fn create(
+        db: &Database,
+        admin_id: ObjectId,
+        user: &User,
+        subscription_type: SubscriptionType,
+        plan_name: &str,
+        send_via: &[String],
+        expires_in_hours: i64,
+    ) -> Result<PaymentLink, String> {
+        let user_id = user.id.ok_or("User missing ID")?;
+        let plan = find_plan(&subscription_type, plan_name)
+            .ok_or_else(|| format!("Unknown plan '{}'", plan_name))?;
+
+        let now = DateTime::now();
+        let expires_at = add_millis(now, expires_in_hours * HOUR_MILLIS);
+        let invoice_number = BillingService::next_invoice_number(db).await?;
+
+        let mut subscription = Subscription {
+            id: None,
+            user_id,
+            subscription_type: subscription_type.clone(),
+            plan_name: plan.name.to_string(),
+            price: plan.price,
+            status: SubscriptionStatus::Pending,
+            starts_at: now,
+            expires_at: add_millis(now, plan.duration_days * DAY_MILLIS),
+            auto_renew: false,
+            payment_id: None,
+            invoice_number: Some(invoice_number),
+            previous_subscription_id: None,
+            scheduled_plan: None,
+            mandate: None,
+            renewal_attempts: 0,
+            next_renewal_at: None,
+            renewal_payment_id: None,
+            grace_until: None,
+            is_trial: false,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let res = db
+            .collection::<Subscription>("subscriptions")
+            .insert_one(&subscription, None)
+            .await
+            .map_err(|e| e.to_string())?;
+        subscription.id = res.inserted_id.as_object_id();
+        let subscription_id = subscription.id.ok_or("Invalid subscription ID")?;
+
+        let gateway = payment_gateway();
+        let hosted = match gateway
+            .create_payment_link(
+                user,
+                plan.price,
+                &format!("invoice_{}", invoice_number),
+                &format!("Mento Services {} plan (invoice #{})", plan.name, invoice_number),
+                expires_at,
+            )
+            .await
+        {
+            Ok(hosted) => hosted,
+            Err(e) => {
+                // Keep the invoice number used, but never leave an orphan pending subscription
+                let _ = db
+                    .collection::<Subscription>("subscriptions")
+                    .update_one(
+                        doc! { "_id": subscription_id },
+                        doc! { "$set": { "status": "cancelled", "updated_at": DateTime::now() } },
+                        None,
+                    )
+                    .await;
+                return Err(e);
+            }
+        };
+
+        let mut link = PaymentLink {
+            id: None,
+            user_id,
+            subscription_id,
+            subscription_type,
+            plan_name: plan.name.to_string(),
+            amount: plan.price,
+            invoice_number,
+            gateway: gateway.name().to_string(),
+            gateway_link_id: hosted.id,
+            short_url: hosted.url,
+            status: PaymentLinkStatus::Created,
+            sent_via: Vec::new(),
+            created_by: admin_id,
+            payment_id: None,
+            paid_at: None,
+            expires_at,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let res = db
+            .collection::<PaymentLink>("payment_links")
+            .insert_one(&link, None)
+            .await
+            .map_err(|e| e.to_string())?;
+        link.id = res.inserted_id.as_object_id();
+
+        link.sent_via = Self::deliver(db, &link, user, send_via).await?;
+
+        info!(
+            "Payment link {} created for user {} (invoice #{})",
+            link.gateway_link_id, user_id, invoice_number
+        );
+
+        Ok(link)
+    }
+
+    /// Send the link over each channel and record the ones that succeeded
+    pub async fn deliver(
+        db: &Database,
+        link: &PaymentLink,
+        user: &User,
+        send_via: &[String],
+    ) -> Result<Vec<String>, String> {
+        let name = user.name.clone().unwrap_or_default();
+        let mut delivered = Vec::new();
+
+        for channel in send_via {
+            let sent = match channel.as_str() {
+                "sms" => match Msg91Service::send_payment_link(
+                    &user.mobile,
+                    &name,
+                    &link.plan_name,
+                    link.amount,
+                    &link.short_url,
+                )
+                .await
+                {
+                    Ok(()) => true,
+                    Err(e) => {
+                        warn!("Failed to send payment link SMS to {}: {}", user.mobile, e);
+                        false
+                    }
+                },
+                "email" => match user.email.as_deref() {
+                    Some(email) => {
+                        EmailService::send_payment_link_email(
+                            email,
+                            &name,
+                            &link.plan_name,
+                            link.amount,
+                            &link.short_url,
+                        )
+                        .await
+                    }
+                    None => false,
+                },
+                _ => false,
+            };
+
+            if sent && !delivered.contains(channel) {
+                delivered.push(channel.clone());
+            }
+        }
+
+        if !delivered.is_empty() {
+            db.collection::<PaymentLink>("payment_links")
+                .update_one(
+                    doc! { "_id": link.id },
+                    doc! {
+                        "$addToSet": { "sent_via": { "$each": delivered.clone() } },
+                        "$set": { "updated_at": DateTime::now() }
+                    },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok(delivered)
+    }
+
+    /// Record payment of a link and activate its subscription. Safe to call more than
+    /// once (webhook and polling may both report the same payment).
+    pub async fn mark_paid(
+        db: &Database,
+        gateway_link_id: &str,
+        payment_id: Option<String>,
+    ) -> Result<Option<Subscription>, String> {
+        let now = DateTime::now();
+
+        let links = db.collection::<PaymentLink>("payment_links");
+        let link = links
+            .find_one_and_update(
+                doc! { "gateway_link_id": gateway_link_id, "status": "created" },
+                doc! {
+                    "$set": {
+                        "status": "paid",
+                        "payment_id": payment_id.clone(),
+                        "paid_at": now,
+                        "updated_at": now
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // A redelivered event finishes a link whose activation failed part way
+        let link = match link {
+            Some(link) => link,
+            None => match links
+                .find_one(doc! { "gateway_link_id": gateway_link_id, "status": "paid" }, None)
+                .await
+                .map_err(|e| e.to_string())?
+            {
+                Some(link) => link,
+                None => return Ok(None), // Unknown or closed
+            },
+        };
+
+        let subscriptions = db.collection::<Subscription>("subscriptions");
+        if let Some(payment_id) = &payment_id {
+            let used = subscriptions
+                .find_one(
+                    doc! { "payment_id": payment_id, "_id": { "$ne": link.subscription_id } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            if used.is_some() {
+                warn!("Payment {} already activated another subscription", payment_id);
+                return Ok(None);
+            }
+        }
+
+        let plan = find_plan(&link.subscription_type, &link.plan_name)
+            .ok_or_else(|| format!("Unknown plan '{}'", link.plan_name))?;
+
+        // The period starts when the customer pays, not when the link was created.
+        // Only a pending subscription is activated, so duplicate deliveries stop here.
+        let activated = subscriptions
+            .update_one(
+                doc! { "_id": link.subscription_id, "status": "pending" },
+                doc! {
+                    "$set": {
+                        "status": "active",
+                        "payment_id": payment_id.clone(),
+                        "starts_at": now,
+                        "expires_at": add_millis(now, plan.duration_days * DAY_MILLIS),
+                        "updated_at": now
+                    }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        if activated.modified_count == 0 {
+            return Ok(None);
+        }
+
+        BillingService::supersede_trials(db, link.user_id, &link.subscription_type, link.subscription_id).await?;
+
+        let subscription = db
+            .collection::<Subscription>("subscriptions")
+            .find_one(doc! { "_id": link.subscription_id }, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Subscription not found")?;
+
+        BillingService::apply_subscription(db, &subscription).await?;
+
+        let _ = create_user_notification(
+            db,
+            link.user_id,
+            "subscription_activated".to_string(),
+            "Subscription Activated".to_string(),
+            format!(
+                "Thanks for your payment! Your {} plan is active until {}.",
+                subscription.plan_name,
+                format_date(subscription.expires_at)
+            ),
+            subscription.id,
+        )
+        .await;
+
+        info!(
+            "Payment link {} paid, activated subscription {}",
+            gateway_link_id, link.subscription_id
+        );
+
+        Ok(Some(subscription))
+    }
+
+    /// Close an unpaid link and cancel its pending subscription
+    pub async fn close(db: &Database, link: &PaymentLink, status: PaymentLinkStatus) -> Result<(), String> {
+        let now = DateTime::now();
+        let status_bson = mongodb::bson::to_bson(&status).map_err(|e| e.to_string())?;
+
+        let closed = db
+            .collection::<PaymentLink>("payment_links")
+            .update_one(
+                doc! { "_id": link.id, "status": "created" },
+                doc! { "$set": { "status": status_bson, "updated_at": now } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if closed.modified_count > 0 {
+            db.collection::<Subscription>("subscriptions")
+                .update_one(
+                    doc! { "_id": link.subscription_id, "status": "pending" },
+                    doc! { "$set": { "status": "cancelled", "updated_at": now } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+
+    /// Ask the gateway for the latest state of an open link and apply it
+    pub async fn refresh(db: &Database, link: &PaymentLink) -> Result<(), String> {
+        if link.status != PaymentLinkStatus::Created {
+            return Ok(());
+        }
+
+        let state = payment_gateway().fetch_payment_link(&link.gateway_link_id).await?;
+
+        match state.status.as_str() {
+            "paid" => {
+                Self::mark_paid(db, &link.gateway_link_id, state.payment_id).await?;
+            }
+            "expired" => Self::close(db, link, PaymentLinkStatus::Expired).await?,
+            "cancelled" => Self::close(db, link, PaymentLinkStatus::Cancelled).await?,
+            _ if link.expires_at <= DateTime::now() => {
+                Self::close(db, link, PaymentLinkStatus::Expired).await?
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Scheduler job: reconcile open links in case a webhook was missed
+    pub async fn sync_open_links(db: &Database) -> Result<(), String> {
+        let open: Vec<PaymentLink> = db
+            .collection::<PaymentLink>("payment_links")
+            .find(doc! { "status": "created" }, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for link in open {
+            if let Err(e) = Self::refresh(db, &link).await {
+                error!("Failed to refresh payment link {}: {}", link.gateway_link_id, e);
+            }
+        }
+
+        Ok(())
+    }
+}
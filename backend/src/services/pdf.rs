@@ -0,0 +1,212 @@
+//! Minimal PDF writer for generated documents. Supports A4 pages with text in the
+//! standard Helvetica fonts and straight lines, which is all our documents need.
+
+pub const PAGE_WIDTH: f64 = 595.0;
+pub const PAGE_HEIGHT: f64 = 842.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+}
+
+pub struct PdfDocument {
+    pages: Vec<Vec<u8>>, // Content stream of each page
+    unsupported: Vec<char>, // Written as '?', in order of first use
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        PdfDocument {
+            pages: vec![Vec::new()],
+            unsupported: Vec::new(),
+        }
+    }
+
+    /// Characters the standard fonts can't show that were written as '?'
+    pub fn unsupported(&self) -> &[char] {
+        &self.unsupported
+    }
+
+    pub fn new_page(&mut self) {
+        self.pages.push(Vec::new());
+    }
+
+    fn current(&mut self) -> &mut Vec<u8> {
+        self.pages.last_mut().expect("document has a page")
+    }
+
+    /// Text with its baseline starting at (x, y), measured from the bottom left
+    pub fn text(&mut self, x: f64, y: f64, size: f64, font: Font, text: &str) {
+        for c in text.chars().filter(|c| !is_supported(*c)) {
+            if !self.unsupported.contains(&c) {
+                self.unsupported.push(c);
+            }
+        }
+        let mut op = format!("BT /{} {:.1} Tf {:.2} {:.2} Td (", font.resource(), size, x, y).into_bytes();
+        op.extend(encode(text));
+        op.extend_from_slice(b") Tj ET\n");
+        self.current().extend(op);
+    }
+
+    /// Text ending at `right`, for amounts in table columns
+    pub fn text_right(&mut self, right: f64, y: f64, size: f64, font: Font, text: &str) {
+        self.text(right - text_width(text, size), y, size, font, text);
+    }
+
+    pub fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, width: f64) {
+        let op = format!("{:.2} w {:.2} {:.2} m {:.2} {:.2} l S\n", width, x1, y1, x2, y2);
+        self.current().extend(op.into_bytes());
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let page_count = self.pages.len();
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+
+        // 1: catalog, 2: page tree, 3-4: fonts, then a page and its content per page
+        let kids: Vec<String> = (0..page_count).map(|i| format!("{} 0 R", 5 + 2 * i)).collect();
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), page_count).into_bytes());
+        for base_font in ["Helvetica", "Helvetica-Bold"] {
+            objects.push(
+                format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                    base_font
+                )
+                .into_bytes(),
+            );
+        }
+
+        for (i, content) in self.pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    PAGE_WIDTH,
+                    PAGE_HEIGHT,
+                    6 + 2 * i
+                )
+                .into_bytes(),
+            );
+
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend_from_slice(content);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut out = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = out.len();
+        out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+        for offset in offsets {
+            out.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+        }
+        out.extend(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref_offset
+            )
+            .into_bytes(),
+        );
+
+        out
+    }
+}
+
+impl Default for PdfDocument {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Approximate Helvetica width of `text` in points
+pub fn text_width(text: &str, size: f64) -> f64 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c {
+            ' ' | '.' | ',' | ':' | ';' | 'i' | 'j' | 'l' | 'I' | '!' | '\'' | '|' | 'f' | 't' => 278,
+            'm' | 'w' | 'M' | 'W' => 833,
+            'A'..='Z' => 667,
+            _ => 556,
+        })
+        .sum();
+
+    units as f64 * size / 1000.0
+}
+
+/// Split `text` into lines no wider than `max_width` points
+pub fn wrap(text: &str, size: f64, max_width: f64) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+
+            if text_width(&candidate, size) > max_width && !line.is_empty() {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Whether `encode` writes `c` as itself or a close stand-in rather than '?'
+fn is_supported(c: char) -> bool {
+    (c as u32) <= 0xFF
+        || matches!(
+            c,
+            '₹' | '\u{2018}' | '\u{2019}' | '\u{201C}' | '\u{201D}' | '\u{2013}' | '\u{2014}' | '\u{2022}'
+        )
+}
+
+/// Escape a string for a PDF literal in WinAnsi encoding
+fn encode(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push(b'\\');
+                out.push(c as u8);
+            }
+            '₹' => out.extend_from_slice(b"Rs."),
+            '\u{2018}' | '\u{2019}' => out.push(b'\''),
+            '\u{201C}' | '\u{201D}' => out.push(b'"'),
+            '\u{2013}' | '\u{2014}' => out.push(b'-'),
+            '\u{2022}' => out.push(0x95),
+            '\n' | '\r' | '\t' => out.push(b' '),
+            c if (c as u32) < 0x20 => {}
+            c if (c as u32) <= 0xFF => out.push(c as u32 as u8),
+            _ => out.push(b'?'),
+        }
+    }
+
+    out
+}
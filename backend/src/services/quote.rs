@@ -0,0 +1,372 @@
+use log::info;
+use mongodb::bson::{doc, DateTime};
+use mongodb::options::{FindOneAndUpdateOptions, FindOneOptions, ReturnDocument};
+use mongodb::Database;
+
+use crate::db::is_duplicate_key;
+use crate::models::{
+    Booking, BookingStatus, BookingStatusChange, LineItemKind, Quote, QuoteLineItem,
+    QuoteLineItemDto, QuoteStatus,
+};
+use crate::routes::notification::create_user_notification;
+use crate::services::pdf::{Font, PAGE_HEIGHT, PAGE_WIDTH, PdfDocument, wrap};
+use crate::services::BookingService;
+use crate::utils::time::{format_date, format_datetime};
+
+const MAX_LINE_ITEMS: usize = 50;
+// Tries at a free quote number when concurrent creates race for the same one
+const NUMBER_ATTEMPTS: usize = 5;
+
+pub struct QuoteService;
+
+impl QuoteService {
+    /// Next value of the quote number sequence
+    pub async fn next_quote_number(db: &Database) -> Result<i64, String> {
+        let last = db
+            .collection::<Quote>("quotes")
+            .find_one(
+                doc! {},
+                FindOneOptions::builder().sort(doc! { "quote_number": -1 }).build(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(last.map(|q| q.quote_number).unwrap_or(0) + 1)
+    }
+
+    /// Insert a new quote under the next quote number. Quotes created at the same
+    /// moment can pick the same number; the unique index rejects all but one and
+    /// the others move on to the following number.
+    pub async fn insert(db: &Database, quote: &mut Quote) -> Result<(), String> {
+        for _ in 0..NUMBER_ATTEMPTS {
+            quote.quote_number = Self::next_quote_number(db).await?;
+            match db.collection::<Quote>("quotes").insert_one(&*quote, None).await {
+                Ok(res) => {
+                    quote.id = res.inserted_id.as_object_id();
+                    return Ok(());
+                }
+                Err(e) if is_duplicate_key(&e) => continue,
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+
+        Err("Could not allocate a quote number".to_string())
+    }
+
+    /// Validate line items and work out their amounts
+    pub fn line_items(items: &[QuoteLineItemDto]) -> Result<Vec<QuoteLineItem>, String> {
+        if items.is_empty() {
+            return Err("A quote needs at least one line item".to_string());
+        }
+        if items.len() > MAX_LINE_ITEMS {
+            return Err(format!("A quote can have at most {} line items", MAX_LINE_ITEMS));
+        }
+
+        items
+            .iter()
+            .map(|item| {
+                let description = item.description.trim();
+                let quantity = item.quantity.unwrap_or(1.0);
+
+                if description.is_empty() {
+                    return Err("Every line item needs a description".to_string());
+                }
+                if !quantity.is_finite() || quantity <= 0.0 {
+                    return Err(format!("Invalid quantity for '{}'", description));
+                }
+                if !item.unit_price.is_finite() || item.unit_price < 0.0 {
+                    return Err(format!("Invalid unit price for '{}'", description));
+                }
+
+                Ok(QuoteLineItem {
+                    kind: item.kind,
+                    description: description.to_string(),
+                    quantity,
+                    unit_price: item.unit_price,
+                    amount: round_paisa(quantity * item.unit_price),
+                })
+            })
+            .collect()
+    }
+
+    /// Subtotal, tax and total for a set of line items
+    pub fn totals(items: &[QuoteLineItem], tax_percent: f64) -> (f64, f64, f64) {
+        let subtotal = round_paisa(items.iter().map(|i| i.amount).sum());
+        let tax = round_paisa(subtotal * tax_percent / 100.0);
+
+        (subtotal, tax, round_paisa(subtotal + tax))
+    }
+
+    /// Accept a sent quote and turn it into a booking request at the customer's chosen time.
+    /// Returns `None` if the quote was no longer open.
+    pub async fn accept(
+        db: &Database,
+        quote: &Quote,
+        scheduled_at: DateTime,
+        duration_minutes: i32,
+    ) -> Result<Option<(Quote, Booking)>, String> {
+        let quote_id = quote.id.ok_or("Quote missing ID")?;
+        let now = DateTime::now();
+
+        let accepted = db
+            .collection::<Quote>("quotes")
+            .find_one_and_update(
+                doc! { "_id": quote_id, "status": "sent", "valid_until": { "$gt": now } },
+                doc! { "$set": { "status": "accepted", "responded_at": now, "updated_at": now } },
+                FindOneAndUpdateOptions::builder()
+                    .return_document(ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let Some(mut accepted) = accepted else {
+            return Ok(None);
+        };
+
+        let booking = Booking {
+            id: None,
+            customer_id: accepted.customer_id,
+            worker_id: accepted.worker_id,
+            worker_user_id: accepted.worker_user_id,
+            category: accepted.category.clone(),
+            subcategory: accepted.subcategory.clone(),
+            address: accepted.address.clone(),
+            location: accepted.location.clone(),
+            scheduled_at,
+            duration_minutes,
+            notes: Some(format!("Quote #{} accepted (total ₹{:.2})", accepted.quote_number, accepted.total)),
+            quote_id: Some(quote_id),
+            service_request_id: None,
+            status: BookingStatus::Requested,
+            status_history: vec![BookingStatusChange {
+                status: BookingStatus::Requested,
+                changed_by: accepted.customer_id,
+                note: Some(format!("From quote #{}", accepted.quote_number)),
+                at: now,
+            }],
+            cancellation_reason: None,
+            cancelled_by: None,
+            late_cancellation: false,
+            no_show: None,
+            completion: None,
+            accepted_at: None,
+            started_at: None,
+            completed_at: None,
+            cancelled_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let booking = match BookingService::create(db, booking).await {
+            Ok(booking) => booking,
+            Err(e) => {
+                // Reopen the quote so the customer can try again
+                let _ = db
+                    .collection::<Quote>("quotes")
+                    .update_one(
+                        doc! { "_id": quote_id },
+                        doc! { "$set": { "status": "sent", "responded_at": null, "updated_at": DateTime::now() } },
+                        None,
+                    )
+                    .await;
+                return Err(e);
+            }
+        };
+
+        db.collection::<Quote>("quotes")
+            .update_one(
+                doc! { "_id": quote_id },
+                doc! { "$set": { "converted_booking_id": booking.id } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        accepted.converted_booking_id = booking.id;
+
+        Self::notify(db, &accepted).await;
+        info!("Quote {} accepted, booking {:?} created", quote_id, booking.id);
+
+        Ok(Some((accepted, booking)))
+    }
+
+    /// Scheduler job: close sent quotes that passed their validity
+    pub async fn expire_stale(db: &Database) -> Result<(), String> {
+        let now = DateTime::now();
+
+        let result = db
+            .collection::<Quote>("quotes")
+            .update_many(
+                doc! { "status": "sent", "valid_until": { "$lte": now } },
+                doc! { "$set": { "status": "expired", "updated_at": now } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if result.modified_count > 0 {
+            info!("Expired {} quote(s)", result.modified_count);
+        }
+
+        Ok(())
+    }
+
+    /// Tell the other party about the quote's current status
+    pub async fn notify(db: &Database, quote: &Quote) {
+        let (recipient, notification_type, title, body) = match quote.status {
+            QuoteStatus::Sent => (
+                quote.customer_id,
+                "quote_received",
+                "New Quote",
+                format!(
+                    "You received quote #{} for {} totalling ₹{:.2}.",
+                    quote.quote_number, quote.subcategory, quote.total
+                ),
+            ),
+            QuoteStatus::Accepted => (
+                quote.worker_user_id,
+                "quote_accepted",
+                "Quote Accepted",
+                format!("Quote #{} was accepted and a booking has been requested.", quote.quote_number),
+            ),
+            QuoteStatus::Rejected => (
+                quote.worker_user_id,
+                "quote_rejected",
+                "Quote Rejected",
+                format!(
+                    "Quote #{} was rejected.{}",
+                    quote.quote_number,
+                    quote
+                        .rejection_reason
+                        .as_deref()
+                        .map(|r| format!(" Reason: {}", r))
+                        .unwrap_or_default()
+                ),
+            ),
+            QuoteStatus::Draft | QuoteStatus::Expired => return,
+        };
+
+        let _ = create_user_notification(
+            db,
+            recipient,
+            notification_type.to_string(),
+            title.to_string(),
+            body,
+            quote.id,
+        )
+        .await;
+    }
+
+    /// Render the quote as a one or more page A4 PDF
+    pub fn render_pdf(quote: &Quote, worker_name: &str, customer_name: &str) -> Vec<u8> {
+        const MARGIN: f64 = 50.0;
+        const RIGHT: f64 = PAGE_WIDTH - MARGIN;
+        const QTY_X: f64 = 360.0;
+        const RATE_X: f64 = 440.0;
+
+        let mut pdf = PdfDocument::new();
+        let mut y = PAGE_HEIGHT - MARGIN - 10.0;
+
+        pdf.text(MARGIN, y, 20.0, Font::Bold, "QUOTATION");
+        pdf.text_right(RIGHT, y, 11.0, Font::Bold, &format!("Quote #{}", quote.quote_number));
+        y -= 18.0;
+        pdf.text_right(RIGHT, y, 9.0, Font::Regular, &format!("Date: {}", format_date(quote.created_at)));
+        if let Some(valid_until) = quote.valid_until {
+            y -= 12.0;
+            pdf.text_right(RIGHT, y, 9.0, Font::Regular, &format!("Valid until: {}", format_datetime(valid_until)));
+        }
+
+        y -= 30.0;
+        pdf.text(MARGIN, y, 10.0, Font::Bold, "From");
+        pdf.text(300.0, y, 10.0, Font::Bold, "For");
+        y -= 14.0;
+        pdf.text(MARGIN, y, 10.0, Font::Regular, worker_name);
+        pdf.text(300.0, y, 10.0, Font::Regular, customer_name);
+        for line in wrap(&quote.address, 9.0, RIGHT - 300.0) {
+            y -= 12.0;
+            pdf.text(300.0, y, 9.0, Font::Regular, &line);
+        }
+
+        y -= 24.0;
+        let service = match &quote.category {
+            Some(category) => format!("Service: {} - {}", category, quote.subcategory),
+            None => format!("Service: {}", quote.subcategory),
+        };
+        pdf.text(MARGIN, y, 10.0, Font::Regular, &service);
+
+        // Line items table
+        y -= 24.0;
+        pdf.text(MARGIN, y, 9.0, Font::Bold, "Description");
+        pdf.text_right(QTY_X, y, 9.0, Font::Bold, "Qty");
+        pdf.text_right(RATE_X, y, 9.0, Font::Bold, "Rate");
+        pdf.text_right(RIGHT, y, 9.0, Font::Bold, "Amount");
+        y -= 6.0;
+        pdf.line(MARGIN, y, RIGHT, y, 0.8);
+
+        for item in &quote.line_items {
+            let kind = match item.kind {
+                LineItemKind::Labour => "Labour",
+                LineItemKind::Materials => "Materials",
+                LineItemKind::Other => "Other",
+            };
+            let lines = wrap(&format!("{}: {}", kind, item.description), 9.0, QTY_X - MARGIN - 50.0);
+
+            if y - 14.0 * (lines.len() as f64) < MARGIN + 80.0 {
+                pdf.new_page();
+                y = PAGE_HEIGHT - MARGIN;
+            }
+
+            y -= 14.0;
+            pdf.text_right(QTY_X, y, 9.0, Font::Regular, &format_quantity(item.quantity));
+            pdf.text_right(RATE_X, y, 9.0, Font::Regular, &format!("{:.2}", item.unit_price));
+            pdf.text_right(RIGHT, y, 9.0, Font::Regular, &format!("{:.2}", item.amount));
+            for (i, line) in lines.iter().enumerate() {
+                if i > 0 {
+                    y -= 11.0;
+                }
+                pdf.text(MARGIN, y, 9.0, Font::Regular, line);
+            }
+        }
+
+        y -= 8.0;
+        pdf.line(MARGIN, y, RIGHT, y, 0.8);
+
+        for (label, amount, font) in [
+            ("Subtotal".to_string(), quote.subtotal, Font::Regular),
+            (format!("Tax ({}%)", format_quantity(quote.tax_percent)), quote.tax_amount, Font::Regular),
+            ("Total (Rs.)".to_string(), quote.total, Font::Bold),
+        ] {
+            y -= 16.0;
+            pdf.text_right(RATE_X, y, 10.0, font, &label);
+            pdf.text_right(RIGHT, y, 10.0, font, &format!("{:.2}", amount));
+        }
+
+        if let Some(notes) = quote.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+            y -= 30.0;
+            pdf.text(MARGIN, y, 10.0, Font::Bold, "Notes");
+            for line in wrap(notes, 9.0, RIGHT - MARGIN) {
+                y -= 12.0;
+                if y < MARGIN {
+                    pdf.new_page();
+                    y = PAGE_HEIGHT - MARGIN;
+                }
+                pdf.text(MARGIN, y, 9.0, Font::Regular, &line);
+            }
+        }
+
+        pdf.text(MARGIN, MARGIN - 20.0, 8.0, Font::Regular, "Generated by Mento Services");
+
+        pdf.to_bytes()
+    }
+}
+
+fn round_paisa(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Quantities without trailing zeros, e.g. "2" or "1.5"
+fn format_quantity(quantity: f64) -> String {
+    let formatted = format!("{:.2}", quantity);
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
@@ -1,5 +1,7 @@
+use hmac::{Hmac, Mac};
 use reqwest::Client;
 use serde_json::json;
+use sha2::Sha256;
 
 pub struct RazorpayService;
 
@@ -32,4 +34,73 @@ impl RazorpayService {
 
         Ok(res.json().await.map_err(|e| e.to_string())?)
     }
+
+    /// POST to a Razorpay API path and return the JSON body, failing on non-2xx responses
+    pub async fn post(path: &str, body: serde_json::Value) -> Result<serde_json::Value, String> {
+        let res = Client::new()
+            .post(format!("https://api.razorpay.com/v1{}", path))
+            .basic_auth(Self::key_id(), Some(Self::key_secret()))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("Razorpay API error ({}): {}", status, text));
+        }
+
+        res.json().await.map_err(|e| e.to_string())
+    }
+
+    /// GET a Razorpay API path and return the JSON body, failing on non-2xx responses
+    pub async fn get(path: &str) -> Result<serde_json::Value, String> {
+        let res = Client::new()
+            .get(format!("https://api.razorpay.com/v1{}", path))
+            .basic_auth(Self::key_id(), Some(Self::key_secret()))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("Razorpay API error ({}): {}", status, text));
+        }
+
+        res.json().await.map_err(|e| e.to_string())
+    }
+
+    /// Verify the checkout signature (HMAC-SHA256 of "order_id|payment_id")
+    pub fn verify_signature(order_id: &str, payment_id: &str, signature: &str) -> Result<(), String> {
+        let secret = std::env::var("RAZORPAY_KEY_SECRET")
+            .map_err(|_| "Missing Razorpay secret".to_string())?;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|_| "Invalid HMAC key".to_string())?;
+        mac.update(format!("{}|{}", order_id, payment_id).as_bytes());
+
+        if hex::encode(mac.finalize().into_bytes()) != signature {
+            return Err("Invalid payment signature".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Verify a webhook delivery (HMAC-SHA256 of the raw body with the webhook secret)
+    pub fn verify_webhook_signature(body: &str, signature: &str) -> Result<(), String> {
+        let secret = std::env::var("RAZORPAY_WEBHOOK_SECRET")
+            .map_err(|_| "Missing Razorpay webhook secret".to_string())?;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .map_err(|_| "Invalid HMAC key".to_string())?;
+        mac.update(body.as_bytes());
+
+        if hex::encode(mac.finalize().into_bytes()) != signature {
+            return Err("Invalid webhook signature".to_string());
+        }
+
+        Ok(())
+    }
 }
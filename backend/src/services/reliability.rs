@@ -0,0 +1,125 @@
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::Database;
+
+use crate::models::{Booking, ReliabilityStats};
+
+/// Completed jobs everyone starts with, so one early slip doesn't sink a new account
+const PRIOR_COMPLETED: f64 = 5.0;
+/// A no-show counts this many times as much as a late cancellation
+const NO_SHOW_WEIGHT: f64 = 2.0;
+/// Weight of the star rating vs. reliability in worker ranking
+const RATING_WEIGHT: f64 = 0.7;
+
+pub struct ReliabilityService;
+
+impl ReliabilityService {
+    pub fn score(completed: i64, late_cancellations: i64, no_shows: i64) -> f64 {
+        let good = completed as f64 + PRIOR_COMPLETED;
+        let bad = late_cancellations as f64 + NO_SHOW_WEIGHT * no_shows as f64;
+        (1000.0 * good / (good + bad)).round() / 10.0
+    }
+
+    async fn count(db: &Database, filter: Document) -> Result<i64, String> {
+        db.collection::<Booking>("bookings")
+            .count_documents(filter, None)
+            .await
+            .map(|n| n as i64)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Recompute both parties' scores after a booking closes
+    pub async fn refresh_for_booking(db: &Database, booking: &Booking) -> Result<(), String> {
+        Self::refresh_worker(db, booking.worker_id, booking.worker_user_id).await?;
+        Self::refresh_customer(db, booking.customer_id).await
+    }
+
+    pub async fn refresh_worker(db: &Database, worker_id: ObjectId, worker_user_id: ObjectId) -> Result<(), String> {
+        // Only jobs the customer signed off on (or let auto-confirm) count in the worker's favour
+        let completed = Self::count(
+            db,
+            doc! {
+                "worker_id": worker_id,
+                "status": "completed",
+                "completion.confirmation": { "$in": ["confirmed", "auto_confirmed"] }
+            },
+        )
+        .await?;
+        let late_cancellations = Self::count(
+            db,
+            doc! {
+                "worker_id": worker_id,
+                "status": "cancelled",
+                "late_cancellation": true,
+                "cancelled_by": worker_user_id
+            },
+        )
+        .await?;
+        let no_shows = Self::count(
+            db,
+            doc! { "worker_id": worker_id, "status": "no_show", "no_show.absent_party": "worker" },
+        )
+        .await?;
+
+        let stats = Self::stats(completed, late_cancellations, no_shows)?;
+        db.collection::<Document>("worker_profiles")
+            .update_one(doc! { "_id": worker_id }, doc! { "$set": { "reliability": stats } }, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn refresh_customer(db: &Database, user_id: ObjectId) -> Result<(), String> {
+        let completed = Self::count(db, doc! { "customer_id": user_id, "status": "completed" }).await?;
+        let late_cancellations = Self::count(
+            db,
+            doc! {
+                "customer_id": user_id,
+                "status": "cancelled",
+                "late_cancellation": true,
+                "cancelled_by": user_id
+            },
+        )
+        .await?;
+        let no_shows = Self::count(
+            db,
+            doc! { "customer_id": user_id, "status": "no_show", "no_show.absent_party": "customer" },
+        )
+        .await?;
+
+        let stats = Self::stats(completed, late_cancellations, no_shows)?;
+        db.collection::<Document>("users")
+            .update_one(doc! { "_id": user_id }, doc! { "$set": { "reliability": stats } }, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    fn stats(completed: i64, late_cancellations: i64, no_shows: i64) -> Result<mongodb::bson::Bson, String> {
+        mongodb::bson::to_bson(&ReliabilityStats {
+            completed,
+            late_cancellations,
+            no_shows,
+            score: Self::score(completed, late_cancellations, no_shows),
+            updated_at: Some(DateTime::now()),
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    /// `$addFields` stage computing `rank_score` (0–100) for worker profiles from
+    /// their rating and reliability. Workers without a track record count as fully
+    /// reliable.
+    pub fn rank_stage() -> Document {
+        doc! {
+            "$addFields": {
+                "rank_score": {
+                    "$add": [
+                        { "$multiply": [{ "$ifNull": ["$rating", 0.0] }, 20.0 * RATING_WEIGHT] },
+                        { "$multiply": [{ "$ifNull": ["$reliability.score", 100.0] }, 1.0 - RATING_WEIGHT] }
+                    ]
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use log::{error, info, warn};
+use chrono::TimeZone;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+use crate::config::Config;
+use crate::models::{PlanChange, Subscription, SubscriptionStatus, User, find_plan};
+use crate::routes::notification::create_user_notification;
+use crate::services::payment_gateway::{PaymentGateway, payment_gateway};
+use crate::services::BillingService;
+use crate::utils::time::{DAY_MILLIS, HOUR_MILLIS, add_millis, format_date};
+
+// How long a renewal attempt holds its subscription before another pass may retry it
+const RENEWAL_LEASE_MILLIS: i64 = HOUR_MILLIS;
+
+/// Gateway receipt for renewing the period that ends at `expires_at`. It is the
+/// same on every attempt, so the gateway can recognise a repeated charge.
+fn renewal_receipt(sub_id: ObjectId, expires_at: DateTime) -> String {
+    let period = chrono::Utc
+        .timestamp_millis_opt(expires_at.timestamp_millis())
+        .single()
+        .map(|d| d.format("%Y%m%d").to_string())
+        .unwrap_or_default();
+    format!("rn_{}_{}", sub_id.to_hex(), period)
+}
+
+pub struct RenewalService;
+
+impl RenewalService {
+    /// One scheduler pass: charge due renewals, open grace periods, expire lapsed subscriptions
+    pub async fn run(db: &Database) -> Result<(), String> {
+        Self::attempt_due_renewals(db).await?;
+        Self::start_grace_periods(db).await?;
+        Self::expire_lapsed(db).await?;
+        Ok(())
+    }
+
+    async fn attempt_due_renewals(db: &Database) -> Result<(), String> {
+        let now = DateTime::now();
+        let window_end = add_millis(now, Config::renewal_lead_days() * DAY_MILLIS);
+
+        let due: Vec<Subscription> = db
+            .collection::<Subscription>("subscriptions")
+            .find(
+                doc! {
+                    "status": "active",
+                    "auto_renew": true,
+                    "mandate.status": "active",
+                    "expires_at": { "$lte": window_end },
+                    "renewal_attempts": { "$not": { "$gte": Config::renewal_max_attempts() } },
+                    "$or": [
+                        { "next_renewal_at": null },
+                        { "next_renewal_at": { "$lte": now } }
+                    ]
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let gateway = payment_gateway();
+        for sub in due {
+            if let Err(e) = Self::renew(db, gateway.as_ref(), &sub).await {
+                error!("Renewal of subscription {:?} failed: {}", sub.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn renew(db: &Database, gateway: &dyn PaymentGateway, due: &Subscription) -> Result<(), String> {
+        let sub_id = due.id.ok_or("Subscription missing ID")?;
+        let now = DateTime::now();
+
+        // Claim the attempt before any money moves, so another scheduler instance or
+        // the next pass can't charge again while this one is in progress
+        let claimed = db
+            .collection::<Subscription>("subscriptions")
+            .find_one_and_update(
+                doc! {
+                    "_id": sub_id,
+                    "status": "active",
+                    "$or": [
+                        { "next_renewal_at": null },
+                        { "next_renewal_at": { "$lte": now } }
+                    ]
+                },
+                doc! { "$set": { "next_renewal_at": add_millis(now, RENEWAL_LEASE_MILLIS), "updated_at": now } },
+                FindOneAndUpdateOptions::builder()
+                    .return_document(ReturnDocument::After)
+                    .build(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        let Some(sub) = claimed.as_ref() else {
+            return Ok(());
+        };
+        let mandate = sub.mandate.as_ref().ok_or("Subscription has no mandate")?;
+
+        // A scheduled downgrade takes effect at renewal
+        let plan_name = sub.scheduled_plan.clone().unwrap_or_else(|| sub.plan_name.clone());
+        let plan = find_plan(&sub.subscription_type, &plan_name)
+            .ok_or_else(|| format!("Unknown plan '{}'", plan_name))?;
+
+        let user = db
+            .collection::<User>("users")
+            .find_one(doc! { "_id": sub.user_id }, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("User not found")?;
+
+        let charge = match sub.renewal_payment_id.clone() {
+            // Charged on an earlier attempt that failed before creating the new period
+            Some(payment_id) => Ok(payment_id),
+            None => {
+                let receipt = renewal_receipt(sub_id, sub.expires_at);
+                gateway.charge_mandate(mandate, &user, plan.price, &receipt).await
+            }
+        };
+
+        match charge {
+            Ok(payment_id) => {
+                db.collection::<Subscription>("subscriptions")
+                    .update_one(
+                        doc! { "_id": sub_id },
+                        doc! { "$set": { "renewal_payment_id": &payment_id } },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                // An earlier attempt may have created the new period before failing
+                let existing = db
+                    .collection::<Subscription>("subscriptions")
+                    .find_one(doc! { "previous_subscription_id": sub_id }, None)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let renewed = match existing {
+                    Some(renewed) => renewed,
+                    None => {
+                        let invoice_number = BillingService::next_invoice_number(db).await?;
+                        // New period starts where the old one ends, or now if we are already in grace
+                        let starts_at = if sub.expires_at > now { sub.expires_at } else { now };
+
+                        let mut renewed = Subscription {
+                            id: None,
+                            user_id: sub.user_id,
+                            subscription_type: sub.subscription_type.clone(),
+                            plan_name: plan.name.to_string(),
+                            price: plan.price,
+                            status: SubscriptionStatus::Active,
+                            starts_at,
+                            expires_at: add_millis(starts_at, plan.duration_days * DAY_MILLIS),
+                            auto_renew: true,
+                            payment_id: Some(payment_id),
+                            invoice_number: Some(invoice_number),
+                            previous_subscription_id: Some(sub_id),
+                            scheduled_plan: None,
+                            mandate: sub.mandate.clone(),
+                            renewal_attempts: 0,
+                            next_renewal_at: None,
+                            renewal_payment_id: None,
+                            grace_until: None,
+                            is_trial: false,
+                            created_at: now,
+                            updated_at: now,
+                        };
+
+                        let res = db
+                            .collection::<Subscription>("subscriptions")
+                            .insert_one(&renewed, None)
+                            .await
+                            .map_err(|e| e.to_string())?;
+                        renewed.id = res.inserted_id.as_object_id();
+                        renewed
+                    }
+                };
+
+                db.collection::<Subscription>("subscriptions")
+                    .update_one(
+                        doc! { "_id": sub_id },
+                        doc! { "$set": { "status": "superseded", "updated_at": now } },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                if sub.scheduled_plan.is_some() {
+                    db.collection::<PlanChange>("plan_changes")
+                        .update_one(
+                            doc! { "from_subscription_id": sub_id, "status": "scheduled" },
+                            doc! {
+                                "$set": {
+                                    "status": "completed",
+                                    "to_subscription_id": renewed.id,
+                                    "effective_at": now,
+                                    "updated_at": now
+                                }
+                            },
+                            None,
+                        )
+                        .await
+                        .map_err(|e| e.to_string())?;
+                }
+
+                BillingService::apply_subscription(db, &renewed).await?;
+
+                let _ = create_user_notification(
+                    db,
+                    sub.user_id,
+                    "subscription_renewed".to_string(),
+                    "Subscription Renewed".to_string(),
+                    format!(
+                        "Your {} plan has been renewed until {}.",
+                        plan.name,
+                        format_date(renewed.expires_at)
+                    ),
+                    renewed.id,
+                )
+                .await;
+
+                info!("Renewed subscription {} as {:?}", sub_id, renewed.id);
+            }
+            Err(e) => {
+                let attempts = sub.renewal_attempts + 1;
+                let next_attempt = add_millis(now, Config::renewal_retry_hours() * HOUR_MILLIS * attempts as i64);
+
+                db.collection::<Subscription>("subscriptions")
+                    .update_one(
+                        doc! { "_id": sub_id },
+                        doc! {
+                            "$set": {
+                                "renewal_attempts": attempts,
+                                "next_renewal_at": next_attempt,
+                                "updated_at": now
+                            }
+                        },
+                        None,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+
+                let body = if attempts < Config::renewal_max_attempts() {
+                    format!(
+                        "We could not charge your saved payment method for the {} plan. We will try again on {}.",
+                        plan.name,
+                        format_date(next_attempt)
+                    )
+                } else {
+                    format!(
+                        "We could not renew your {} plan. Please renew manually to keep your benefits.",
+                        plan.name
+                    )
+                };
+
+                let _ = create_user_notification(
+                    db,
+                    sub.user_id,
+                    "subscription_renewal_failed".to_string(),
+                    "Renewal Payment Failed".to_string(),
+                    body,
+                    Some(sub_id),
+                )
+                .await;
+
+                warn!("Renewal attempt {} for subscription {} failed: {}", attempts, sub_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keep auto-renewing subscriptions active for a grace period after expiry
+    async fn start_grace_periods(db: &Database) -> Result<(), String> {
+        let now = DateTime::now();
+        let grace_until = add_millis(now, Config::renewal_grace_days() * DAY_MILLIS);
+
+        let lapsed: Vec<Subscription> = db
+            .collection::<Subscription>("subscriptions")
+            .find(
+                doc! {
+                    "status": "active",
+                    "auto_renew": true,
+                    "mandate.status": "active",
+                    "expires_at": { "$lte": now },
+                    "grace_until": null
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for sub in lapsed {
+            db.collection::<Subscription>("subscriptions")
+                .update_one(
+                    doc! { "_id": sub.id },
+                    doc! { "$set": { "grace_until": grace_until, "updated_at": now } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+
+            let _ = create_user_notification(
+                db,
+                sub.user_id,
+                "subscription_grace_period".to_string(),
+                "Subscription Payment Due".to_string(),
+                format!(
+                    "Your {} plan has expired but stays active until {} while we retry payment.",
+                    sub.plan_name,
+                    format_date(grace_until)
+                ),
+                sub.id,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// Expire subscriptions past their end date that are not (or no longer) in grace
+    async fn expire_lapsed(db: &Database) -> Result<(), String> {
+        let now = DateTime::now();
+
+        let expired: Vec<Subscription> = db
+            .collection::<Subscription>("subscriptions")
+            .find(
+                doc! {
+                    "status": "active",
+                    "is_trial": { "$ne": true }, // Trials end in EntitlementService::expire_trials
+                    "expires_at": { "$lte": now },
+                    "$or": [
+                        { "auto_renew": false },
+                        { "mandate.status": { "$ne": "active" } },
+                        { "grace_until": { "$lte": now } }
+                    ]
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for sub in expired {
+            db.collection::<Subscription>("subscriptions")
+                .update_one(
+                    doc! { "_id": sub.id, "status": "active" },
+                    doc! { "$set": { "status": "expired", "updated_at": now } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+
+            db.collection::<PlanChange>("plan_changes")
+                .update_many(
+                    doc! { "from_subscription_id": sub.id, "status": "scheduled" },
+                    doc! { "$set": { "status": "cancelled", "updated_at": now } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+
+            BillingService::clear_subscription(db, &sub).await?;
+
+            let _ = create_user_notification(
+                db,
+                sub.user_id,
+                "subscription_expired".to_string(),
+                "Subscription Expired".to_string(),
+                format!("Your {} plan has expired. Subscribe again to restore your benefits.", sub.plan_name),
+                sub.id,
+            )
+            .await;
+
+            info!("Expired subscription {:?}", sub.id);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1025 @@
+//! Resumes for job seeker profiles. Uploaded PDFs are parsed on this server:
+//! fields are guessed from section headings and the usual resume layouts, and
+//! the result is only a draft for the seeker to correct. Profiles can also be
+//! rendered into a resume PDF with one of a few templates.
+
+use chrono::Datelike;
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::models::{
+    Education, JobSeekerProfile, ResumeDraft, ResumeTemplate, User, WorkExperience,
+};
+use crate::services::pdf::{Font, PAGE_HEIGHT, PAGE_WIDTH, PdfDocument, text_width, wrap};
+
+// Larger files are refused rather than parsed
+pub const MAX_RESUME_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_SKILLS: usize = 30;
+const MAX_SKILL_CHARS: usize = 40;
+const MAX_SKILL_WORDS: usize = 4;
+const MAX_HEADLINE_CHARS: usize = 120;
+const MAX_BIO_CHARS: usize = 1000;
+// Lines at the top searched for the name
+const NAME_LINES: usize = 5;
+// Lines with more words than this are prose, not headings or titles
+const MAX_HEADER_WORDS: usize = 12;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+const DEGREE_WORDS: &[&str] = &[
+    "bachelor", "bachelors", "master", "masters", "b.tech", "btech", "b.e", "m.tech", "mtech",
+    "m.e", "b.sc", "bsc", "m.sc", "msc", "b.com", "bcom", "m.com", "mcom", "bca", "mca", "bba",
+    "mba", "b.a", "m.a", "ph.d", "phd", "doctorate", "diploma", "degree", "pgdm", "llb", "mbbs",
+    "iti", "hsc", "ssc", "10th", "12th", "matriculation", "intermediate", "higher secondary",
+    "senior secondary", "secondary school",
+];
+const INSTITUTION_WORDS: &[&str] = &[
+    "university", "college", "institute", "school", "academy", "polytechnic", "iit", "nit",
+    "iiit", "vidyalaya", "campus", "govt", "government",
+];
+const COMPANY_WORDS: &[&str] = &[
+    "ltd", "limited", "pvt", "private", "inc", "llc", "llp", "corp", "corporation", "company",
+    "technologies", "solutions", "services", "systems", "labs", "group", "enterprises",
+];
+const TITLE_WORDS: &[&str] = &[
+    "engineer", "developer", "manager", "intern", "analyst", "designer", "consultant", "lead",
+    "executive", "associate", "officer", "assistant", "architect", "specialist", "administrator",
+    "coordinator", "head", "director", "scientist", "technician", "trainee", "representative",
+    "accountant", "teacher", "supervisor", "operator", "driver", "electrician", "plumber",
+];
+
+/// "Jan 2020 - Present", "03/2018 to 2021", "2015 – 2019"
+static DATE_RANGE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    let month = "jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec";
+    Regex::new(&format!(
+        r"(?i)\b(?:(?P<sm>{month})[a-z]*\.?,?\s*|(?P<sn>\d{{1,2}})\s*[/.-]\s*)?(?P<sy>(?:19|20)\d{{2}})\s*(?:-|–|—|to|till|until)\s*(?:(?:(?P<em>{month})[a-z]*\.?,?\s*|(?P<en>\d{{1,2}})\s*[/.-]\s*)?(?P<ey>(?:19|20)\d{{2}})|(?P<now>present|current|now|date|ongoing))\b"
+    ))
+    .expect("valid date range pattern")
+});
+static YEAR_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:19|20)\d{2}\b").expect("valid year pattern"));
+static GRADE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:cgpa|gpa|percentage|marks|grade|score)\b|%").expect("valid grade pattern")
+});
+static EXPERIENCE_YEARS_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(\d{1,2})\+?\s*(?:years?|yrs?)\b(?:\s+of)?(?:\s+\w+)?\s+experience")
+        .expect("valid experience pattern")
+});
+static LINKEDIN_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:https?://)?(?:[a-z]{2,3}\.)?linkedin\.com/in/[a-z0-9_%-]+")
+        .expect("valid linkedin pattern")
+});
+static PORTFOLIO_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:https?://)?(?:www\.)?(?:github\.com|gitlab\.com|behance\.net|dribbble\.com)/[a-z0-9_.-]+")
+        .expect("valid portfolio pattern")
+});
+// Columns in extracted text come out as runs of spaces
+static GAP_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s{3,}").expect("valid gap pattern"));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Section {
+    Header,
+    Summary,
+    Skills,
+    Education,
+    Experience,
+    Other,
+}
+
+/// Section a heading line starts, if it is one
+fn heading(line: &str) -> Option<Section> {
+    if line.chars().count() > 40 {
+        return None;
+    }
+    let key = line
+        .to_lowercase()
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty() && *w != "and")
+        .collect::<Vec<_>>()
+        .join(" ");
+    let section = match key.as_str() {
+        "summary" | "professional summary" | "profile" | "profile summary" | "professional profile"
+        | "objective" | "career objective" | "about" | "about me" => Section::Summary,
+        "skills" | "key skills" | "technical skills" | "core skills" | "skill set" | "skillset"
+        | "competencies" | "core competencies" | "technologies" | "tools technologies"
+        | "skills tools" | "areas of expertise" | "expertise" => Section::Skills,
+        "education" | "educational qualification" | "educational qualifications"
+        | "academic qualification" | "academic qualifications" | "academic background"
+        | "academics" | "qualifications" | "education training" => Section::Education,
+        "experience" | "work experience" | "professional experience" | "employment"
+        | "employment history" | "work history" | "career history" | "internship"
+        | "internships" | "experience internships" => Section::Experience,
+        "projects" | "academic projects" | "certifications" | "certificates" | "courses"
+        | "achievements" | "awards" | "interests" | "hobbies" | "references"
+        | "personal details" | "personal information" | "contact" | "declaration"
+        | "publications" | "volunteering" | "extracurricular activities" | "details"
+        | "additional details" | "other details" | "additional information" => Section::Other,
+        _ => return None,
+    };
+    Some(section)
+}
+
+fn strip_bullet(line: &str) -> &str {
+    line.trim_start_matches(|c: char| {
+        c.is_whitespace() || matches!(c, '•' | '·' | '●' | '▪' | '◦' | '■' | '-' | '*' | '–' | '>')
+    })
+}
+
+fn is_bullet(line: &str) -> bool {
+    strip_bullet(line).len() < line.len()
+}
+
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Whether `text` contains one of `words` as a whole word (or phrase)
+fn has_word(text: &str, words: &[&str]) -> bool {
+    let lower = text.to_lowercase();
+    let tokens: Vec<&str> = lower
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | '(' | ')' | '|' | ':' | ';' | '/'))
+        .map(|t| t.trim_end_matches("'s").trim_end_matches("’s").trim_end_matches('.'))
+        .collect();
+    words.iter().any(|word| {
+        if word.contains(' ') {
+            lower.contains(word)
+        } else {
+            tokens.contains(word)
+        }
+    })
+}
+
+/// Parts of a line separated by commas, pipes, dashes or column gaps
+fn segments(text: &str) -> Vec<String> {
+    let mut text = GAP_PATTERN.replace_all(text, ",").to_string();
+    for separator in [" | ", " at ", " @ ", " – ", " — ", " - ", "\t"] {
+        text = text.replace(separator, ",");
+    }
+    text.split([',', '|'])
+        .map(|part| {
+            part.trim_matches(|c: char| {
+                c.is_whitespace() || matches!(c, '-' | '–' | '—' | ':' | '(' | '.')
+            })
+        })
+        .filter(|part| part.chars().any(char::is_alphanumeric))
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_contact(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    lower.contains('@')
+        || lower.contains("http")
+        || lower.contains("www.")
+        || lower.contains(".com")
+        || line.chars().filter(char::is_ascii_digit).count() >= 6
+}
+
+/// Two to four capitalised words, as names are set at the top of a resume
+fn looks_like_name(line: &str) -> bool {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let lower = line.to_lowercase();
+    (2..=4).contains(&words.len())
+        && !["resume", "curriculum vitae", "biodata", "bio data"].iter().any(|w| lower.contains(w))
+        && words.iter().all(|word| {
+            word.chars().next().is_some_and(char::is_uppercase)
+                && word.chars().all(|c| c.is_alphabetic() || matches!(c, '.' | '\'' | '-'))
+        })
+}
+
+/// "JOHN DOE" becomes "John Doe"; mixed case is kept as written
+fn tidy_name(name: &str) -> String {
+    if name.chars().any(char::is_lowercase) {
+        return name.to_string();
+    }
+    name.split_whitespace()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_string() + &chars.as_str().to_lowercase())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn with_scheme(url: &str) -> String {
+    if url.to_lowercase().starts_with("http") {
+        url.to_string()
+    } else {
+        format!("https://{}", url)
+    }
+}
+
+fn month_number(name: Option<regex::Match>, number: Option<regex::Match>) -> Option<u32> {
+    if let Some(name) = name {
+        let prefix = name.as_str().to_lowercase();
+        return MONTHS.iter().position(|m| *m == prefix).map(|i| i as u32 + 1);
+    }
+    number
+        .and_then(|n| n.as_str().parse().ok())
+        .filter(|n| (1..=12).contains(n))
+}
+
+/// A month in a resume; the month is unknown when only the year is given
+#[derive(Debug, Clone, Copy)]
+struct YearMonth {
+    year: i32,
+    month: Option<u32>,
+}
+
+impl YearMonth {
+    fn iso_date(&self) -> String {
+        format!("{:04}-{:02}-01", self.year, self.month.unwrap_or(1))
+    }
+
+    fn months(&self) -> i32 {
+        self.year * 12 + self.month.unwrap_or(1) as i32 - 1
+    }
+}
+
+/// Start and end of a date range; no end means it is ongoing
+fn date_range(captures: &Captures) -> (YearMonth, Option<YearMonth>) {
+    let start = YearMonth {
+        year: captures["sy"].parse().unwrap_or_default(),
+        month: month_number(captures.name("sm"), captures.name("sn")),
+    };
+    let end = captures.name("ey").map(|year| YearMonth {
+        year: year.as_str().parse().unwrap_or_default(),
+        month: month_number(captures.name("em"), captures.name("en")),
+    });
+    (start, end)
+}
+
+#[derive(Default)]
+struct EducationEntry {
+    degree: Option<String>,
+    institution: Option<String>,
+    field_of_study: Option<String>,
+    years: Vec<i32>,
+    is_current: bool,
+}
+
+impl EducationEntry {
+    fn is_empty(&self) -> bool {
+        self.degree.is_none() && self.institution.is_none()
+    }
+
+    fn finish(self) -> Option<Education> {
+        if self.is_empty() {
+            return None;
+        }
+        let first = self.years.iter().min().copied();
+        let last = self.years.iter().max().copied();
+        // A single year is when the course ended, unless it is still going
+        let (start_year, end_year) = match (self.years.len(), self.is_current) {
+            (0, _) => (None, None),
+            (1, true) => (first, None),
+            (1, false) => (None, last),
+            (_, true) => (first, None),
+            (_, false) => (first, last),
+        };
+        Some(Education {
+            degree: self.degree.unwrap_or_default(),
+            institution: self.institution.unwrap_or_default(),
+            field_of_study: self.field_of_study,
+            start_year,
+            end_year,
+            is_current: self.is_current,
+        })
+    }
+}
+
+#[derive(Default)]
+struct ExperienceEntry {
+    parts: Vec<String>,
+    start: Option<YearMonth>,
+    end: Option<YearMonth>,
+    is_current: bool,
+    description: Vec<String>,
+}
+
+impl ExperienceEntry {
+    fn is_empty(&self) -> bool {
+        self.parts.is_empty() && self.start.is_none()
+    }
+
+    fn finish(mut self) -> Option<WorkExperience> {
+        // Without dates, a lone line is more likely a stray location than a job
+        if self.parts.is_empty() || (self.start.is_none() && self.parts.len() < 2) {
+            return None;
+        }
+        // Title first: swap when the company was written first
+        if self.parts.len() >= 2 {
+            let first_is_company = has_word(&self.parts[0], COMPANY_WORDS) || has_word(&self.parts[1], TITLE_WORDS);
+            let second_is_company = has_word(&self.parts[1], COMPANY_WORDS) || has_word(&self.parts[0], TITLE_WORDS);
+            if first_is_company && !second_is_company {
+                self.parts.swap(0, 1);
+            }
+        }
+        let mut parts = self.parts.into_iter();
+        Some(WorkExperience {
+            title: parts.next().unwrap_or_default(),
+            company: parts.next().unwrap_or_default(),
+            location: parts.next(),
+            start_date: self.start.map(|s| s.iso_date()),
+            end_date: self.end.map(|e| e.iso_date()),
+            is_current: self.is_current,
+            description: (!self.description.is_empty()).then(|| self.description.join("\n")),
+        })
+    }
+}
+
+// Page layout of generated resumes
+const MARGIN: f64 = 50.0;
+const RIGHT: f64 = PAGE_WIDTH - MARGIN;
+const BULLET_INDENT: f64 = 12.0;
+// Line height as a multiple of the type size
+const LEADING: f64 = 1.35;
+
+/// Type sizes and spacing of a template
+struct Style {
+    centred_header: bool,
+    caps_headings: bool,
+    name_size: f64,
+    heading_size: f64,
+    body_size: f64,
+    section_gap: f64,
+    rule_width: f64,
+}
+
+impl Style {
+    fn of(template: ResumeTemplate) -> Self {
+        match template {
+            ResumeTemplate::Classic => Style {
+                centred_header: true,
+                caps_headings: false,
+                name_size: 22.0,
+                heading_size: 12.0,
+                body_size: 10.0,
+                section_gap: 16.0,
+                rule_width: 0.5,
+            },
+            ResumeTemplate::Modern => Style {
+                centred_header: false,
+                caps_headings: true,
+                name_size: 26.0,
+                heading_size: 11.0,
+                body_size: 10.0,
+                section_gap: 20.0,
+                rule_width: 1.5,
+            },
+            ResumeTemplate::Compact => Style {
+                centred_header: false,
+                caps_headings: true,
+                name_size: 18.0,
+                heading_size: 9.5,
+                body_size: 8.5,
+                section_gap: 10.0,
+                rule_width: 0.5,
+            },
+        }
+    }
+}
+
+/// Writes a resume top to bottom, starting new pages as it runs out of room
+struct ResumeWriter {
+    pdf: PdfDocument,
+    style: Style,
+    y: f64,
+}
+
+impl ResumeWriter {
+    fn new(style: Style) -> Self {
+        ResumeWriter {
+            pdf: PdfDocument::new(),
+            style,
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    /// Start a new page unless `height` still fits on this one
+    fn keep(&mut self, height: f64) {
+        if self.y - height < MARGIN {
+            self.pdf.new_page();
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    /// Move down one line of `size` type
+    fn next_line(&mut self, size: f64) {
+        self.keep(size * LEADING);
+        self.y -= size * LEADING;
+    }
+
+    /// Header text, centred or left-aligned as the template has it
+    fn header(&mut self, size: f64, font: Font, text: &str) {
+        for line in wrap(text, size, RIGHT - MARGIN) {
+            self.next_line(size);
+            let x = if self.style.centred_header {
+                ((PAGE_WIDTH - text_width(&line, size)) / 2.0).max(MARGIN)
+            } else {
+                MARGIN
+            };
+            self.pdf.text(x, self.y, size, font, &line);
+        }
+    }
+
+    fn heading(&mut self, title: &str) {
+        let style = &self.style;
+        let title = if style.caps_headings { title.to_uppercase() } else { title.to_string() };
+        let (gap, heading_size, body_size) = (style.section_gap, style.heading_size, style.body_size);
+        // Keep a heading on the same page as the start of its section
+        self.keep(gap + (heading_size + 3.0 * body_size) * LEADING);
+        self.y -= gap;
+        self.next_line(heading_size);
+        self.pdf.text(MARGIN, self.y, heading_size, Font::Bold, &title);
+        self.y -= 4.0;
+        self.pdf.line(MARGIN, self.y, RIGHT, self.y, self.style.rule_width);
+        self.y -= 2.0;
+    }
+
+    fn paragraph(&mut self, font: Font, text: &str) {
+        let size = self.style.body_size;
+        for line in wrap(text, size, RIGHT - MARGIN) {
+            self.next_line(size);
+            self.pdf.text(MARGIN, self.y, size, font, &line);
+        }
+    }
+
+    fn bullet(&mut self, text: &str) {
+        let size = self.style.body_size;
+        for (i, line) in wrap(text, size, RIGHT - MARGIN - BULLET_INDENT).iter().enumerate() {
+            self.next_line(size);
+            if i == 0 {
+                self.pdf.text(MARGIN + 2.0, self.y, size, Font::Regular, "\u{2022}");
+            }
+            self.pdf.text(MARGIN + BULLET_INDENT, self.y, size, Font::Regular, line);
+        }
+    }
+
+    /// Bold text on the left with dates on the right of its first line
+    fn row(&mut self, left: &str, right: &str) {
+        let size = self.style.body_size;
+        let right_width = if right.is_empty() { 0.0 } else { text_width(right, size) + 12.0 };
+        for (i, line) in wrap(left, size, RIGHT - MARGIN - right_width).iter().enumerate() {
+            self.next_line(size);
+            self.pdf.text(MARGIN, self.y, size, Font::Bold, line);
+            if i == 0 && !right.is_empty() {
+                self.pdf.text_right(RIGHT, self.y, size, Font::Regular, right);
+            }
+        }
+    }
+
+    /// Space between entries of a section
+    fn gap(&mut self) {
+        self.y -= self.style.body_size * 0.6;
+    }
+}
+
+/// "2020-01-01" as "Jan 2020"; anything else is shown as written
+fn display_date(date: &str) -> String {
+    let mut parts = date.trim().split('-');
+    let year = parts.next().filter(|y| y.len() == 4 && y.chars().all(|c| c.is_ascii_digit()));
+    let month = parts
+        .next()
+        .and_then(|m| m.parse::<usize>().ok())
+        .filter(|m| (1..=12).contains(m));
+    match (year, month) {
+        (Some(year), Some(month)) => {
+            let name = MONTHS[month - 1];
+            format!("{}{} {}", name[..1].to_uppercase(), &name[1..], year)
+        }
+        (Some(year), None) => year.to_string(),
+        _ => date.trim().to_string(),
+    }
+}
+
+/// "Jan 2020 - Present", or whichever end is known
+fn period(start: Option<String>, end: Option<String>, is_current: bool) -> String {
+    let end = if is_current { Some("Present".to_string()) } else { end };
+    match (start, end) {
+        (Some(start), Some(end)) => format!("{} - {}", start, end),
+        (Some(date), None) | (None, Some(date)) => date,
+        (None, None) => String::new(),
+    }
+}
+
+fn non_empty(values: &[Option<&str>]) -> Vec<String> {
+    values
+        .iter()
+        .flatten()
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub struct ResumeService;
+
+impl ResumeService {
+    /// Plain text of a PDF. Parsing runs on a blocking thread, and a panic in the
+    /// parser on a malformed file is reported like any other unreadable PDF.
+    pub async fn extract_text(bytes: Vec<u8>) -> Result<String, String> {
+        tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes))
+            .await
+            .map_err(|_| "The PDF could not be read".to_string())?
+            .map_err(|e| format!("The PDF could not be read: {}", e))
+    }
+
+    /// Render a profile as an A4 resume. Contact details come from the user's
+    /// account; empty sections are left out. Fails, listing the characters, when
+    /// the profile has text the standard fonts can't show.
+    pub fn render_pdf(
+        profile: &JobSeekerProfile,
+        user: &User,
+        template: ResumeTemplate,
+    ) -> Result<Vec<u8>, String> {
+        let style = Style::of(template);
+        let (name_size, body_size) = (style.name_size, style.body_size);
+        let mut writer = ResumeWriter::new(style);
+
+        writer.header(name_size, Font::Bold, &profile.full_name);
+        if let Some(headline) = profile.headline.as_deref().filter(|h| !h.trim().is_empty()) {
+            writer.y -= 2.0;
+            writer.header(body_size + 2.0, Font::Regular, headline);
+        }
+        let contact = non_empty(&[
+            Some(user.mobile.as_str()),
+            user.email.as_deref(),
+            user.city.as_deref(),
+            profile.linkedin_url.as_deref(),
+            profile.portfolio_url.as_deref(),
+        ]);
+        if !contact.is_empty() {
+            writer.y -= 2.0;
+            writer.header(body_size, Font::Regular, &contact.join("  |  "));
+        }
+
+        if let Some(bio) = profile.bio.as_deref().filter(|b| !b.trim().is_empty()) {
+            writer.heading("Summary");
+            writer.paragraph(Font::Regular, bio);
+        }
+
+        let skills = non_empty(&profile.skills.iter().map(|s| Some(s.as_str())).collect::<Vec<_>>());
+        if !skills.is_empty() {
+            writer.heading("Skills");
+            writer.paragraph(Font::Regular, &skills.join("  \u{2022}  "));
+        }
+
+        if !profile.work_experience.is_empty() {
+            writer.heading("Work Experience");
+            for (i, job) in profile.work_experience.iter().enumerate() {
+                if i > 0 {
+                    writer.gap();
+                }
+                let dates = period(
+                    job.start_date.as_deref().map(display_date),
+                    job.end_date.as_deref().map(display_date),
+                    job.is_current,
+                );
+                writer.row(&job.title, &dates);
+                let place = non_empty(&[Some(job.company.as_str()), job.location.as_deref()]);
+                if !place.is_empty() {
+                    writer.paragraph(Font::Regular, &place.join(", "));
+                }
+                for line in job.description.as_deref().unwrap_or_default().lines() {
+                    let line = strip_bullet(line).trim();
+                    if !line.is_empty() {
+                        writer.bullet(line);
+                    }
+                }
+            }
+        }
+
+        if !profile.education.is_empty() {
+            writer.heading("Education");
+            for (i, education) in profile.education.iter().enumerate() {
+                if i > 0 {
+                    writer.gap();
+                }
+                let years = period(
+                    education.start_year.map(|y| y.to_string()),
+                    education.end_year.map(|y| y.to_string()),
+                    education.is_current,
+                );
+                let degree = match education.field_of_study.as_deref().filter(|f| !f.trim().is_empty()) {
+                    Some(field) if !education.degree.trim().is_empty() => format!("{} in {}", education.degree, field),
+                    _ => education.degree.clone(),
+                };
+                if degree.trim().is_empty() {
+                    writer.row(&education.institution, &years);
+                } else {
+                    writer.row(&degree, &years);
+                    if !education.institution.trim().is_empty() {
+                        writer.paragraph(Font::Regular, &education.institution);
+                    }
+                }
+            }
+        }
+
+        let mut details = Vec::new();
+        if let Some(years) = profile.experience_years.filter(|y| *y > 0) {
+            details.push(format!(
+                "Total experience: {} year{}",
+                years,
+                if years == 1 { "" } else { "s" }
+            ));
+        }
+        if !profile.preferred_job_types.is_empty() {
+            details.push(format!("Preferred job types: {}", profile.preferred_job_types.join(", ")));
+        }
+        if !profile.preferred_locations.is_empty() {
+            details.push(format!("Preferred locations: {}", profile.preferred_locations.join(", ")));
+        }
+        if profile.willing_to_relocate {
+            details.push("Willing to relocate".to_string());
+        }
+        if !details.is_empty() {
+            writer.heading("Details");
+            for detail in details {
+                writer.paragraph(Font::Regular, &detail);
+            }
+        }
+
+        // The standard fonts only cover Latin-1, so e.g. Devanagari would come out as '?'
+        let unsupported = writer.pdf.unsupported();
+        if !unsupported.is_empty() {
+            return Err(format!(
+                "The resume can't show these characters yet: {}. Please use English text in your profile",
+                unsupported.iter().take(10).map(|c| format!("'{}'", c)).collect::<Vec<_>>().join(", ")
+            ));
+        }
+
+        Ok(writer.pdf.to_bytes())
+    }
+
+    /// Best guess at profile fields from resume text
+    pub fn parse(text: &str) -> ResumeDraft {
+        let mut sections: HashMap<Section, Vec<String>> = HashMap::new();
+        let mut current = Section::Header;
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            // "Skills: Rust, Go" starts a section and has content on the same line
+            let (section, rest) = match line.split_once(':') {
+                Some((label, rest)) if heading(label).is_some() => (heading(label), rest.trim()),
+                _ => (heading(line), ""),
+            };
+            match section {
+                Some(section) => {
+                    current = section;
+                    if !rest.is_empty() {
+                        sections.entry(current).or_default().push(rest.to_string());
+                    }
+                }
+                None => sections.entry(current).or_default().push(line.to_string()),
+            }
+        }
+        let section = |s: Section| sections.get(&s).map(Vec::as_slice).unwrap_or_default();
+
+        let header = section(Section::Header);
+        let name_index = header.iter().take(NAME_LINES).position(|l| looks_like_name(l));
+        let full_name = name_index.map(|i| tidy_name(&header[i]));
+        let headline = name_index
+            .and_then(|i| header.get(i + 1))
+            .filter(|l| !is_contact(l) && l.chars().count() <= MAX_HEADLINE_CHARS)
+            .cloned();
+
+        let summary = section(Section::Summary).join(" ");
+        let bio = (!summary.is_empty()).then(|| summary.chars().take(MAX_BIO_CHARS).collect());
+
+        let work_experience = Self::parse_experience(section(Section::Experience));
+        let experience_years = EXPERIENCE_YEARS_PATTERN
+            .captures(&format!("{} {}", header.join(" "), summary))
+            .and_then(|c| c[1].parse().ok())
+            .or_else(|| Self::total_years(section(Section::Experience)));
+
+        ResumeDraft {
+            full_name,
+            headline,
+            bio,
+            skills: Self::parse_skills(section(Section::Skills)),
+            experience_years,
+            education: Self::parse_education(section(Section::Education)),
+            work_experience,
+            resume_url: None,
+            portfolio_url: PORTFOLIO_PATTERN.find(text).map(|m| with_scheme(m.as_str())),
+            linkedin_url: LINKEDIN_PATTERN.find(text).map(|m| with_scheme(m.as_str())),
+        }
+    }
+
+    fn parse_skills(lines: &[String]) -> Vec<String> {
+        let mut skills: Vec<String> = Vec::new();
+        for line in lines {
+            // "Languages: Rust, Go" lists its skills after the label
+            let line = line.split_once(':').map_or(line.as_str(), |(_, rest)| rest);
+            for item in line.split([',', ';', '|', '•', '·', '●', '▪']) {
+                let skill = strip_bullet(item).trim().trim_end_matches('.');
+                if skill.is_empty()
+                    || skill.chars().count() > MAX_SKILL_CHARS
+                    || word_count(skill) > MAX_SKILL_WORDS
+                    || skills.iter().any(|s| s.eq_ignore_ascii_case(skill))
+                {
+                    continue;
+                }
+                skills.push(skill.to_string());
+                if skills.len() == MAX_SKILLS {
+                    return skills;
+                }
+            }
+        }
+        skills
+    }
+
+    fn parse_education(lines: &[String]) -> Vec<Education> {
+        let mut education = Vec::new();
+        let mut entry = EducationEntry::default();
+        for line in lines {
+            let line = strip_bullet(line);
+            let lower = line.to_lowercase();
+            let mut rest = line.to_string();
+            let mut years = Vec::new();
+            let mut ongoing = lower.contains("pursuing") || lower.contains("expected");
+            if let Some(captures) = DATE_RANGE_PATTERN.captures(line) {
+                let (start, end) = date_range(&captures);
+                years.push(start.year);
+                match end {
+                    Some(end) => years.push(end.year),
+                    None => ongoing = true,
+                }
+                rest = rest.replace(&captures[0], " ");
+            } else {
+                years.extend(YEAR_PATTERN.find_iter(line).filter_map(|m| m.as_str().parse::<i32>().ok()));
+                rest = YEAR_PATTERN.replace_all(&rest, " ").to_string();
+            }
+
+            for part in segments(&rest) {
+                if GRADE_PATTERN.is_match(&part) {
+                    continue;
+                }
+                // "Govt ITI Pune" after a degree is where it was studied
+                let is_institution = has_word(&part, INSTITUTION_WORDS);
+                let is_degree = has_word(&part, DEGREE_WORDS)
+                    && !(is_institution && entry.degree.is_some() && entry.institution.is_none());
+                if is_degree {
+                    if entry.degree.is_some() {
+                        education.extend(std::mem::take(&mut entry).finish());
+                    }
+                    // "B.Tech in Computer Science", "B.Sc (Physics)"
+                    let (degree, field) = match part.split_once(" in ").or_else(|| part.split_once('(')) {
+                        Some((degree, field)) => (degree, Some(field.trim_end_matches(')').trim())),
+                        None => (part.as_str(), None),
+                    };
+                    entry.degree = Some(degree.trim().to_string());
+                    entry.field_of_study = field.filter(|f| !f.is_empty()).map(str::to_string);
+                } else if is_institution {
+                    if entry.institution.is_some() {
+                        education.extend(std::mem::take(&mut entry).finish());
+                    }
+                    entry.institution = Some(part);
+                } else if entry.degree.is_some()
+                    && entry.field_of_study.is_none()
+                    && entry.institution.is_none()
+                    && word_count(&part) <= 5
+                {
+                    entry.field_of_study = Some(part);
+                }
+            }
+            if !entry.is_empty() || !years.is_empty() {
+                entry.years.extend(years);
+                entry.is_current |= ongoing;
+            }
+        }
+        education.extend(entry.finish());
+        education
+    }
+
+    /// Jobs start at a line with a date range or after a job's description.
+    /// Heading lines give title, company and location in that order; bullets
+    /// and long lines are the description.
+    fn parse_experience(lines: &[String]) -> Vec<WorkExperience> {
+        let mut jobs = Vec::new();
+        let mut entry = ExperienceEntry::default();
+        let is_description = |line: &str| is_bullet(line) || word_count(line) > MAX_HEADER_WORDS;
+        for (i, line) in lines.iter().enumerate() {
+            let text = strip_bullet(line);
+            let range = DATE_RANGE_PATTERN.captures(text);
+            if range.is_none() && is_description(line) {
+                if !entry.is_empty() {
+                    entry.description.push(text.to_string());
+                }
+                continue;
+            }
+
+            // After a dated title and company, a plain line is the location unless
+            // more dates follow before the description, making it the next job
+            let dates_follow = || {
+                lines[i + 1..]
+                    .iter()
+                    .take_while(|l| !is_description(l))
+                    .any(|l| DATE_RANGE_PATTERN.is_match(l))
+            };
+            let new_job = !entry.description.is_empty()
+                || (entry.start.is_some()
+                    && (range.is_some() || entry.parts.len() >= 3 || (entry.parts.len() == 2 && dates_follow())));
+            if new_job {
+                jobs.extend(std::mem::take(&mut entry).finish());
+            }
+            let mut rest = text.to_string();
+            if let Some(captures) = range {
+                let (start, end) = date_range(&captures);
+                entry.start = Some(start);
+                entry.end = end;
+                entry.is_current = end.is_none();
+                rest = rest.replace(&captures[0], " ");
+            }
+            entry.parts.extend(segments(&rest));
+        }
+        jobs.extend(entry.finish());
+        jobs
+    }
+
+    /// Whole years across the dated jobs, counting ongoing ones up to now
+    fn total_years(lines: &[String]) -> Option<i32> {
+        let today = chrono::Utc::now();
+        let now = YearMonth {
+            year: today.year(),
+            month: Some(today.month()),
+        };
+        let months: i32 = lines
+            .iter()
+            .filter_map(|line| DATE_RANGE_PATTERN.captures(line))
+            .map(|captures| {
+                let (start, end) = date_range(&captures);
+                (end.unwrap_or(now).months() - start.months()).max(0)
+            })
+            .sum();
+        (months >= 12).then_some(months / 12)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "
+        PRIYA SHARMA
+        Senior Backend Developer
+        priya.sharma@example.com | +91 98765 43210 | linkedin.com/in/priya-sharma
+        github.com/priyasharma
+
+        PROFESSIONAL SUMMARY
+        Backend developer with 6+ years of experience building payment systems.
+
+        Technical Skills
+        Languages: Rust, Go, Python
+        • PostgreSQL • MongoDB • Docker
+
+        WORK EXPERIENCE
+        Senior Software Engineer
+        Acme Payments Pvt Ltd
+        Jan 2021 - Present
+        Bengaluru
+        • Led the settlement service rewrite
+        • Cut payout latency by 40%
+        Software Engineer    Globex Technologies    06/2018 to 12/2020
+        - Built the merchant onboarding APIs
+
+        Education
+        B.Tech in Computer Science    2014 - 2018
+        National Institute of Technology, Trichy
+        CGPA: 8.4 / 10
+
+        Hobbies
+        Chess, trekking
+    ";
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect()
+    }
+
+    #[test]
+    fn heading_recognises_section_titles() {
+        assert_eq!(heading("WORK EXPERIENCE"), Some(Section::Experience));
+        assert_eq!(heading("Skills & Tools"), Some(Section::Skills));
+        assert_eq!(heading("Education and Training"), Some(Section::Education));
+        assert_eq!(heading("Career Objective:"), Some(Section::Summary));
+        assert_eq!(heading("Hobbies"), Some(Section::Other));
+        assert_eq!(heading("Built the merchant onboarding APIs"), None);
+        assert_eq!(heading(&format!("Experience {}", "x".repeat(40))), None);
+    }
+
+    #[test]
+    fn segments_split_on_separators_and_column_gaps() {
+        assert_eq!(
+            segments("Software Engineer    Globex | Pune - India"),
+            vec!["Software Engineer", "Globex", "Pune", "India"]
+        );
+        assert_eq!(segments("Developer at Acme Corp"), vec!["Developer", "Acme Corp"]);
+        assert!(segments(" -- | ").is_empty());
+    }
+
+    #[test]
+    fn date_range_pattern_reads_common_formats() {
+        let captures = DATE_RANGE_PATTERN.captures("Jan 2020 - Present").unwrap();
+        let (start, end) = date_range(&captures);
+        assert_eq!(start.iso_date(), "2020-01-01");
+        assert!(end.is_none());
+
+        let captures = DATE_RANGE_PATTERN.captures("03/2018 to 11/2021").unwrap();
+        let (start, end) = date_range(&captures);
+        assert_eq!(start.iso_date(), "2018-03-01");
+        assert_eq!(end.unwrap().iso_date(), "2021-11-01");
+
+        let captures = DATE_RANGE_PATTERN.captures("September, 2015 – 2019").unwrap();
+        let (start, end) = date_range(&captures);
+        assert_eq!((start.year, start.month), (2015, Some(9)));
+        assert_eq!((end.unwrap().year, end.unwrap().month), (2019, None));
+
+        assert!(DATE_RANGE_PATTERN.captures("Graduated in 2019").is_none());
+    }
+
+    #[test]
+    fn experience_years_pattern_reads_stated_experience() {
+        let years = |text: &str| EXPERIENCE_YEARS_PATTERN.captures(text).map(|c| c[1].to_string());
+        assert_eq!(years("6+ years of experience in backend").as_deref(), Some("6"));
+        assert_eq!(years("3 yrs experience").as_deref(), Some("3"));
+        assert_eq!(years("10 years of industry experience").as_deref(), Some("10"));
+        assert_eq!(years("Worked for years"), None);
+    }
+
+    #[test]
+    fn parse_splits_text_into_sections() {
+        let draft = ResumeService::parse(SAMPLE);
+
+        assert_eq!(draft.full_name.as_deref(), Some("Priya Sharma"));
+        assert_eq!(draft.headline.as_deref(), Some("Senior Backend Developer"));
+        assert_eq!(
+            draft.bio.as_deref(),
+            Some("Backend developer with 6+ years of experience building payment systems.")
+        );
+        assert_eq!(draft.experience_years, Some(6));
+        assert_eq!(draft.linkedin_url.as_deref(), Some("https://linkedin.com/in/priya-sharma"));
+        assert_eq!(draft.portfolio_url.as_deref(), Some("https://github.com/priyasharma"));
+        // The hobbies section is not mistaken for skills
+        assert_eq!(draft.skills, vec!["Rust", "Go", "Python", "PostgreSQL", "MongoDB", "Docker"]);
+    }
+
+    #[test]
+    fn parse_reads_work_experience() {
+        let jobs = ResumeService::parse(SAMPLE).work_experience;
+        assert_eq!(jobs.len(), 2);
+
+        assert_eq!(jobs[0].title, "Senior Software Engineer");
+        assert_eq!(jobs[0].company, "Acme Payments Pvt Ltd");
+        assert_eq!(jobs[0].location.as_deref(), Some("Bengaluru"));
+        assert_eq!(jobs[0].start_date.as_deref(), Some("2021-01-01"));
+        assert!(jobs[0].is_current && jobs[0].end_date.is_none());
+        assert_eq!(
+            jobs[0].description.as_deref(),
+            Some("Led the settlement service rewrite\nCut payout latency by 40%")
+        );
+
+        assert_eq!(jobs[1].title, "Software Engineer");
+        assert_eq!(jobs[1].company, "Globex Technologies");
+        assert_eq!(jobs[1].start_date.as_deref(), Some("2018-06-01"));
+        assert_eq!(jobs[1].end_date.as_deref(), Some("2020-12-01"));
+        assert!(!jobs[1].is_current);
+    }
+
+    #[test]
+    fn parse_reads_education() {
+        let education = ResumeService::parse(SAMPLE).education;
+        assert_eq!(education.len(), 1);
+
+        assert_eq!(education[0].degree, "B.Tech");
+        assert_eq!(education[0].field_of_study.as_deref(), Some("Computer Science"));
+        // The city after the comma is its own segment and isn't kept
+        assert_eq!(education[0].institution, "National Institute of Technology");
+        assert_eq!((education[0].start_year, education[0].end_year), (Some(2014), Some(2018)));
+    }
+
+    #[test]
+    fn parse_education_puts_institution_after_degree() {
+        let education = ResumeService::parse_education(&lines("ITI Electrician\nGovt ITI Pune\n2019"));
+        assert_eq!(education.len(), 1);
+        assert_eq!(education[0].institution, "Govt ITI Pune");
+        assert_eq!(education[0].end_year, Some(2019));
+    }
+
+    #[test]
+    fn parse_skills_handles_inline_heading() {
+        let draft = ResumeService::parse("Ravi Kumar\nSkills: Wiring, Plumbing, wiring");
+        assert_eq!(draft.skills, vec!["Wiring", "Plumbing"]);
+    }
+
+    #[test]
+    fn total_years_adds_up_dated_jobs() {
+        let years = ResumeService::total_years(&lines("Jan 2015 - Dec 2017\n2018 - 2020"));
+        assert_eq!(years, Some(4));
+        assert_eq!(ResumeService::total_years(&lines("Mar 2020 - Aug 2020")), None);
+    }
+}
@@ -0,0 +1,78 @@
+use log::{error, info};
+use rocket::fairing::AdHoc;
+use std::time::Duration;
+
+use crate::db::DbConn;
+use crate::services::{
+    BookingService, DisputeService, EntitlementService, InterviewService, JobAlertService,
+    JobPostService, PaymentLinkService, QuoteService, RenewalService, ServiceRequestService,
+};
+
+/// Background jobs run on a fixed interval once Rocket has launched
+pub fn init() -> AdHoc {
+    AdHoc::on_liftoff("Scheduler", |rocket| {
+        Box::pin(async move {
+            let Some(db) = rocket.state::<DbConn>().cloned() else {
+                error!("✗ Scheduler not started: database unavailable");
+                return;
+            };
+
+            let interval_secs = crate::config::Config::scheduler_interval_secs();
+            info!("✓ Scheduler running every {}s", interval_secs);
+
+            rocket::tokio::spawn(async move {
+                let mut interval = rocket::tokio::time::interval(Duration::from_secs(interval_secs));
+                loop {
+                    interval.tick().await;
+                    run_jobs(&db).await;
+                }
+            });
+        })
+    })
+}
+
+async fn run_jobs(db: &DbConn) {
+    if let Err(e) = RenewalService::run(db).await {
+        error!("Subscription renewal job failed: {}", e);
+    }
+
+    if let Err(e) = EntitlementService::expire_trials(db).await {
+        error!("Trial expiry job failed: {}", e);
+    }
+
+    if let Err(e) = PaymentLinkService::sync_open_links(db).await {
+        error!("Payment link sync job failed: {}", e);
+    }
+
+    if let Err(e) = QuoteService::expire_stale(db).await {
+        error!("Quote expiry job failed: {}", e);
+    }
+
+    if let Err(e) = ServiceRequestService::run(db).await {
+        error!("Service request job failed: {}", e);
+    }
+
+    if let Err(e) = DisputeService::check_sla(db).await {
+        error!("Dispute SLA job failed: {}", e);
+    }
+
+    if let Err(e) = BookingService::auto_confirm_completions(db).await {
+        error!("Completion auto-confirm job failed: {}", e);
+    }
+
+    if let Err(e) = InterviewService::send_reminders(db).await {
+        error!("Interview reminder job failed: {}", e);
+    }
+
+    if let Err(e) = JobPostService::send_expiry_reminders(db).await {
+        error!("Job expiry reminder job failed: {}", e);
+    }
+
+    if let Err(e) = JobPostService::expire_posts(db).await {
+        error!("Job expiry job failed: {}", e);
+    }
+
+    if let Err(e) = JobAlertService::send_alerts(db).await {
+        error!("Job alert job failed: {}", e);
+    }
+}
@@ -0,0 +1,347 @@
+use log::{error, info};
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::UpdateOptions;
+use mongodb::Database;
+use rocket::futures::TryStreamExt;
+
+use crate::models::{
+    Booking, BookingStatus, BookingStatusChange, PLANS, RequestMatch, ServiceOffer, ServiceRequest,
+    SubscriptionType, find_plan,
+};
+use crate::routes::notification::create_user_notification;
+use crate::services::{BookingService, MatchingService};
+use crate::utils::time::{MINUTE_MILLIS, add_millis};
+
+/// Most workers a single request is offered to
+const MAX_MATCHES: i64 = 100;
+
+pub struct ServiceRequestService;
+
+impl ServiceRequestService {
+    /// Delay before a worker on `plan` can see new requests: the top worker tier
+    /// immediately, then one more head start for each tier below it. Workers
+    /// without a plan count as tier 0.
+    pub fn access_delay_millis(plan: &str) -> i64 {
+        let head_start = crate::config::Config::service_request_early_access_minutes() * MINUTE_MILLIS;
+        let top_tier = PLANS
+            .iter()
+            .filter(|p| p.subscription_type == SubscriptionType::Worker)
+            .map(|p| p.tier)
+            .max()
+            .unwrap_or(0);
+        let tier = find_plan(&SubscriptionType::Worker, plan).map(|p| p.tier).unwrap_or(0);
+
+        i64::from(top_tier - tier) * head_start
+    }
+
+    /// Verified, available workers offering the service within the request's radius
+    pub async fn match_workers(db: &Database, request: &ServiceRequest) -> Result<Vec<RequestMatch>, String> {
+        let [longitude, latitude] = request.location.coordinates;
+
+        let workers = MatchingService::nearby_workers(
+            db,
+            longitude,
+            latitude,
+            request.radius_km * 1000.0,
+            doc! {
+                "is_verified": true,
+                "is_available": true,
+                "subcategories": &request.subcategory,
+                "user_id": { "$ne": request.customer_id }
+            },
+            MAX_MATCHES,
+        )
+        .await?;
+
+        Ok(workers
+            .iter()
+            .filter_map(|w| {
+                let plan = w.get_str("subscription_plan").unwrap_or("none");
+                Some(RequestMatch {
+                    worker_id: w.get_object_id("_id").ok()?,
+                    worker_user_id: w.get_object_id("user_id").ok()?,
+                    distance_m: w.get_f64("distance").unwrap_or_default().round(),
+                    access_at: add_millis(request.created_at, Self::access_delay_millis(plan)),
+                    notified_at: None,
+                })
+            })
+            .collect())
+    }
+
+    /// Match workers, save the request and notify those with immediate access
+    pub async fn create(db: &Database, mut request: ServiceRequest) -> Result<ServiceRequest, String> {
+        request.matches = Self::match_workers(db, &request).await?;
+
+        let res = db
+            .collection::<ServiceRequest>("service_requests")
+            .insert_one(&request, None)
+            .await
+            .map_err(|e| e.to_string())?;
+        request.id = res.inserted_id.as_object_id();
+
+        Self::notify_due(db, &request).await?;
+        info!(
+            "Service request {:?} posted, matched {} worker(s)",
+            request.id,
+            request.matches.len()
+        );
+
+        Ok(request)
+    }
+
+    /// Notify matched workers whose access time has come and mark them notified
+    async fn notify_due(db: &Database, request: &ServiceRequest) -> Result<(), String> {
+        let now = DateTime::now();
+        let due: Vec<&RequestMatch> = request
+            .matches
+            .iter()
+            .filter(|m| m.notified_at.is_none() && m.access_at <= now)
+            .collect();
+
+        if due.is_empty() {
+            return Ok(());
+        }
+
+        let body = match (request.budget_min, request.budget_max) {
+            (_, Some(max)) => format!("New {} request near you (budget up to ₹{:.0}).", request.subcategory, max),
+            _ => format!("New {} request near you. Send your offer!", request.subcategory),
+        };
+
+        for m in &due {
+            let _ = create_user_notification(
+                db,
+                m.worker_user_id,
+                "service_request_new".to_string(),
+                "New Service Request".to_string(),
+                body.clone(),
+                request.id,
+            )
+            .await;
+        }
+
+        let notified: Vec<ObjectId> = due.iter().map(|m| m.worker_id).collect();
+        db.collection::<ServiceRequest>("service_requests")
+            .update_one(
+                doc! { "_id": request.id },
+                doc! { "$set": { "matches.$[m].notified_at": now } },
+                UpdateOptions::builder()
+                    .array_filters(vec![doc! { "m.worker_id": { "$in": notified } }])
+                    .build(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Scheduler job: open the next plan tier's access on open requests, and close
+    /// requests that passed their expiry
+    pub async fn run(db: &Database) -> Result<(), String> {
+        let now = DateTime::now();
+
+        let due: Vec<ServiceRequest> = db
+            .collection::<ServiceRequest>("service_requests")
+            .find(
+                doc! {
+                    "status": "open",
+                    "expires_at": { "$gt": now },
+                    "matches": { "$elemMatch": { "notified_at": null, "access_at": { "$lte": now } } }
+                },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for request in &due {
+            if let Err(e) = Self::notify_due(db, request).await {
+                error!("Failed to notify workers of service request {:?}: {}", request.id, e);
+            }
+        }
+
+        let expired: Vec<ServiceRequest> = db
+            .collection::<ServiceRequest>("service_requests")
+            .find(doc! { "status": "open", "expires_at": { "$lte": now } }, None)
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for request in expired {
+            let closed = db
+                .collection::<ServiceRequest>("service_requests")
+                .update_one(
+                    doc! { "_id": request.id, "status": "open" },
+                    doc! { "$set": { "status": "expired", "updated_at": now } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+
+            if closed.modified_count > 0 {
+                Self::decline_pending_offers(db, request.id, None).await?;
+                let _ = create_user_notification(
+                    db,
+                    request.customer_id,
+                    "service_request_expired".to_string(),
+                    "Service Request Expired".to_string(),
+                    format!("Your {} request expired after {} offer(s).", request.subcategory, request.offers_count),
+                    request.id,
+                )
+                .await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decline the request's pending offers except `keep`, telling those workers
+    pub async fn decline_pending_offers(
+        db: &Database,
+        request_id: Option<ObjectId>,
+        keep: Option<ObjectId>,
+    ) -> Result<(), String> {
+        let filter = doc! { "request_id": request_id, "status": "pending", "_id": { "$ne": keep } };
+
+        let declined: Vec<ServiceOffer> = db
+            .collection::<ServiceOffer>("service_offers")
+            .find(filter.clone(), None)
+            .await
+            .map_err(|e| e.to_string())?
+            .try_collect()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        db.collection::<ServiceOffer>("service_offers")
+            .update_many(
+                filter,
+                doc! { "$set": { "status": "declined", "updated_at": DateTime::now() } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for offer in declined {
+            let _ = create_user_notification(
+                db,
+                offer.worker_user_id,
+                "service_offer_declined".to_string(),
+                "Offer Not Selected".to_string(),
+                "The customer has closed a request you made an offer on.".to_string(),
+                request_id,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// Assign the request to `offer`, decline the rest and create an accepted booking
+    /// at the offered time. Returns `None` if the request or offer was no longer open.
+    pub async fn accept_offer(
+        db: &Database,
+        request: &ServiceRequest,
+        offer: &ServiceOffer,
+    ) -> Result<Option<Booking>, String> {
+        let now = DateTime::now();
+
+        let assigned = db
+            .collection::<ServiceRequest>("service_requests")
+            .update_one(
+                doc! { "_id": request.id, "status": "open" },
+                doc! { "$set": { "status": "assigned", "accepted_offer_id": offer.id, "updated_at": now } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        if assigned.modified_count == 0 {
+            return Ok(None);
+        }
+
+        let accepted = db
+            .collection::<ServiceOffer>("service_offers")
+            .update_one(
+                doc! { "_id": offer.id, "status": "pending" },
+                doc! { "$set": { "status": "accepted", "updated_at": now } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        if accepted.modified_count == 0 {
+            // Withdrawn in the meantime; reopen the request
+            db.collection::<ServiceRequest>("service_requests")
+                .update_one(
+                    doc! { "_id": request.id },
+                    doc! { "$set": { "status": "open", "accepted_offer_id": null, "updated_at": now } },
+                    None,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            return Ok(None);
+        }
+
+        Self::decline_pending_offers(db, request.id, offer.id).await?;
+
+        let booking = Booking {
+            id: None,
+            customer_id: request.customer_id,
+            worker_id: offer.worker_id,
+            worker_user_id: offer.worker_user_id,
+            category: request.category.clone(),
+            subcategory: request.subcategory.clone(),
+            address: request.address.clone(),
+            location: request.location.clone(),
+            scheduled_at: offer.scheduled_at,
+            duration_minutes: offer.duration_minutes,
+            notes: Some(format!("{}\nAgreed price: ₹{:.2}", request.description, offer.price)),
+            quote_id: None,
+            service_request_id: request.id,
+            status: BookingStatus::Accepted,
+            status_history: vec![BookingStatusChange {
+                status: BookingStatus::Accepted,
+                changed_by: request.customer_id,
+                note: Some("Offer accepted".to_string()),
+                at: now,
+            }],
+            cancellation_reason: None,
+            cancelled_by: None,
+            late_cancellation: false,
+            no_show: None,
+            completion: None,
+            accepted_at: Some(now),
+            started_at: None,
+            completed_at: None,
+            cancelled_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        let booking = BookingService::create(db, booking).await?;
+
+        db.collection::<ServiceRequest>("service_requests")
+            .update_one(
+                doc! { "_id": request.id },
+                doc! { "$set": { "booking_id": booking.id } },
+                None,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let _ = create_user_notification(
+            db,
+            offer.worker_user_id,
+            "service_offer_accepted".to_string(),
+            "Offer Accepted".to_string(),
+            format!("Your offer for the {} request was accepted.", request.subcategory),
+            booking.id,
+        )
+        .await;
+
+        info!("Service request {:?} assigned to offer {:?}", request.id, offer.id);
+
+        Ok(Some(booking))
+    }
+}
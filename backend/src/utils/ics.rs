@@ -0,0 +1,191 @@
+use chrono::{TimeZone, Utc};
+use mongodb::bson::DateTime;
+
+/// iCalendar METHOD of an invite: a new or updated event, or its cancellation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IcsMethod {
+    Request,
+    Cancel,
+}
+
+impl IcsMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IcsMethod::Request => "REQUEST",
+            IcsMethod::Cancel => "CANCEL",
+        }
+    }
+}
+
+/// A single calendar event, rendered as an RFC 5545 `.ics` file
+pub struct CalendarEvent<'a> {
+    pub uid: &'a str,
+    pub sequence: i32, // Must go up whenever the event changes, so clients replace their copy
+    pub start: DateTime,
+    pub end: DateTime,
+    pub summary: &'a str,
+    pub description: &'a str,
+    pub location: Option<&'a str>,
+    pub organizer_email: &'a str,
+    pub attendee_emails: Vec<&'a str>,
+}
+
+impl CalendarEvent<'_> {
+    pub fn to_ics(&self, method: IcsMethod) -> String {
+        let status = match method {
+            IcsMethod::Request => "CONFIRMED",
+            IcsMethod::Cancel => "CANCELLED",
+        };
+
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//Mento Services//Interviews//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+            format!("METHOD:{}", method.as_str()),
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", self.uid),
+            format!("SEQUENCE:{}", self.sequence),
+            format!("DTSTAMP:{}", utc_stamp(DateTime::now())),
+            format!("DTSTART:{}", utc_stamp(self.start)),
+            format!("DTEND:{}", utc_stamp(self.end)),
+            format!("SUMMARY:{}", escape(self.summary)),
+            format!("DESCRIPTION:{}", escape(self.description)),
+        ];
+        if let Some(location) = self.location {
+            lines.push(format!("LOCATION:{}", escape(location)));
+        }
+        lines.push(format!("ORGANIZER:mailto:{}", self.organizer_email));
+        for attendee in &self.attendee_emails {
+            lines.push(format!("ATTENDEE;ROLE=REQ-PARTICIPANT;RSVP=TRUE:mailto:{}", attendee));
+        }
+        lines.push(format!("STATUS:{}", status));
+        lines.push("END:VEVENT".to_string());
+        lines.push("END:VCALENDAR".to_string());
+
+        lines.iter().map(|line| fold(line)).collect::<Vec<_>>().join("\r\n") + "\r\n"
+    }
+}
+
+/// UTC timestamp in iCalendar form, e.g. "20250314T050000Z"
+fn utc_stamp(date: DateTime) -> String {
+    Utc.timestamp_millis_opt(date.timestamp_millis())
+        .single()
+        .map(|d| d.format("%Y%m%dT%H%M%SZ").to_string())
+        .unwrap_or_default()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Lines longer than 75 octets continue on the next line after a space
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event<'a>(summary: &'a str, location: Option<&'a str>) -> CalendarEvent<'a> {
+        CalendarEvent {
+            uid: "interview-1@mento",
+            sequence: 2,
+            start: DateTime::from_millis(1_741_928_400_000), // 2025-03-14 05:00 UTC
+            end: DateTime::from_millis(1_741_932_000_000),
+            summary,
+            description: "Round 1",
+            location,
+            organizer_email: "hr@example.com",
+            attendee_emails: vec!["seeker@example.com"],
+        }
+    }
+
+    #[test]
+    fn escape_handles_special_characters() {
+        assert_eq!(escape(r"a,b;c\d"), r"a\,b\;c\\d");
+        assert_eq!(escape("line one\r\nline two\nthree"), r"line one\nline two\nthree");
+        assert_eq!(escape("plain"), "plain");
+    }
+
+    #[test]
+    fn fold_keeps_short_lines() {
+        let line = "x".repeat(75);
+        assert_eq!(fold(&line), line);
+    }
+
+    #[test]
+    fn fold_splits_at_75_octets() {
+        let line = "x".repeat(160);
+        let folded = fold(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].len(), 75);
+        assert!(parts[1].starts_with(' ') && parts[1].len() == 75);
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn fold_does_not_split_multibyte_characters() {
+        let line = "é".repeat(50); // 100 octets
+        let folded = fold(&line);
+
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= 75);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn to_ics_renders_request() {
+        let ics = event("Interview: Sales, Pune", Some("Office; 2nd floor")).to_ics(IcsMethod::Request);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("\r\nMETHOD:REQUEST\r\n"));
+        assert!(ics.contains("\r\nUID:interview-1@mento\r\n"));
+        assert!(ics.contains("\r\nSEQUENCE:2\r\n"));
+        assert!(ics.contains("\r\nDTSTART:20250314T050000Z\r\n"));
+        assert!(ics.contains("\r\nDTEND:20250314T060000Z\r\n"));
+        assert!(ics.contains("\r\nSUMMARY:Interview: Sales\\, Pune\r\n"));
+        assert!(ics.contains("\r\nLOCATION:Office\\; 2nd floor\r\n"));
+        assert!(ics.contains("\r\nATTENDEE;ROLE=REQ-PARTICIPANT;RSVP=TRUE:mailto:seeker@example.com\r\n"));
+        assert!(ics.contains("\r\nSTATUS:CONFIRMED\r\n"));
+    }
+
+    #[test]
+    fn to_ics_renders_cancellation_without_location() {
+        let ics = event("Interview", None).to_ics(IcsMethod::Cancel);
+
+        assert!(ics.contains("\r\nMETHOD:CANCEL\r\n"));
+        assert!(ics.contains("\r\nSTATUS:CANCELLED\r\n"));
+        assert!(!ics.contains("LOCATION:"));
+    }
+
+    #[test]
+    fn to_ics_folds_long_lines() {
+        let summary = "A very long interview summary ".repeat(5);
+        let ics = event(&summary, None).to_ics(IcsMethod::Request);
+
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= 75, "line too long: {:?}", line);
+        }
+        assert!(ics.replace("\r\n ", "").contains(&format!("SUMMARY:{}", summary)));
+    }
+}
@@ -1,5 +1,8 @@
 pub mod validation;
 pub mod response;
+pub mod time;
+pub mod ics;
+pub mod search;
 
 pub use validation::*;
-pub use response::*;
\ No newline at end of file
+pub use response::*;
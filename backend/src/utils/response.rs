@@ -72,6 +72,13 @@ impl ApiError {
         }
     }
 
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        ApiError {
+            status: Status::Forbidden,
+            message: message.into(),
+        }
+    }
+
     pub fn not_found(message: impl Into<String>) -> Self {
         ApiError {
             status: Status::NotFound,
@@ -131,6 +138,7 @@ impl OpenApiResponderInner for ApiError {
         for (code, description) in [
             ("400", "Bad request"),
             ("401", "Unauthorized"),
+            ("403", "Forbidden"),
             ("404", "Not found"),
             ("429", "Too many requests"),
             ("500", "Internal server error"),
@@ -148,3 +156,43 @@ impl OpenApiResponderInner for ApiError {
         Ok(responses)
     }
 }
+
+/// -----------------------------
+/// PDF download
+/// -----------------------------
+pub struct PdfFile {
+    pub filename: String,
+    pub bytes: Vec<u8>,
+}
+
+impl<'r> Responder<'r, 'static> for PdfFile {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        Response::build()
+            .header(rocket::http::ContentType::PDF)
+            .raw_header(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", self.filename),
+            )
+            .sized_body(self.bytes.len(), Cursor::new(self.bytes))
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for PdfFile {
+    fn responses(_generator: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut content = Map::new();
+        content.insert("application/pdf".to_owned(), MediaType::default());
+
+        let mut responses = Responses::default();
+        responses.responses.insert(
+            "200".to_string(),
+            rocket_okapi::okapi::openapi3::RefOr::Object(OpenApiResponse {
+                description: "PDF document".to_string(),
+                content,
+                ..Default::default()
+            }),
+        );
+
+        Ok(responses)
+    }
+}
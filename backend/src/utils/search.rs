@@ -0,0 +1,225 @@
+use mongodb::bson::{doc, Bson, Document};
+
+// Words of a query used; the rest are ignored
+const MAX_TERMS: usize = 10;
+// Words shown either side of the first match in a highlight
+const HIGHLIGHT_CONTEXT_WORDS: usize = 8;
+// Values listed per facet
+const MAX_FACET_VALUES: i32 = 20;
+
+/// Lowercase search words from user input. Punctuation is dropped, which also
+/// strips `$text` operators such as quotes and leading `-`.
+pub fn terms(q: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in q
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        if !terms.iter().any(|t| t == word) {
+            terms.push(word.to_string());
+        }
+        if terms.len() == MAX_TERMS {
+            break;
+        }
+    }
+    terms
+}
+
+/// Relevance-scored match over the collection's text index (see `db::ensure_indexes`)
+pub fn text_filter(terms: &[String]) -> Document {
+    doc! { "$text": { "$search": terms.join(" ") } }
+}
+
+/// Start of a term that still has to match. Dropping the last letters of longer
+/// words tolerates typos at the end, e.g. "plumbr" finds "plumber".
+fn prefix(term: &str) -> String {
+    let len = term.chars().count();
+    let keep = if len <= 3 { len } else { (len * 2 / 3).max(3) };
+    term.chars().take(keep).collect()
+}
+
+/// Fallback when text search finds nothing: every term must start a word in one
+/// of `fields`
+pub fn fuzzy_filter(terms: &[String], fields: &[&str]) -> Document {
+    let conditions: Vec<Document> = terms
+        .iter()
+        .map(|term| {
+            let pattern = format!(r"\b{}", regex::escape(&prefix(term)));
+            let any_field: Vec<Document> = fields
+                .iter()
+                .map(|field| {
+                    let mut condition = Document::new();
+                    condition.insert(*field, doc! { "$regex": &pattern, "$options": "i" });
+                    condition
+                })
+                .collect();
+            doc! { "$or": any_field }
+        })
+        .collect();
+    doc! { "$and": conditions }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Snippet of `value` around its first match, with matching words wrapped in
+/// `<em>`. None if no word matches.
+pub fn highlight(value: &str, terms: &[String]) -> Option<String> {
+    let prefixes: Vec<String> = terms.iter().map(|t| prefix(t)).collect();
+    let matches = |word: &str| {
+        let word: String = word.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect();
+        !word.is_empty() && prefixes.iter().any(|p| word.starts_with(p.as_str()))
+    };
+
+    let words: Vec<&str> = value.split_whitespace().collect();
+    let first = words.iter().position(|w| matches(w))?;
+    let start = first.saturating_sub(HIGHLIGHT_CONTEXT_WORDS);
+    let end = (first + HIGHLIGHT_CONTEXT_WORDS * 2).min(words.len());
+
+    let snippet = words[start..end]
+        .iter()
+        .map(|w| {
+            if matches(w) {
+                format!("<em>{}</em>", escape_html(w))
+            } else {
+                escape_html(w)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(format!(
+        "{}{}{}",
+        if start > 0 { "… " } else { "" },
+        snippet,
+        if end < words.len() { " …" } else { "" }
+    ))
+}
+
+/// Highlights for the fields of one result that match, keyed by field name
+pub fn highlights(fields: &[(&str, &str)], terms: &[String]) -> serde_json::Value {
+    let mut found = serde_json::Map::new();
+    for (name, value) in fields {
+        if let Some(snippet) = highlight(value, terms) {
+            found.insert(name.to_string(), serde_json::json!(snippet));
+        }
+    }
+    serde_json::Value::Object(found)
+}
+
+/// `$facet` sub-pipeline counting documents per value of `field`, most common
+/// first. Array fields are unwound so each element counts.
+pub fn facet(field: &str, is_array: bool) -> Vec<Document> {
+    let mut stages = Vec::new();
+    if is_array {
+        stages.push(doc! { "$unwind": format!("${}", field) });
+    }
+    stages.push(doc! { "$group": { "_id": format!("${}", field), "count": { "$sum": 1 } } });
+    stages.push(doc! { "$match": { "_id": { "$ne": null } } });
+    stages.push(doc! { "$sort": { "count": -1, "_id": 1 } });
+    stages.push(doc! { "$limit": MAX_FACET_VALUES });
+    stages
+}
+
+/// `[{ "value", "count" }]` from a facet's `{ _id, count }` documents
+pub fn facet_counts(buckets: Option<&Bson>) -> serde_json::Value {
+    let counts: Vec<serde_json::Value> = buckets
+        .and_then(Bson::as_array)
+        .map(|buckets| {
+            buckets
+                .iter()
+                .filter_map(Bson::as_document)
+                .map(|bucket| {
+                    serde_json::json!({
+                        "value": bucket.get("_id").cloned().map(Bson::into_relaxed_extjson),
+                        "count": bucket.get_i32("count").unwrap_or_default()
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    serde_json::Value::Array(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owned(terms: &[&str]) -> Vec<String> {
+        terms.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn terms_lowercases_dedupes_and_drops_punctuation() {
+        assert_eq!(terms("Plumber, PLUMBER  pune!"), owned(&["plumber", "pune"]));
+        assert_eq!(terms(r#""exact phrase" -excluded"#), owned(&["exact", "phrase", "excluded"]));
+        assert!(terms("  ?! ").is_empty());
+    }
+
+    #[test]
+    fn terms_caps_the_number_of_words() {
+        let q = (0..15).map(|i| format!("w{}", i)).collect::<Vec<_>>().join(" ");
+        assert_eq!(terms(&q).len(), MAX_TERMS);
+    }
+
+    #[test]
+    fn prefix_trims_longer_words() {
+        assert_eq!(prefix("ac"), "ac");
+        assert_eq!(prefix("tap"), "tap");
+        assert_eq!(prefix("wire"), "wir");
+        assert_eq!(prefix("plumbr"), "plum");
+        assert_eq!(prefix("electrician"), "electri");
+        assert_eq!(prefix("सफाईवाला"), "सफाईव");
+    }
+
+    #[test]
+    fn fuzzy_filter_escapes_regex_input() {
+        let filter = fuzzy_filter(&owned(&["c++"]), &["skills"]);
+        let condition = filter.get_array("$and").unwrap()[0].as_document().unwrap();
+        let field = condition.get_array("$or").unwrap()[0].as_document().unwrap();
+        let pattern = field.get_document("skills").unwrap().get_str("$regex").unwrap();
+
+        assert_eq!(pattern, r"\bc\+\+");
+        assert!(regex::Regex::new(pattern).unwrap().is_match("knows c++ well"));
+    }
+
+    #[test]
+    fn fuzzy_filter_requires_every_term_in_any_field() {
+        let filter = fuzzy_filter(&owned(&["plumber", "pune"]), &["bio", "service_areas"]);
+        let conditions = filter.get_array("$and").unwrap();
+
+        assert_eq!(conditions.len(), 2);
+        for condition in conditions {
+            assert_eq!(condition.as_document().unwrap().get_array("$or").unwrap().len(), 2);
+        }
+    }
+
+    #[test]
+    fn highlight_wraps_matching_words() {
+        let snippet = highlight("Experienced plumber in Pune", &owned(&["plumbr"]));
+        assert_eq!(snippet.as_deref(), Some("Experienced <em>plumber</em> in Pune"));
+    }
+
+    #[test]
+    fn highlight_returns_none_without_a_match() {
+        assert_eq!(highlight("Experienced electrician", &owned(&["plumber"])), None);
+    }
+
+    #[test]
+    fn highlight_escapes_html_and_special_characters() {
+        let snippet = highlight("Fixes <pipes> & c++ (taps)", &owned(&["c", "taps"])).unwrap();
+        assert_eq!(snippet, "Fixes &lt;pipes&gt; &amp; <em>c++</em> <em>(taps)</em>");
+    }
+
+    #[test]
+    fn highlight_trims_long_values_around_the_first_match() {
+        let value = format!("{} plumber {}", "before ".repeat(20).trim(), "after ".repeat(20).trim());
+        let snippet = highlight(&value, &owned(&["plumber"])).unwrap();
+
+        assert!(snippet.starts_with("… before"));
+        assert!(snippet.ends_with("after …"));
+        assert!(snippet.contains("<em>plumber</em>"));
+        assert_eq!(snippet.split_whitespace().count(), HIGHLIGHT_CONTEXT_WORDS * 3 + 2);
+    }
+}
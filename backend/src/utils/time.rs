@@ -0,0 +1,52 @@
+use chrono::{FixedOffset, TimeZone};
+use mongodb::bson::DateTime;
+
+pub const MINUTE_MILLIS: i64 = 60 * 1000;
+pub const HOUR_MILLIS: i64 = 60 * MINUTE_MILLIS;
+pub const DAY_MILLIS: i64 = 24 * HOUR_MILLIS;
+
+/// Offset used for times shown to users and for local calendar days (IST, UTC+05:30)
+pub fn local_offset() -> FixedOffset {
+    FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap()
+}
+
+/// Human-readable date for notification and email text, e.g. "05 Mar 2025"
+pub fn format_date(date: DateTime) -> String {
+    chrono::Utc
+        .timestamp_millis_opt(date.timestamp_millis())
+        .single()
+        .map(|d| d.format("%d %b %Y").to_string())
+        .unwrap_or_default()
+}
+
+/// Local date and time for notification text, e.g. "05 Mar 2025, 10:30 AM"
+pub fn format_datetime(date: DateTime) -> String {
+    local_offset()
+        .timestamp_millis_opt(date.timestamp_millis())
+        .single()
+        .map(|d| d.format("%d %b %Y, %I:%M %p").to_string())
+        .unwrap_or_default()
+}
+
+/// Parse an RFC 3339 timestamp from a request, e.g. "2025-03-14T10:30:00+05:30"
+pub fn parse_rfc3339(value: &str) -> Option<DateTime> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|d| DateTime::from_millis(d.timestamp_millis()))
+}
+
+/// `date` shifted by `millis` (may be negative)
+pub fn add_millis(date: DateTime, millis: i64) -> DateTime {
+    DateTime::from_millis(date.timestamp_millis() + millis)
+}
+
+/// `date` in local time
+pub fn to_local(date: DateTime) -> chrono::DateTime<FixedOffset> {
+    local_offset().timestamp_millis_opt(date.timestamp_millis()).unwrap()
+}
+
+/// Midnight (local time) at the start of a calendar day
+pub fn local_day_start(day: chrono::NaiveDate) -> DateTime {
+    let utc_millis = day.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis();
+    DateTime::from_millis(utc_millis - local_offset().local_minus_utc() as i64 * 1000)
+}
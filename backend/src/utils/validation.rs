@@ -20,4 +20,21 @@ pub fn generate_otp() -> String {
     let mut rng = rand::thread_rng();
     let otp: u32 = rng.gen_range(100000..999999);
     otp.to_string()
-}
\ No newline at end of file
+}
+
+/// Whether `url` points at a file the upload endpoints stored under `uploads/<dir>`,
+/// as a relative path or prefixed with APP_BASE_URL
+pub fn is_uploaded_file(url: &str, dir: &str) -> bool {
+    let base = std::env::var("APP_BASE_URL").unwrap_or_default();
+    let path = url
+        .strip_prefix(base.trim_end_matches('/'))
+        .filter(|_| !base.is_empty())
+        .unwrap_or(url);
+
+    path.strip_prefix(&format!("/uploads/{}/", dir))
+        .is_some_and(|name| {
+            !name.is_empty()
+                && !name.contains("..")
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+}